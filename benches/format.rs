@@ -0,0 +1,39 @@
+//! Format parsing and version extraction over large synthetic version sets:
+//! the work `oneup version` does per registry version.
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use oneup::format::VersionFormat;
+use oneup::resolve::max_micro_today;
+
+/// `count` versions spread over the past periods of `fmt`, ending with a
+/// run of today's, the way a high-frequency publisher's packument looks.
+fn versions(fmt: &VersionFormat, count: u64) -> Vec<String> {
+    let mut versions: Vec<String> = (0..count)
+        .map(|i| format!("{}.{}.{}", 20 + i % 6, 1 + i % 12, i / 72))
+        .collect();
+    versions.extend((0..count / 100).map(|micro| fmt.build_version(micro)));
+    versions
+}
+
+fn parse(c: &mut Criterion) {
+    c.bench_function("parse YYYY.MM.DD.MICRO+SHA", |b| {
+        b.iter(|| VersionFormat::parse(black_box("YYYY.MM.DD.MICRO+SHA")).unwrap())
+    });
+}
+
+fn extract(c: &mut Criterion) {
+    let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
+    let mut group = c.benchmark_group("highest MICRO today");
+    for count in [1_000, 10_000, 100_000] {
+        let versions = versions(&fmt, count);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &versions,
+            |b, versions| b.iter(|| max_micro_today(&fmt, versions)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, extract);
+criterion_main!(benches);
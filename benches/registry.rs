@@ -0,0 +1,98 @@
+//! End-to-end `oneup version --dry-run` against a mock npm registry serving
+//! a synthetic packument, and against the same packument as a fixture file.
+//! Covers the HTTP client, packument streaming and the version scan.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+
+/// A packument with `count` versions, each with a small manifest and a
+/// publish time, like the npm registry sends.
+fn packument(count: usize) -> String {
+    let versions: Vec<String> = (0..count)
+        .map(|i| format!("{}.{}.{}", 20 + i % 6, 1 + i % 12, i / 72))
+        .collect();
+    let manifests: Vec<String> = versions
+        .iter()
+        .map(|v| format!(r#""{v}": {{"name": "bench-pkg", "version": "{v}", "dist": {{"tarball": "https://example.com/bench-pkg-{v}.tgz"}}}}"#))
+        .collect();
+    let times: Vec<String> = versions
+        .iter()
+        .map(|v| format!(r#""{v}": "2026-01-01T00:00:00.000Z""#))
+        .collect();
+    format!(
+        r#"{{"name": "bench-pkg", "dist-tags": {{"latest": "{}"}}, "versions": {{{}}}, "time": {{{}}}}}"#,
+        versions[count - 1],
+        manifests.join(","),
+        times.join(",")
+    )
+}
+
+/// Serve `body` to every request on a local port; returns the registry URL.
+fn mock_registry(body: String) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            // Skip the request line and headers
+            while reader.read_line(&mut line).is_ok_and(|n| n > 0) && line != "\r\n" {
+                line.clear();
+            }
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+    url
+}
+
+fn oneup(dir: &Path, registry: &str) {
+    let status = Command::new(env!("CARGO_BIN_EXE_oneup"))
+        .current_dir(dir)
+        .args(["version", "--dry-run", "--registry", registry])
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn version(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("package.json"),
+        r#"{"name": "bench-pkg", "version": "0.0.0"}"#,
+    )
+    .unwrap();
+
+    let mut group = c.benchmark_group("oneup version");
+    group.sample_size(10);
+    for count in [1_000, 20_000] {
+        let body = packument(count);
+        let fixture = dir.path().join(format!("packument-{count}.json"));
+        std::fs::write(&fixture, &body).unwrap();
+        let fixture = format!("file://{}", fixture.display());
+        let registry = mock_registry(body);
+
+        group.bench_with_input(BenchmarkId::new("registry", count), &registry, |b, url| {
+            b.iter(|| oneup(dir.path(), url))
+        });
+        group.bench_with_input(BenchmarkId::new("fixture", count), &fixture, |b, url| {
+            b.iter(|| oneup(dir.path(), url))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, version);
+criterion_main!(benches);
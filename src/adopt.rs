@@ -0,0 +1,222 @@
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use std::cmp::Ordering;
+use std::path::Path;
+
+use crate::backup;
+use crate::cli::AdoptArgs;
+use crate::config::{Config, DEFAULT_CLOCK_SKEW_HOURS};
+use crate::format::VersionFormat;
+use crate::registry::PackageInfo;
+use crate::resolve::compare_versions;
+use crate::target;
+use crate::timing::Timings;
+use crate::version::{determine_version, load_targets, query_registry};
+
+/// CalVer formats tried in order of preference when adopting
+const CANDIDATE_FORMATS: &[&str] = &["YY.MM.MICRO", "YYYY.MM.MICRO"];
+
+/// Suggested CalVer format and first version for a package
+#[derive(Debug)]
+pub struct Proposal {
+    pub format: &'static str,
+    pub version: String,
+    /// Highest version currently in the registry, if any
+    pub previous: Option<String>,
+    /// Date the version was built from
+    pub today: NaiveDate,
+}
+
+pub fn run(args: AdoptArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let targets = load_targets(&args.target, config.primary_target(), &config, None)?;
+    let (primary_path, primary_target) = &targets[0];
+
+    if args.verbose {
+        eprintln!("[target] primary: {}", primary_path.display());
+        eprintln!("[target] package: {}", primary_target.package_name);
+    }
+
+    let info = query_registry(
+        primary_path,
+        primary_target,
+        args.registry.as_deref(),
+        args.registry_type,
+        &config,
+        args.verbose,
+        &mut Timings::new(false),
+    )?;
+
+    let proposal = propose(&info)?;
+
+    eprintln!("package: {}", primary_target.package_name);
+    match &proposal.previous {
+        Some(previous) => eprintln!("highest published version: {}", previous),
+        None => eprintln!("highest published version: none"),
+    }
+    eprintln!("proposed format: {}", proposal.format);
+    eprintln!("initial version: {}", proposal.version);
+    eprintln!();
+    for note in range_notes(&proposal, primary_target.is_cargo()) {
+        eprintln!("note: {}", note);
+    }
+    eprintln!(
+        "note: run future releases with `oneup version --format {}`",
+        proposal.format
+    );
+
+    if args.dry_run {
+        for (path, _) in &targets {
+            eprintln!("[dry-run] would write {}", path.display());
+        }
+    } else {
+        let writes: Vec<_> = targets
+            .iter()
+            .map(|(path, target)| (path.as_path(), target, proposal.version.as_str()))
+            .collect();
+        let rendered = target::render_all(&writes, Some(proposal.today))?;
+        let paths: Vec<&Path> = rendered.iter().map(|file| file.path).collect();
+        let backups = backup::project_dir(primary_path);
+        if let Some(snapshot) = backup::snapshot(&backups, &paths, config.keep_backups)?
+            && args.verbose
+        {
+            eprintln!("[backup] {}", snapshot.display());
+        }
+        target::write_all(&rendered)?;
+        if args.verbose {
+            for (path, _) in &targets {
+                eprintln!("[file] updated {}", path.display());
+            }
+        }
+    }
+
+    println!("{}", proposal.version);
+
+    Ok(())
+}
+
+/// Pick the first candidate format whose next version sorts above every
+/// published version, so npm/cargo never consider the CalVer release older.
+pub fn propose(info: &PackageInfo) -> Result<Proposal> {
+    let previous = match info {
+        PackageInfo::NotFound => None,
+        PackageInfo::Found { versions, .. } => versions
+            .iter()
+            .max_by(|a, b| compare_versions(a, b))
+            .cloned(),
+    };
+
+    // One date for every candidate, and for the files written with it
+    let today = chrono::Local::now().date_naive();
+    for format in CANDIDATE_FORMATS {
+        let fmt = VersionFormat::parse(format)?.with_today(today);
+        let version = determine_version(info, "", &fmt, &[], DEFAULT_CLOCK_SKEW_HOURS, false)?;
+
+        let above_history = previous
+            .as_deref()
+            .is_none_or(|p| compare_versions(&version, p) == Ordering::Greater);
+
+        if above_history {
+            return Ok(Proposal {
+                format,
+                version,
+                previous,
+                today,
+            });
+        }
+    }
+
+    bail!(
+        "published version {} sorts above every supported CalVer format ({}); \
+         a CalVer release would never be picked as the latest",
+        previous.unwrap_or_default(),
+        CANDIDATE_FORMATS.join(", ")
+    )
+}
+
+/// Explain how the switch affects consumers' version ranges.
+fn range_notes(proposal: &Proposal, cargo: bool) -> Vec<String> {
+    let mut notes = Vec::new();
+    let manifest = if cargo { "Cargo.toml" } else { "package.json" };
+
+    let major = proposal.version.split('.').next().unwrap_or_default();
+
+    if let Some(previous) = &proposal.previous
+        && previous.split('.').next() != Some(major)
+    {
+        notes.push(format!(
+            "dependents using ^{} will not receive {}: caret ranges never cross a major version, \
+             so they must update the range in their {}",
+            previous, proposal.version, manifest
+        ));
+    }
+
+    notes.push(format!(
+        "the year is the major version, so ^{} only matches releases from {}; \
+         consumers who want every release should use >={}",
+        proposal.version, major, proposal.version
+    ));
+
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+
+    fn found(versions: &[&str]) -> PackageInfo {
+        PackageInfo::Found {
+            versions: versions.iter().map(|v| v.to_string()).collect(),
+            latest: versions.last().unwrap().to_string(),
+            published: Default::default(),
+        }
+    }
+
+    #[test]
+    fn propose_new_package_uses_default_format() {
+        let p = propose(&PackageInfo::NotFound).unwrap();
+        assert_eq!(p.format, "YY.MM.MICRO");
+        assert!(p.previous.is_none());
+        assert!(p.version.ends_with(".0"));
+        // Built from the date the proposal carries to the files it writes
+        let month = format!("{}.{}.", p.today.year() % 100, p.today.month());
+        assert!(p.version.starts_with(&month));
+    }
+
+    #[test]
+    fn propose_semver_history_below_short_year() {
+        let p = propose(&found(&["1.0.0", "3.4.1", "2.9.0"])).unwrap();
+        assert_eq!(p.format, "YY.MM.MICRO");
+        assert_eq!(p.previous.as_deref(), Some("3.4.1"));
+    }
+
+    #[test]
+    fn propose_full_year_when_major_exceeds_short_year() {
+        let yy = chrono::Local::now().year() % 100;
+        let high = format!("{}.0.0", yy + 10);
+        let p = propose(&found(&["1.0.0", &high])).unwrap();
+        assert_eq!(p.format, "YYYY.MM.MICRO");
+    }
+
+    #[test]
+    fn propose_fails_when_major_exceeds_full_year() {
+        assert!(propose(&found(&["99999.0.0"])).is_err());
+    }
+
+    #[test]
+    fn range_notes_skip_caret_warning_within_same_major() {
+        let current = propose(&PackageInfo::NotFound).unwrap().version;
+        let p = propose(&found(&[&current])).unwrap();
+        let notes = range_notes(&p, false);
+        assert_eq!(notes.len(), 1);
+    }
+
+    #[test]
+    fn range_notes_mention_previous() {
+        let p = propose(&found(&["3.4.1"])).unwrap();
+        let notes = range_notes(&p, false);
+        assert!(notes[0].contains("^3.4.1"));
+        assert!(notes[0].contains("package.json"));
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::cli::AnnounceArgs;
+use crate::config::{CONFIG_FILE, Config, with_package};
+use crate::error::{ErrorKind, ResultExt};
+use crate::feed::{repository_url, tag_url};
+use crate::http;
+use crate::version::load_targets;
+
+const DEFAULT_TEMPLATE: &str = "{package} {version} is out! {link}";
+
+pub fn run(args: AnnounceArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    // Posting publicly is never a default: it takes the config table
+    let Some(mastodon) = &config.mastodon else {
+        return Err(anyhow!(
+            "announcements are off: add [announce.mastodon] with an instance to {}",
+            CONFIG_FILE
+        ))
+        .kind(ErrorKind::Config);
+    };
+
+    let primary = args.primary.as_deref().or(config.primary_target());
+    let targets = load_targets(&args.target, primary, &config, None)?;
+    let target = &targets[0].1;
+    let package = target.package_name.clone();
+    let version = args
+        .version
+        .clone()
+        .unwrap_or_else(|| target.version.clone());
+    let tag = format!("v{version}");
+    let link = args
+        .link
+        .clone()
+        .or_else(|| repository_url().map(|url| tag_url(&url, &tag)))
+        .unwrap_or_default();
+    let template = mastodon.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+    let status = render(template, &package, &version, &tag, &link);
+    if args.verbose {
+        eprintln!("[mastodon] instance: {}", mastodon.instance);
+    }
+
+    if args.dry_run {
+        eprintln!("[dry-run] would post to {}:", mastodon.instance);
+        for line in status.lines() {
+            eprintln!("[dry-run]   {}", line);
+        }
+    } else {
+        let token = std::env::var("MASTODON_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty())
+            .context(
+                "posting to Mastodon needs MASTODON_TOKEN (an access token with write:statuses)",
+            )
+            .kind(ErrorKind::Auth)?;
+        let url = post_status(
+            &mastodon.instance,
+            &token,
+            &status,
+            mastodon.visibility.as_deref(),
+            // Retries of the same release post once
+            &format!("oneup-{package}-{version}"),
+        )?;
+        eprintln!("[mastodon] posted {}", url);
+    }
+
+    println!("{}", version);
+    Ok(())
+}
+
+/// The status text: `template` with {package}, {version}, {tag} and {link}
+/// replaced (`${VAR}` and {branch} are expanded when oneup.toml loads), trimmed of the space a missing link leaves behind.
+fn render(template: &str, package: &str, version: &str, tag: &str, link: &str) -> String {
+    with_package(template, package)
+        .replace("{version}", version)
+        .replace("{tag}", tag)
+        .replace("{link}", link)
+        .trim()
+        .to_string()
+}
+
+/// POST the status; returns its URL.
+fn post_status(
+    instance: &str,
+    token: &str,
+    status: &str,
+    visibility: Option<&str>,
+    idempotency_key: &str,
+) -> Result<String> {
+    let mut body = json!({ "status": status });
+    if let Some(visibility) = visibility {
+        body["visibility"] = json!(visibility);
+    }
+    let resp = http::client()
+        .post(format!("{instance}/api/v1/statuses"))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Idempotency-Key", idempotency_key)
+        .json(&body)
+        .send()
+        .with_context(|| format!("failed to post to {}", instance))
+        .kind(ErrorKind::Network)?;
+
+    let status_code = resp.status();
+    let answer: Value = resp.json().unwrap_or(Value::Null);
+    if !status_code.is_success() {
+        let kind = match status_code {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => ErrorKind::Auth,
+            // Too long, or a visibility the server refuses
+            reqwest::StatusCode::UNPROCESSABLE_ENTITY => ErrorKind::Config,
+            _ => ErrorKind::Network,
+        };
+        return Err(anyhow!(
+            "{} refused the post: HTTP {} {}",
+            instance,
+            status_code,
+            answer
+                .get("error")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        ))
+        .kind(kind);
+    }
+    Ok(answer
+        .get("url")
+        .and_then(Value::as_str)
+        .unwrap_or(instance)
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        assert_eq!(
+            render(
+                DEFAULT_TEMPLATE,
+                "@circlesac/oneup",
+                "26.10.3",
+                "v26.10.3",
+                "https://github.com/circlesac/oneup/releases/tag/v26.10.3"
+            ),
+            "@circlesac/oneup 26.10.3 is out! https://github.com/circlesac/oneup/releases/tag/v26.10.3"
+        );
+        assert_eq!(
+            render(DEFAULT_TEMPLATE, "app", "26.10.3", "v26.10.3", ""),
+            "app 26.10.3 is out!"
+        );
+        assert_eq!(
+            render("Tagged {tag}\n#release", "app", "26.10.3", "v26.10.3", ""),
+            "Tagged v26.10.3\n#release"
+        );
+    }
+}
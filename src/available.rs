@@ -0,0 +1,106 @@
+use anyhow::{Result, anyhow};
+use serde_json::json;
+use std::path::Path;
+
+use crate::cli::AvailableArgs;
+use crate::config::Config;
+use crate::error::{ErrorKind, ResultExt};
+use crate::format::VersionFormat;
+use crate::registry::PackageInfo;
+use crate::timing::Timings;
+use crate::version::{determine_version, load_targets, query_registry};
+
+pub fn run(args: AvailableArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let targets = load_targets(
+        &args.target,
+        config.primary_target(),
+        &config,
+        args.package_name.as_deref(),
+    )?;
+    let (primary_path, primary_target) = &targets[0];
+    let package = &primary_target.package_name;
+
+    let info = query_registry(
+        primary_path,
+        primary_target,
+        args.registry.as_deref(),
+        args.registry_type,
+        &config,
+        args.verbose,
+        &mut Timings::new(false),
+    )?;
+
+    // Check the given version, or the one `oneup version` would compute
+    let version = match &args.version {
+        Some(v) => v.clone(),
+        None => {
+            let fmt = VersionFormat::parse(&args.format)?;
+            determine_version(
+                &info,
+                package,
+                &fmt,
+                &config.skip,
+                config.clock_skew_hours,
+                args.verbose,
+            )?
+        }
+    };
+
+    let package_exists = matches!(info, PackageInfo::Found { .. });
+    let available = is_available(&info, &version);
+
+    if args.json {
+        let report = json!({
+            "package": package,
+            "package_exists": package_exists,
+            "version": version,
+            "available": available,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        if !package_exists {
+            eprintln!("package {} is not in the registry (name is free)", package);
+        }
+        // A taken version is reported as the error below
+        if available {
+            eprintln!("{} {} is available", package, version);
+        }
+        println!("{}", if available { "available" } else { "taken" });
+    }
+
+    if !available {
+        return Err(anyhow!("{} {} is already published", package, version)).kind(ErrorKind::Check);
+    }
+
+    Ok(())
+}
+
+/// Whether `version` has not been published yet.
+fn is_available(info: &PackageInfo, version: &str) -> bool {
+    match info {
+        PackageInfo::NotFound => true,
+        PackageInfo::Found { versions, .. } => !versions.iter().any(|v| v == version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn available_when_package_missing() {
+        assert!(is_available(&PackageInfo::NotFound, "26.2.0"));
+    }
+
+    #[test]
+    fn available_checks_published_versions() {
+        let info = PackageInfo::Found {
+            versions: vec!["26.2.0".to_string(), "26.2.1".to_string()],
+            latest: "26.2.1".to_string(),
+            published: Default::default(),
+        };
+        assert!(!is_available(&info, "26.2.1"));
+        assert!(is_available(&info, "26.2.2"));
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::cli::RestoreArgs;
+use crate::git;
+use crate::target::write_atomic;
+
+/// Snapshots live under <project>/.oneup/backup/<timestamp>/
+const BACKUP_DIR: &str = ".oneup/backup";
+const MANIFEST: &str = "manifest.json";
+
+/// Snapshots kept when oneup.toml does not set `keep-backups`
+pub const DEFAULT_KEEP_BACKUPS: u64 = 10;
+
+/// One backed-up file: where it came from and its copy inside the snapshot
+/// (None when the file did not exist yet and restoring removes it)
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Entry {
+    path: PathBuf,
+    file: Option<String>,
+}
+
+/// Project whose `.oneup/` holds the snapshots of the primary target
+/// `primary`: the git repository containing it, else its directory.
+/// Anchoring to the repository keeps `oneup restore` working from any
+/// subdirectory.
+pub fn project_dir(primary: &Path) -> PathBuf {
+    let dir = primary
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    repo_or(dir)
+}
+
+fn repo_or(dir: &Path) -> PathBuf {
+    git::repo_root(dir).unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// Copy `paths` into a new snapshot under `dir` before they are overwritten,
+/// then drop all but the newest `keep` snapshots. Returns the snapshot
+/// directory, or None when `keep` is 0 and backups are off.
+pub fn snapshot(dir: &Path, paths: &[&Path], keep: u64) -> Result<Option<PathBuf>> {
+    if keep == 0 {
+        return Ok(None);
+    }
+
+    let root = dir.join(BACKUP_DIR);
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let snapshot = root.join(stamp);
+    std::fs::create_dir_all(&snapshot)
+        .with_context(|| format!("failed to create {}", snapshot.display()))?;
+
+    // Keep snapshots out of git without touching the project's .gitignore
+    let ignore = dir.join(".oneup/.gitignore");
+    if !ignore.exists() {
+        std::fs::write(&ignore, "*\n")
+            .with_context(|| format!("failed to write {}", ignore.display()))?;
+        eprintln!(
+            "created {} to keep backups out of git (keep-backups = 0 in oneup.toml turns backups off)",
+            ignore.display()
+        );
+    }
+
+    let mut entries = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let absolute = std::path::absolute(path)
+            .with_context(|| format!("failed to resolve {}", path.display()))?;
+        let file = if path.exists() {
+            let file = i.to_string();
+            std::fs::copy(path, snapshot.join(&file))
+                .with_context(|| format!("failed to back up {}", path.display()))?;
+            Some(file)
+        } else {
+            None
+        };
+        entries.push(Entry {
+            path: absolute,
+            file,
+        });
+    }
+
+    let manifest = serde_json::to_string_pretty(&entries)?;
+    std::fs::write(snapshot.join(MANIFEST), manifest + "\n")
+        .with_context(|| format!("failed to write {}", snapshot.display()))?;
+
+    prune(dir, keep)?;
+    Ok(Some(snapshot))
+}
+
+/// Remove the oldest snapshots until at most `keep` are left.
+fn prune(dir: &Path, keep: u64) -> Result<()> {
+    let snapshots = list(dir)?;
+    let excess = snapshots.len().saturating_sub(keep as usize);
+    for name in &snapshots[..excess] {
+        let path = dir.join(BACKUP_DIR).join(name);
+        std::fs::remove_dir_all(&path)
+            .with_context(|| format!("failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub fn run(args: RestoreArgs) -> Result<()> {
+    let dir = &repo_or(Path::new("."));
+    let snapshots = list(dir)?;
+
+    if args.list {
+        for name in &snapshots {
+            eprintln!("{}", name);
+            for entry in read_manifest(&dir.join(BACKUP_DIR).join(name))? {
+                eprintln!("  {}", entry.path.display());
+            }
+        }
+        return Ok(());
+    }
+
+    let name = match &args.snapshot {
+        Some(name) => {
+            if !snapshots.contains(name) {
+                bail!("no backup snapshot named {}", name);
+            }
+            name
+        }
+        None => snapshots.last().context("no backup snapshots to restore")?,
+    };
+    let snapshot = dir.join(BACKUP_DIR).join(name);
+
+    for entry in read_manifest(&snapshot)? {
+        if args.dry_run {
+            eprintln!("[dry-run] would restore {}", entry.path.display());
+            continue;
+        }
+        match &entry.file {
+            Some(file) => {
+                let content = std::fs::read(snapshot.join(file)).with_context(|| {
+                    format!("backup copy of {} is missing", entry.path.display())
+                })?;
+                write_atomic(&entry.path, &content)?;
+                eprintln!("restored {}", entry.path.display());
+            }
+            None => {
+                if entry.path.exists() {
+                    std::fs::remove_file(&entry.path)
+                        .with_context(|| format!("failed to remove {}", entry.path.display()))?;
+                }
+                eprintln!("removed {} (did not exist before)", entry.path.display());
+            }
+        }
+    }
+
+    println!("{}", name);
+    Ok(())
+}
+
+/// Snapshot names under `dir`, oldest first.
+fn list(dir: &Path) -> Result<Vec<String>> {
+    let root = dir.join(BACKUP_DIR);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in
+        std::fs::read_dir(&root).with_context(|| format!("failed to read {}", root.display()))?
+    {
+        let entry = entry?;
+        if entry.path().join(MANIFEST).exists() {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    // Timestamps sort chronologically
+    names.sort();
+    Ok(names)
+}
+
+fn read_manifest(snapshot: &Path) -> Result<Vec<Entry>> {
+    let path = snapshot.join(MANIFEST);
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("invalid {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_copies_files_and_lists_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("package.json");
+        std::fs::write(&file, "original").unwrap();
+        let generated = dir.path().join("version.rs");
+
+        let snapshot = snapshot(dir.path(), &[file.as_path(), generated.as_path()], 10)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(snapshot.join("0")).unwrap(),
+            "original"
+        );
+        assert_eq!(
+            read_manifest(&snapshot).unwrap(),
+            vec![
+                Entry {
+                    path: file.clone(),
+                    file: Some("0".to_string())
+                },
+                Entry {
+                    path: generated,
+                    file: None
+                }
+            ]
+        );
+        assert_eq!(list(dir.path()).unwrap().len(), 1);
+        assert!(dir.path().join(".oneup/.gitignore").exists());
+    }
+
+    #[test]
+    fn snapshot_keeps_the_newest_and_can_be_turned_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("package.json");
+        std::fs::write(&file, "original").unwrap();
+
+        let mut taken = Vec::new();
+        for _ in 0..3 {
+            taken.push(snapshot(dir.path(), &[file.as_path()], 2).unwrap().unwrap());
+            // Snapshot names have millisecond resolution
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let names: Vec<String> = taken[1..]
+            .iter()
+            .map(|s| s.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(list(dir.path()).unwrap(), names);
+
+        assert!(
+            snapshot(dir.path(), &[file.as_path()], 0)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(list(dir.path()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn list_without_backups_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list(dir.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use std::ops::Range;
+
+use crate::scan;
+
+/// `version` of the `module(...)` call in a MODULE.bazel.
+pub fn module_version(content: &str) -> Option<&str> {
+    module_version_range(content).map(|r| &content[r])
+}
+
+/// Set the `version` argument of `module(...)`. `bazel_dep` versions and
+/// everything else in the file are left untouched.
+pub fn update(content: &str, version: &str) -> Result<String> {
+    let range = module_version_range(content)
+        .context("no module(version = \"...\") found in MODULE.bazel")?;
+    let mut output = content.to_string();
+    output.replace_range(range, version);
+    Ok(output)
+}
+
+/// Byte range of the string value of `version = "..."` inside `module(...)`.
+fn module_version_range(content: &str) -> Option<Range<usize>> {
+    let start = call_start(content, "module(")?;
+    let end = start + content[start..].find(')')?;
+    let args = &content[start..end];
+
+    for key in scan::word_starts(args, "version", &['_']) {
+        let from = key + "version".len();
+        let Some(value) = args[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let value_start = start + (args.len() - value.len()) + 1;
+        let len = value[1..].find(quote)?;
+        return Some(value_start..value_start + len);
+    }
+    None
+}
+
+/// Offset just past `name` where it starts a call at the top level of a line
+/// (not part of a longer identifier like `bazel_dep(`).
+fn call_start(content: &str, name: &str) -> Option<usize> {
+    scan::word_starts(content, name, &['_', '.'])
+        .next()
+        .map(|start| start + name.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"module(
+    name = "my_module",
+    version = "26.2.1",
+    compatibility_level = 1,
+)
+
+bazel_dep(name = "rules_rust", version = "0.40.0")
+"#;
+
+    #[test]
+    fn updates_module_version_only() {
+        assert_eq!(module_version(MODULE), Some("26.2.1"));
+        let updated = update(MODULE, "26.2.5").unwrap();
+        assert!(updated.contains("    version = \"26.2.5\",\n"));
+        assert!(updated.contains("version = \"0.40.0\")"));
+    }
+
+    #[test]
+    fn module_without_version() {
+        let content = "bazel_dep(name = \"a\", version = \"1.0\")\nmodule(name = \"x\")\n";
+        assert_eq!(module_version(content), None);
+        assert!(update(content, "26.2.5").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+
+/// CI provider detected from the environment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Provider {
+    GitHubActions,
+    GitLabCi,
+    CircleCi,
+    Buildkite,
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Provider::GitHubActions => "GitHub Actions",
+            Provider::GitLabCi => "GitLab CI",
+            Provider::CircleCi => "CircleCI",
+            Provider::Buildkite => "Buildkite",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Metadata about the CI run oneup is executing in
+#[derive(Debug, Clone)]
+pub struct CiInfo {
+    pub provider: Provider,
+    pub run_url: Option<String>,
+    pub actor: Option<String>,
+    pub pipeline_id: Option<String>,
+    /// Sequential, per-project run number (usable as MICRO)
+    pub run_number: Option<u64>,
+    /// Branch being built (CI checkouts are often a detached HEAD)
+    pub branch: Option<String>,
+    /// Pull/merge request number when building one
+    pub pull_request: Option<u64>,
+}
+
+impl CiInfo {
+    /// Detect the CI provider from the process environment.
+    /// Returns None when not running on a known CI provider.
+    pub fn detect() -> Option<Self> {
+        detect_with(|key| std::env::var(key).ok().filter(|v| !v.is_empty()))
+    }
+}
+
+fn detect_with(get: impl Fn(&str) -> Option<String>) -> Option<CiInfo> {
+    let is_set = |key: &str| get(key).is_some_and(|v| v == "true");
+
+    if is_set("GITHUB_ACTIONS") {
+        let run_url = match (
+            get("GITHUB_SERVER_URL"),
+            get("GITHUB_REPOSITORY"),
+            get("GITHUB_RUN_ID"),
+        ) {
+            (Some(server), Some(repo), Some(run)) => {
+                Some(format!("{server}/{repo}/actions/runs/{run}"))
+            }
+            _ => None,
+        };
+        return Some(CiInfo {
+            provider: Provider::GitHubActions,
+            run_url,
+            actor: get("GITHUB_ACTOR"),
+            pipeline_id: get("GITHUB_RUN_ID"),
+            run_number: number(get("GITHUB_RUN_NUMBER")),
+            // GITHUB_HEAD_REF is the source branch of a pull request
+            branch: get("GITHUB_HEAD_REF").or_else(|| {
+                get("GITHUB_REF").and_then(|r| r.strip_prefix("refs/heads/").map(String::from))
+            }),
+            // refs/pull/<number>/merge
+            pull_request: get("GITHUB_REF").and_then(|r| {
+                r.strip_prefix("refs/pull/")
+                    .and_then(|rest| rest.split('/').next())
+                    .and_then(|n| n.parse().ok())
+            }),
+        });
+    }
+
+    if is_set("GITLAB_CI") {
+        return Some(CiInfo {
+            provider: Provider::GitLabCi,
+            run_url: get("CI_PIPELINE_URL"),
+            actor: get("GITLAB_USER_LOGIN"),
+            pipeline_id: get("CI_PIPELINE_ID"),
+            run_number: number(get("CI_PIPELINE_IID")),
+            branch: get("CI_COMMIT_BRANCH").or_else(|| get("CI_MERGE_REQUEST_SOURCE_BRANCH_NAME")),
+            pull_request: number(get("CI_MERGE_REQUEST_IID")),
+        });
+    }
+
+    if is_set("CIRCLECI") {
+        return Some(CiInfo {
+            provider: Provider::CircleCi,
+            run_url: get("CIRCLE_BUILD_URL"),
+            actor: get("CIRCLE_USERNAME"),
+            pipeline_id: get("CIRCLE_WORKFLOW_ID"),
+            run_number: number(get("CIRCLE_BUILD_NUM")),
+            branch: get("CIRCLE_BRANCH"),
+            // Full URL: https://github.com/<owner>/<repo>/pull/<number>
+            pull_request: number(
+                get("CIRCLE_PULL_REQUEST").and_then(|u| u.rsplit('/').next().map(String::from)),
+            ),
+        });
+    }
+
+    if is_set("BUILDKITE") {
+        return Some(CiInfo {
+            provider: Provider::Buildkite,
+            run_url: get("BUILDKITE_BUILD_URL"),
+            actor: get("BUILDKITE_BUILD_CREATOR"),
+            pipeline_id: get("BUILDKITE_BUILD_ID"),
+            run_number: number(get("BUILDKITE_BUILD_NUMBER")),
+            branch: get("BUILDKITE_BRANCH"),
+            // "false" when not building a pull request
+            pull_request: number(get("BUILDKITE_PULL_REQUEST")),
+        });
+    }
+
+    None
+}
+
+fn number(value: Option<String>) -> Option<u64> {
+    value.and_then(|v| v.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect_from(vars: &[(&str, &str)]) -> Option<CiInfo> {
+        let env: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        detect_with(|key| env.get(key).cloned())
+    }
+
+    #[test]
+    fn detect_none_outside_ci() {
+        assert!(detect_from(&[]).is_none());
+    }
+
+    #[test]
+    fn detect_github_actions() {
+        let info = detect_from(&[
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_SERVER_URL", "https://github.com"),
+            ("GITHUB_REPOSITORY", "circlesac/oneup"),
+            ("GITHUB_RUN_ID", "42"),
+            ("GITHUB_ACTOR", "octocat"),
+            ("GITHUB_RUN_NUMBER", "17"),
+            ("GITHUB_REF", "refs/heads/develop"),
+        ])
+        .unwrap();
+        assert_eq!(info.provider, Provider::GitHubActions);
+        assert_eq!(
+            info.run_url.as_deref(),
+            Some("https://github.com/circlesac/oneup/actions/runs/42")
+        );
+        assert_eq!(info.actor.as_deref(), Some("octocat"));
+        assert_eq!(info.pipeline_id.as_deref(), Some("42"));
+        assert_eq!(info.run_number, Some(17));
+        assert_eq!(info.branch.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn detect_github_actions_tag_ref_has_no_branch() {
+        let info =
+            detect_from(&[("GITHUB_ACTIONS", "true"), ("GITHUB_REF", "refs/tags/v1")]).unwrap();
+        assert!(info.branch.is_none());
+    }
+
+    #[test]
+    fn detect_github_actions_partial_url() {
+        let info = detect_from(&[("GITHUB_ACTIONS", "true"), ("GITHUB_RUN_ID", "42")]).unwrap();
+        assert!(info.run_url.is_none());
+    }
+
+    #[test]
+    fn detect_gitlab_ci() {
+        let info = detect_from(&[
+            ("GITLAB_CI", "true"),
+            ("CI_PIPELINE_URL", "https://gitlab.com/g/p/-/pipelines/7"),
+            ("CI_PIPELINE_ID", "7"),
+        ])
+        .unwrap();
+        assert_eq!(info.provider, Provider::GitLabCi);
+        assert_eq!(info.pipeline_id.as_deref(), Some("7"));
+        assert!(info.actor.is_none());
+        assert!(info.run_number.is_none());
+    }
+
+    #[test]
+    fn detect_pull_request_numbers() {
+        let github = detect_from(&[
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_REF", "refs/pull/123/merge"),
+        ])
+        .unwrap();
+        assert_eq!(github.pull_request, Some(123));
+
+        let circle = detect_from(&[
+            ("CIRCLECI", "true"),
+            ("CIRCLE_PULL_REQUEST", "https://github.com/o/r/pull/45"),
+        ])
+        .unwrap();
+        assert_eq!(circle.pull_request, Some(45));
+
+        let buildkite =
+            detect_from(&[("BUILDKITE", "true"), ("BUILDKITE_PULL_REQUEST", "false")]).unwrap();
+        assert_eq!(buildkite.pull_request, None);
+    }
+
+    #[test]
+    fn detect_requires_true_flag() {
+        assert!(detect_from(&[("CIRCLECI", "false")]).is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::yaml::{line_end, top_level_value};
+
+/// Current `version` in a CITATION.cff.
+pub fn cff_version(content: &str) -> Option<&str> {
+    top_level_value(content, "version").map(|r| &content[r])
+}
+
+/// Set `version` and `date-released` (YYYY-MM-DD) in a CITATION.cff.
+/// A missing `date-released` is added right after `version`. Without a
+/// `date`, `date-released` is left as it is.
+pub fn update_cff(content: &str, version: &str, date: Option<&str>) -> Result<String> {
+    let range = top_level_value(content, "version")
+        .context("no top-level version found (expected version: ...)")?;
+
+    // An unquoted version like 26.10 would be read back as a number
+    let quoted = matches!(content[..range.start].chars().next_back(), Some('"' | '\''));
+    let version = if !quoted && version.parse::<f64>().is_ok() {
+        format!("\"{}\"", version)
+    } else {
+        version.to_string()
+    };
+
+    let mut output = content.to_string();
+    let Some(date) = date else {
+        output.replace_range(range, &version);
+        return Ok(output);
+    };
+    match top_level_value(content, "date-released") {
+        Some(date_range) => {
+            // Apply back to front so the earlier range stays valid
+            let mut edits = [(range, version.as_str()), (date_range, date)];
+            edits.sort_by_key(|(r, _)| std::cmp::Reverse(r.start));
+            for (r, value) in edits {
+                output.replace_range(r, value);
+            }
+        }
+        None => {
+            output.insert_str(
+                line_end(content, range.end),
+                &format!("\ndate-released: {}", date),
+            );
+            output.replace_range(range, &version);
+        }
+    }
+    Ok(output)
+}
+
+/// Set `version` and `dateModified` (when given a `date`) in a
+/// codemeta.json. `datePublished` is the first release and stays as is.
+pub fn update_codemeta(raw: &Value, version: &str, date: Option<&str>) -> Result<String> {
+    let mut raw = raw.clone();
+    let obj = raw
+        .as_object_mut()
+        .context("codemeta.json must be a JSON object")?;
+    obj.insert("version".to_string(), Value::String(version.to_string()));
+    if let Some(date) = date {
+        obj.insert("dateModified".to_string(), Value::String(date.to_string()));
+    }
+
+    let mut output = serde_json::to_string_pretty(&raw)?;
+    output.push('\n');
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CFF: &str = r#"cff-version: 1.2.0
+title: my-tool
+version: 26.2.1
+date-released: "2026-02-01"
+authors:
+  - family-names: Doe
+"#;
+
+    #[test]
+    fn cff_updates_version_and_date() {
+        assert_eq!(cff_version(CFF), Some("26.2.1"));
+        let updated = update_cff(CFF, "26.2.5", Some("2026-02-17")).unwrap();
+        assert!(updated.contains("\nversion: 26.2.5\n"));
+        assert!(updated.contains("\ndate-released: \"2026-02-17\"\n"));
+        assert!(updated.starts_with("cff-version: 1.2.0\n"));
+
+        let repaired = update_cff(CFF, "26.2.5", None).unwrap();
+        assert_eq!(repaired, CFF.replace("26.2.1", "26.2.5"));
+    }
+
+    #[test]
+    fn cff_adds_date_and_quotes_numeric_versions() {
+        let updated = update_cff("title: a\nversion: 1.0\n", "26.10", Some("2026-10-17")).unwrap();
+        assert_eq!(
+            updated,
+            "title: a\nversion: \"26.10\"\ndate-released: 2026-10-17\n"
+        );
+        assert!(update_cff("title: a\n", "26.2.5", Some("2026-02-17")).is_err());
+    }
+
+    #[test]
+    fn codemeta_updates_version_and_date_modified() {
+        let raw: Value = serde_json::from_str(
+            r#"{"name": "my-tool", "version": "26.2.1", "datePublished": "2026-01-05"}"#,
+        )
+        .unwrap();
+        let updated: Value =
+            serde_json::from_str(&update_codemeta(&raw, "26.2.5", Some("2026-02-17")).unwrap())
+                .unwrap();
+        assert_eq!(updated["version"], "26.2.5");
+        assert_eq!(updated["dateModified"], "2026-02-17");
+        assert_eq!(updated["datePublished"], "2026-01-05");
+
+        let repaired: Value =
+            serde_json::from_str(&update_codemeta(&raw, "26.2.5", None).unwrap()).unwrap();
+        assert_eq!(repaired["version"], "26.2.5");
+        assert!(repaired.get("dateModified").is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use clap::{Parser, Subcommand};
+use clap::builder::BoolishValueParser;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -12,27 +13,749 @@ pub struct Cli {
 pub enum Commands {
     /// Calculate next version and update target files
     Version(VersionArgs),
+    /// Propose a CalVer format for a semver package and write its first CalVer version
+    Adopt(AdoptArgs),
+    /// Check whether a version (or the next computed one) is still free in the registry
+    Available(AvailableArgs),
+    /// Report the highest version published in a date period (e.g. --period 2026-01)
+    Latest(LatestArgs),
+    /// Check that all targets carry the same version, and rewrite them with --fix
+    Sync(SyncArgs),
+    /// Run pre-release checks (manifest, format, git state, tag, registry, auth)
+    Preflight(PreflightArgs),
+    /// Show every package's version, registry latest, today's release and pending commits
+    Status(StatusArgs),
+    /// Report releases over a period from git tags and registry data: counts per
+    /// package and month, lead times
+    Report(ReportArgs),
+    /// Read and edit oneup.toml, or show the effective configuration and its sources
+    Config(ConfigArgs),
+    /// Log in to a registry (token paste or npm web login) and store the token
+    /// in the OS credential store, or in ~/.npmrc with --npmrc
+    #[cfg(feature = "npm")]
+    Login(LoginArgs),
+    /// Remove a registry token stored by login from the OS credential store and ~/.npmrc
+    #[cfg(feature = "npm")]
+    Logout(LogoutArgs),
+    /// Restore target files from a backup snapshot taken before they were written
+    Restore(RestoreArgs),
+    /// Write a release manifest: package, version, git SHA and tag, timestamp, target hashes
+    ReleaseManifest(ReleaseManifestArgs),
+    /// Add the current version to an Atom feed of releases (releases.xml)
+    Feed(FeedArgs),
+    /// Print the image tags derived from the version (exact, date prefix, latest or channel)
+    DockerTag(DockerTagArgs),
+    /// Post a release announcement to Mastodon ([announce.mastodon] in oneup.toml)
+    #[cfg(feature = "network")]
+    Announce(AnnounceArgs),
+    /// Announce a release on GitHub: fire a repository_dispatch event or create
+    /// a deployment carrying the version, to start downstream workflows
+    #[cfg(feature = "network")]
+    Dispatch(DispatchArgs),
+    /// Update a Homebrew formula, Scoop manifest or winget manifest in a tap
+    /// or bucket repository to the release: version, download URLs and their
+    /// SHA-256, committed through the GitHub API
+    #[cfg(feature = "network")]
+    Distribute(DistributeArgs),
+    /// Update oneup to the latest GitHub release
+    #[cfg(feature = "network")]
+    SelfUpdate(SelfUpdateArgs),
+    /// Write man pages and a JSON CLI spec for all subcommands
+    Man(ManArgs),
+    /// Scaffold release automation (a CI workflow) from the detected targets
+    Generate(GenerateArgs),
+    /// Run oneup end to end against a local registry stub and temporary git
+    /// repositories to validate the installation
+    #[cfg(feature = "selftest")]
+    Selftest(SelftestArgs),
 }
 
 #[derive(Parser)]
 pub struct VersionArgs {
-    /// Target file(s) — repeatable (auto-detected if omitted)
-    #[arg(long)]
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
     pub target: Vec<PathBuf>,
 
     /// Registry URL override (auto-detected from .npmrc or crates.io)
-    #[arg(long)]
+    #[arg(long, env = "ONEUP_REGISTRY")]
     pub registry: Option<String>,
 
-    /// Version format (CalVer tokens: YYYY, YY, MM, DD, MICRO)
-    #[arg(long, default_value = "YY.MM.MICRO")]
+    /// Registry type (auto-detected from the primary target); github-tags reads
+    /// the tags of the --registry owner/repo (default: GITHUB_REPOSITORY)
+    #[arg(long, value_enum, env = "ONEUP_REGISTRY_TYPE")]
+    pub registry_type: Option<RegistryType>,
+
+    /// Version format (CalVer tokens: YYYY, YY, MM, DD, MICRO; optional +SHA build metadata)
+    #[arg(long, default_value = "YY.MM.MICRO", env = "ONEUP_FORMAT")]
     pub format: String,
 
+    /// Where MICRO comes from: registry (highest published + 1), ci (pipeline run number)
+    /// or git-count (commits since the period started)
+    #[arg(
+        long,
+        value_enum,
+        default_value = "registry",
+        env = "ONEUP_MICRO_SOURCE"
+    )]
+    pub micro_source: MicroSource,
+
+    /// Explicit MICRO value (still validated against the registry)
+    #[arg(long, env = "ONEUP_MICRO")]
+    pub micro: Option<u64>,
+
+    /// Build a pull request preview version 0.0.0-pr<N>.<n>; without a number,
+    /// the pull request is detected from the CI environment
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "auto", env = "ONEUP_PR")]
+    pub pr: Option<String>,
+
+    /// Target whose version and package name face the registry
+    /// (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Package name for the registry query (overrides the manifest's name)
+    #[arg(long, value_name = "NAME", env = "ONEUP_PACKAGE_NAME")]
+    pub package_name: Option<String>,
+
+    /// Hotfix the released version: <latest>-hotfix.<n> instead of the next
+    /// scheduled version
+    #[arg(long, conflicts_with = "pr", env = "ONEUP_HOTFIX", value_parser = BoolishValueParser::new())]
+    pub hotfix: bool,
+
+    /// Bump even outside the allowed-windows set in oneup.toml
+    #[arg(long, env = "ONEUP_OVERRIDE_WINDOW", value_parser = BoolishValueParser::new())]
+    pub override_window: bool,
+
+    /// Emit TeamCity service messages (build number and env.ONEUP_VERSION)
+    #[arg(long, env = "ONEUP_TEAMCITY", value_parser = BoolishValueParser::new())]
+    pub teamcity: bool,
+
+    /// Write the new version to a Java properties file (VERSION=...) for Jenkins
+    #[arg(long, value_name = "PATH", env = "ONEUP_PROPERTIES_FILE")]
+    pub properties_file: Option<PathBuf>,
+
     /// Show what would happen without making changes
-    #[arg(long)]
+    #[arg(long, env = "ONEUP_DRY_RUN", value_parser = BoolishValueParser::new())]
+    pub dry_run: bool,
+
+    /// Count versions unpublished from npm or yanked from crates.io as taken,
+    /// so their numbers are never issued again
+    #[arg(long, env = "ONEUP_NEVER_REUSE", value_parser = BoolishValueParser::new())]
+    pub never_reuse: bool,
+
+    /// Only count versions published before this time (YYYY-MM-DD or RFC 3339),
+    /// like npm's --before, to replay a past release decision
+    #[arg(long, value_name = "TIME", env = "ONEUP_BEFORE")]
+    pub before: Option<String>,
+
+    /// Build the version for this date instead of today (YYYY-MM-DD;
+    /// defaults to the date of --before)
+    #[arg(long, value_name = "DATE", env = "ONEUP_DATE")]
+    pub date: Option<chrono::NaiveDate>,
+
+    /// Take today's date in UTC instead of the local timezone (default: on
+    /// when a CI provider is detected; --utc=false turns it off)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true", env = "ONEUP_UTC", value_parser = BoolishValueParser::new())]
+    pub utc: Option<bool>,
+
+    /// Save the registry's answer (versions and latest) to a cassette file
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "replay",
+        env = "ONEUP_RECORD"
+    )]
+    pub record: Option<PathBuf>,
+
+    /// Use a recorded cassette instead of querying the registry
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "registry",
+        env = "ONEUP_REPLAY"
+    )]
+    pub replay: Option<PathBuf>,
+
+    /// Report time spent in each phase (targets, npmrc, registry, compute, write)
+    #[arg(long, env = "ONEUP_TIMING", value_parser = BoolishValueParser::new())]
+    pub timing: bool,
+
+    /// After the run, push package, version, duration and outcome to a
+    /// Prometheus Pushgateway, or to an OTLP/HTTP endpoint (URL ending in /v1/metrics)
+    #[arg(long, value_name = "URL", env = "ONEUP_METRICS")]
+    pub metrics: Option<String>,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum RegistryType {
+    /// npm registry (.npmrc)
+    Npm,
+    /// crates.io
+    Crates,
+    /// Tags of a GitHub repository (v-prefix optional)
+    GithubTags,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum MicroSource {
+    /// Highest MICRO published for today's prefix, plus one
+    Registry,
+    /// CI run number (GITHUB_RUN_NUMBER, CI_PIPELINE_IID, CIRCLE_BUILD_NUM, BUILDKITE_BUILD_NUMBER)
+    Ci,
+    /// Number of git commits since the start of the current date period (no registry query)
+    GitCount,
+}
+
+#[derive(Parser)]
+pub struct SyncArgs {
+    /// Canonical version (defaults to the primary target's version)
+    pub version: Option<String>,
+
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Target whose version is canonical (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Rewrite disagreeing targets to the canonical version
+    #[arg(long, env = "ONEUP_FIX", value_parser = BoolishValueParser::new())]
+    pub fix: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct PreflightArgs {
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Registry URL override (auto-detected from .npmrc or crates.io)
+    #[arg(long, env = "ONEUP_REGISTRY")]
+    pub registry: Option<String>,
+
+    /// Registry type (auto-detected from the primary target)
+    #[arg(long, value_enum, env = "ONEUP_REGISTRY_TYPE")]
+    pub registry_type: Option<RegistryType>,
+
+    /// Package name for the registry query (overrides the manifest's name)
+    #[arg(long, value_name = "NAME", env = "ONEUP_PACKAGE_NAME")]
+    pub package_name: Option<String>,
+
+    /// Version format to validate and compute the next version with
+    #[arg(long, default_value = "YY.MM.MICRO", env = "ONEUP_FORMAT")]
+    pub format: String,
+
+    /// Only warn when the uncommitted changes are all to the target files
+    /// (e.g. pre-edited by another tool); other changes still fail
+    #[arg(
+        long,
+        env = "ONEUP_ALLOW_DIRTY_TARGETS",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub allow_dirty_targets: bool,
+
+    /// Print the report as JSON
+    #[arg(long, env = "ONEUP_JSON", value_parser = BoolishValueParser::new())]
+    pub json: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct StatusArgs {
+    /// Package manifest(s) — repeatable or comma-separated (default: the
+    /// root package and the npm or Cargo workspace members)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Registry URL override (auto-detected from .npmrc or crates.io)
+    #[arg(long, env = "ONEUP_REGISTRY")]
+    pub registry: Option<String>,
+
+    /// Registry type (auto-detected per package)
+    #[arg(long, value_enum, env = "ONEUP_REGISTRY_TYPE")]
+    pub registry_type: Option<RegistryType>,
+
+    /// Version format whose date period counts as today
+    #[arg(long, default_value = "YY.MM.MICRO", env = "ONEUP_FORMAT")]
+    pub format: String,
+
+    /// Print the report as JSON
+    #[arg(long, env = "ONEUP_JSON", value_parser = BoolishValueParser::new())]
+    pub json: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct ReportArgs {
+    /// Start of the period: a month (2026-01) or a day (2026-01-15), UTC
+    #[arg(long, env = "ONEUP_SINCE")]
+    pub since: String,
+
+    /// End of the period, inclusive: a month or a day (default: now)
+    #[arg(long, env = "ONEUP_UNTIL")]
+    pub until: Option<String>,
+
+    /// Package manifest(s) — repeatable or comma-separated (default: the
+    /// root package and the npm or Cargo workspace members)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Registry URL override (auto-detected from .npmrc or crates.io)
+    #[arg(long, env = "ONEUP_REGISTRY")]
+    pub registry: Option<String>,
+
+    /// Registry type (auto-detected per package)
+    #[arg(long, value_enum, env = "ONEUP_REGISTRY_TYPE")]
+    pub registry_type: Option<RegistryType>,
+
+    /// Report format (default: from the --out extension, else markdown)
+    #[arg(long, value_enum, env = "ONEUP_OUTPUT_FORMAT")]
+    pub output_format: Option<ReportFormat>,
+
+    /// Write the report to a file instead of stdout
+    #[arg(long, env = "ONEUP_REPORT_OUT")]
+    pub out: Option<PathBuf>,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Csv,
+    Html,
+}
+
+#[derive(Parser)]
+pub struct ReleaseManifestArgs {
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Target whose package name and version are released (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Where to write the manifest
+    #[arg(long, default_value = "release-manifest.json", env = "ONEUP_MANIFEST_OUT")]
+    pub out: PathBuf,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct FeedArgs {
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Target whose package name and version are released (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Feed file to create or update
+    #[arg(long, default_value = "releases.xml", env = "ONEUP_FEED_OUT")]
+    pub out: PathBuf,
+
+    /// Feed title (default: "<package> releases")
+    #[arg(long, env = "ONEUP_TITLE")]
+    pub title: Option<String>,
+
+    /// Repository web URL for tag links (default: from GitHub Actions or GitLab CI)
+    #[arg(long, value_name = "URL", env = "ONEUP_LINK")]
+    pub link: Option<String>,
+
+    /// Releases kept in the feed, newest first
+    #[arg(long, default_value_t = 50, env = "ONEUP_MAX_ENTRIES")]
+    pub max_entries: usize,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct DockerTagArgs {
+    /// Image name, e.g. ghcr.io/org/app
+    pub image: String,
+
+    /// Version to tag (defaults to the primary target's version)
+    pub version: Option<String>,
+
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Target whose version is tagged (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Version format the version was built from (for the date-prefix tag)
+    #[arg(long, default_value = "YY.MM.MICRO", env = "ONEUP_FORMAT")]
+    pub format: String,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct AnnounceArgs {
+    /// Version to announce (defaults to the primary target's version)
+    pub version: Option<String>,
+
+    /// Link for {link} in the template (default: the release tag page, from
+    /// GitHub Actions or GitLab CI)
+    #[arg(long, value_name = "URL", env = "ONEUP_ANNOUNCE_LINK")]
+    pub link: Option<String>,
+
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Target whose package and version are announced (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Print the post without sending it
+    #[arg(long, env = "ONEUP_DRY_RUN", value_parser = BoolishValueParser::new())]
     pub dry_run: bool,
 
     /// Print detailed debug output
-    #[arg(long)]
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct DispatchArgs {
+    /// Version to announce (defaults to the primary target's version)
+    pub version: Option<String>,
+
+    /// GitHub repository to notify, owner/repo (default: GITHUB_REPOSITORY)
+    #[arg(long, env = "ONEUP_REPO")]
+    pub repo: Option<String>,
+
+    /// Fire a repository_dispatch event of this type
+    #[arg(long, value_name = "TYPE", env = "ONEUP_EVENT")]
+    pub event: Option<String>,
+
+    /// Create a deployment of the release to this environment
+    #[arg(long, value_name = "ENVIRONMENT", env = "ONEUP_DEPLOYMENT")]
+    pub deployment: Option<String>,
+
+    /// Git ref to deploy (default: the release tag, v<version>)
+    #[arg(long = "ref", value_name = "REF", env = "ONEUP_REF")]
+    pub git_ref: Option<String>,
+
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Target whose version is announced (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Print the requests without sending them
+    #[arg(long, env = "ONEUP_DRY_RUN", value_parser = BoolishValueParser::new())]
+    pub dry_run: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct DistributeArgs {
+    /// Version to distribute (defaults to the primary target's version)
+    pub version: Option<String>,
+
+    /// Tap or bucket repository holding the manifests, owner/repo
+    #[arg(long, env = "ONEUP_DISTRIBUTE_REPO")]
+    pub repo: String,
+
+    /// Manifest(s) in that repository — repeatable or comma-separated
+    /// (Formula/oneup.rb, bucket/oneup.json, manifests/.../*.yaml)
+    #[arg(
+        long,
+        required = true,
+        value_delimiter = ',',
+        env = "ONEUP_DISTRIBUTE_PATH"
+    )]
+    pub path: Vec<String>,
+
+    /// Branch to commit to (default: the repository's default branch)
+    #[arg(long, env = "ONEUP_DISTRIBUTE_BRANCH")]
+    pub branch: Option<String>,
+
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Target whose version is distributed (default: the first published target without its own format)
+    #[arg(long, value_name = "PATH", env = "ONEUP_PRIMARY")]
+    pub primary: Option<PathBuf>,
+
+    /// Print the changed lines without committing them
+    #[arg(long, env = "ONEUP_DRY_RUN", value_parser = BoolishValueParser::new())]
+    pub dry_run: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print a setting from oneup.toml (dotted key, e.g. targets."Chart.yaml".format)
+    Get { key: String },
+    /// Set a setting in oneup.toml; the value is read as TOML, or else as a string
+    Set { key: String, value: String },
+    /// List the settings in oneup.toml
+    List,
+    /// Show every setting with its effective value and where it comes from
+    /// (oneup.toml, .oneup-freeze, ONEUP_* environment variable or default)
+    Effective,
+    /// Check oneup.toml for syntax errors, unknown keys, invalid values and
+    /// conflicting settings; exits non-zero on any problem
+    Validate {
+        /// Also expand ${NAME} and {branch} as a run would, reporting unset
+        /// variables (default: check the settings as written)
+        #[arg(long, env = "ONEUP_EXPAND", value_parser = BoolishValueParser::new())]
+        expand: bool,
+    },
+}
+
+#[cfg(feature = "npm")]
+#[derive(Parser)]
+pub struct LoginArgs {
+    /// npm registry URL or GitLab shorthand (defaults to the registry of ./package.json)
+    pub registry: Option<String>,
+
+    /// Log in through the browser (npm web login) instead of pasting a token
+    #[arg(long, env = "ONEUP_WEB", value_parser = BoolishValueParser::new())]
+    pub web: bool,
+
+    /// Write the token to ~/.npmrc instead of the OS credential store
+    #[arg(long, env = "ONEUP_NPMRC", value_parser = BoolishValueParser::new())]
+    pub npmrc: bool,
+}
+
+#[cfg(feature = "npm")]
+#[derive(Parser)]
+pub struct LogoutArgs {
+    /// npm registry URL or GitLab shorthand (defaults to the registry of ./package.json)
+    pub registry: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct RestoreArgs {
+    /// Snapshot to restore (defaults to the most recent)
+    pub snapshot: Option<String>,
+
+    /// List snapshots and the files they hold
+    #[arg(long, env = "ONEUP_LIST", value_parser = BoolishValueParser::new())]
+    pub list: bool,
+
+    /// Show what would be restored without writing
+    #[arg(long, env = "ONEUP_DRY_RUN", value_parser = BoolishValueParser::new())]
+    pub dry_run: bool,
+}
+
+#[cfg(feature = "selftest")]
+#[derive(Parser)]
+pub struct SelftestArgs {
+    /// Print each oneup invocation and the stub's address
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[cfg(feature = "network")]
+#[derive(Parser)]
+pub struct SelfUpdateArgs {
+    /// Only check whether a newer release exists
+    #[arg(long, env = "ONEUP_CHECK", value_parser = BoolishValueParser::new())]
+    pub check: bool,
+
+    /// Update even if oneup appears to be installed by a package manager
+    #[arg(long, env = "ONEUP_SELF_UPDATE_FORCE", value_parser = BoolishValueParser::new())]
+    pub force: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct ManArgs {
+    /// Directory to write man pages and oneup.json into
+    #[arg(long, default_value = "man", env = "ONEUP_OUT_DIR")]
+    pub out_dir: PathBuf,
+}
+
+#[derive(Parser)]
+pub struct GenerateArgs {
+    #[command(subcommand)]
+    pub what: GenerateCommand,
+}
+
+#[derive(Subcommand)]
+pub enum GenerateCommand {
+    /// Write a release workflow: oneup version, publish, tag push and release notes
+    Ci(GenerateCiArgs),
+}
+
+#[derive(Parser)]
+pub struct GenerateCiArgs {
+    /// CI system (default: gitlab when .gitlab-ci.yml exists, else github)
+    #[arg(long, value_enum, env = "ONEUP_PROVIDER")]
+    pub provider: Option<CiProvider>,
+
+    /// Target file(s) the workflow bumps — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Version format the workflow releases with
+    #[arg(long, default_value = "YY.MM.MICRO", env = "ONEUP_FORMAT")]
+    pub format: String,
+
+    /// File to write (default: .github/workflows/release.yml, or .gitlab-ci.yml;
+    /// .gitlab/oneup-release.yml to include when .gitlab-ci.yml exists)
+    #[arg(long, env = "ONEUP_CI_OUT")]
+    pub out: Option<PathBuf>,
+
+    /// Overwrite the file if it exists
+    #[arg(long, env = "ONEUP_CI_FORCE", value_parser = BoolishValueParser::new())]
+    pub force: bool,
+
+    /// Print the workflow instead of writing it
+    #[arg(long, env = "ONEUP_DRY_RUN", value_parser = BoolishValueParser::new())]
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum CiProvider {
+    /// GitHub Actions
+    Github,
+    /// GitLab CI/CD
+    Gitlab,
+}
+
+#[derive(Parser)]
+pub struct AdoptArgs {
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Registry URL override (auto-detected from .npmrc or crates.io)
+    #[arg(long, env = "ONEUP_REGISTRY")]
+    pub registry: Option<String>,
+
+    /// Registry type (auto-detected from the primary target); github-tags reads
+    /// the tags of the --registry owner/repo (default: GITHUB_REPOSITORY)
+    #[arg(long, value_enum, env = "ONEUP_REGISTRY_TYPE")]
+    pub registry_type: Option<RegistryType>,
+
+    /// Show the proposal without writing target files
+    #[arg(long, env = "ONEUP_DRY_RUN", value_parser = BoolishValueParser::new())]
+    pub dry_run: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct LatestArgs {
+    /// Date period: YYYY, YYYY-MM or YYYY-MM-DD
+    #[arg(long, env = "ONEUP_PERIOD")]
+    pub period: String,
+
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Registry URL override (auto-detected from .npmrc or crates.io)
+    #[arg(long, env = "ONEUP_REGISTRY")]
+    pub registry: Option<String>,
+
+    /// Registry type (auto-detected from the primary target); github-tags reads
+    /// the tags of the --registry owner/repo (default: GITHUB_REPOSITORY)
+    #[arg(long, value_enum, env = "ONEUP_REGISTRY_TYPE")]
+    pub registry_type: Option<RegistryType>,
+
+    /// Package name for the registry query (overrides the manifest's name)
+    #[arg(long, value_name = "NAME", env = "ONEUP_PACKAGE_NAME")]
+    pub package_name: Option<String>,
+
+    /// Version format the package is released with
+    #[arg(long, default_value = "YY.MM.MICRO", env = "ONEUP_FORMAT")]
+    pub format: String,
+
+    /// Print the result as JSON
+    #[arg(long, env = "ONEUP_JSON", value_parser = BoolishValueParser::new())]
+    pub json: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
+    pub verbose: bool,
+}
+
+#[derive(Parser)]
+pub struct AvailableArgs {
+    /// Version to check (defaults to the version `oneup version` would compute)
+    pub version: Option<String>,
+
+    /// Target file(s) — repeatable or comma-separated (auto-detected if omitted)
+    #[arg(long, env = "ONEUP_TARGET", value_delimiter = ',')]
+    pub target: Vec<PathBuf>,
+
+    /// Registry URL override (auto-detected from .npmrc or crates.io)
+    #[arg(long, env = "ONEUP_REGISTRY")]
+    pub registry: Option<String>,
+
+    /// Registry type (auto-detected from the primary target); github-tags reads
+    /// the tags of the --registry owner/repo (default: GITHUB_REPOSITORY)
+    #[arg(long, value_enum, env = "ONEUP_REGISTRY_TYPE")]
+    pub registry_type: Option<RegistryType>,
+
+    /// Package name for the registry query (overrides the manifest's name)
+    #[arg(long, value_name = "NAME", env = "ONEUP_PACKAGE_NAME")]
+    pub package_name: Option<String>,
+
+    /// Version format used to compute the version when none is given
+    #[arg(long, default_value = "YY.MM.MICRO", env = "ONEUP_FORMAT")]
+    pub format: String,
+
+    /// Print the result as JSON
+    #[arg(long, env = "ONEUP_JSON", value_parser = BoolishValueParser::new())]
+    pub json: bool,
+
+    /// Print detailed debug output
+    #[arg(long, env = "ONEUP_VERBOSE", value_parser = BoolishValueParser::new())]
     pub verbose: bool,
 }
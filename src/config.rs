@@ -0,0 +1,2043 @@
+use anyhow::{Context, Result, bail};
+use clap::CommandFactory;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use toml_edit::{DocumentMut, ImDocument, Item, Key, Table, TableLike};
+
+use crate::backup::DEFAULT_KEEP_BACKUPS;
+use crate::ci::CiInfo;
+use crate::cli::{Cli, ConfigAction, ConfigArgs};
+use crate::docs;
+use crate::error::{ErrorKind, ResultExt};
+use crate::git;
+use crate::policy::{POLICY_KEYS, Policy};
+use crate::registry::DEFAULT_MAX_RESPONSE_MB;
+use crate::resolve::Skip;
+use crate::suggest;
+use crate::window::ReleaseWindow;
+
+pub const CONFIG_FILE: &str = "oneup.toml";
+
+/// Hours the registry's latest may be dated ahead of today before oneup
+/// warns: publishers in timezones up to UTC+14 are a day ahead of UTC-12
+pub const DEFAULT_CLOCK_SKEW_HOURS: u64 = 26;
+
+/// Committing this file freezes releases; its contents are the reason
+pub const FREEZE_FILE: &str = ".oneup-freeze";
+
+/// Top-level oneup.toml keys and their defaults, as TOML
+const KEYS: &[(&str, &str)] = &[
+    ("skip", "[]"),
+    ("channels", "{}"),
+    ("allowed-windows", "[]"),
+    ("registry-mirrors", "[]"),
+    ("publish-registries", "[]"),
+    ("freeze", "false"),
+    ("hotfix-segment", "\"hotfix\""),
+    ("clock-skew-hours", "26"),
+    ("max-response-mb", "256"),
+    ("keep-backups", "10"),
+    ("extends", "\"\""),
+    ("policy", "{}"),
+    ("announce", "{}"),
+    ("targets", "{}"),
+];
+
+/// Keys of a [targets."<path>"] table
+const TARGET_KEYS: &[&str] = &[
+    "format",
+    "primary",
+    "publish-name",
+    "template",
+    "app-version-from",
+    "patterns",
+    "image",
+];
+
+/// Keys of the [announce.mastodon] table
+const MASTODON_KEYS: &[&str] = &["instance", "template", "visibility"];
+
+/// Settings read from oneup.toml
+pub struct Config {
+    /// Versions or MICRO values that must never be emitted
+    pub skip: Vec<Skip>,
+    /// Branch pattern → prerelease channel, in file order (first match wins)
+    pub channels: Vec<(String, String)>,
+    /// Windows in which releases are allowed; empty means always
+    pub allowed_windows: Vec<ReleaseWindow>,
+    /// Reason releases are frozen (`freeze` in oneup.toml or .oneup-freeze)
+    pub freeze: Option<String>,
+    /// Prerelease identifier for --hotfix versions
+    pub hotfix_segment: String,
+    /// npm registries tried in order when the registry lookup fails
+    pub registry_mirrors: Vec<String>,
+    /// Further npm registries the package is published to; lookups take
+    /// the highest version across all of them
+    pub publish_registries: Vec<String>,
+    /// Tolerance before a registry latest dated ahead of today is reported
+    pub clock_skew_hours: u64,
+    /// Largest registry response read, in megabytes
+    pub max_response_mb: u64,
+    /// Backup snapshots kept under .oneup/backup; 0 turns backups off
+    pub keep_backups: u64,
+    /// Rules every computed version must pass ([policy])
+    pub policy: Policy,
+    /// Where `oneup announce` posts; announcements are off without it
+    pub mastodon: Option<MastodonConfig>,
+    /// Per-target settings from [targets."<path>"]
+    pub targets: Vec<(PathBuf, TargetConfig)>,
+}
+
+/// Settings for one target file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TargetConfig {
+    /// Version format for this target when it is not the primary
+    pub format: Option<String>,
+    /// This target's version and package name face the registry
+    pub primary: bool,
+    /// Registry package name, when it differs from (or is missing in) the manifest
+    pub publish_name: Option<String>,
+    /// Template for a generated source target ({version} is replaced)
+    pub template: Option<String>,
+    /// Target whose version becomes this Helm chart's appVersion
+    pub app_version_from: Option<PathBuf>,
+    /// Patterns around {version} to rewrite in a docs target (README, ...)
+    pub patterns: Vec<String>,
+    /// Container image whose tag this compose/Kubernetes/kustomize file pins
+    pub image: Option<String>,
+}
+
+/// [announce.mastodon]: the account release announcements are posted to
+#[derive(Debug, Clone, PartialEq)]
+pub struct MastodonConfig {
+    /// Server URL, e.g. https://fosstodon.org
+    pub instance: String,
+    /// Status text; {package}, {version}, {tag} and {link} are replaced
+    pub template: Option<String>,
+    /// public, unlisted, private or direct (default: the account's setting)
+    pub visibility: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            skip: Vec::new(),
+            channels: Vec::new(),
+            allowed_windows: Vec::new(),
+            freeze: None,
+            hotfix_segment: "hotfix".to_string(),
+            registry_mirrors: Vec::new(),
+            publish_registries: Vec::new(),
+            clock_skew_hours: DEFAULT_CLOCK_SKEW_HOURS,
+            max_response_mb: DEFAULT_MAX_RESPONSE_MB,
+            keep_backups: DEFAULT_KEEP_BACKUPS,
+            policy: Policy::default(),
+            mastodon: None,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load oneup.toml from `dir`. A missing file yields the default config.
+    /// A .oneup-freeze file in `dir` freezes releases regardless of the config.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(CONFIG_FILE);
+        let mut config = match read_config(&path)? {
+            Some((_, merged)) => Self::from_merged(&path, &merged)?,
+            None => Self::default(),
+        };
+
+        let freeze_path = dir.join(FREEZE_FILE);
+        if freeze_path.exists() {
+            let reason = std::fs::read_to_string(&freeze_path)
+                .with_context(|| format!("failed to read {}", freeze_path.display()))?;
+            let reason = reason.trim();
+            config.freeze = Some(if reason.is_empty() {
+                format!("{} is present", FREEZE_FILE)
+            } else {
+                reason.to_string()
+            });
+        }
+
+        Ok(config)
+    }
+
+    /// The config of `path` from its document with `extends` merged in.
+    fn from_merged(path: &Path, merged: &DocumentMut) -> Result<Self> {
+        Self::from_document(merged)
+            .with_context(|| format!("invalid config {}", path.display()))
+            .kind(ErrorKind::Config)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let doc: toml_edit::DocumentMut = content.parse().context("invalid TOML")?;
+        Self::from_document(&doc)
+    }
+
+    fn from_document(doc: &DocumentMut) -> Result<Self> {
+        Self::parse_document(&interpolate_document(doc)?)
+    }
+
+    /// The settings of `doc` as written, without expanding `${NAME}` or
+    /// `{branch}`.
+    fn parse_document(doc: &DocumentMut) -> Result<Self> {
+        let mut config = Self::default();
+
+        if let Some(item) = doc.get("skip") {
+            let array = item
+                .as_array()
+                .context("'skip' must be an array of versions or MICRO values")?;
+            for value in array.iter() {
+                let skip = if let Some(v) = value.as_str() {
+                    Skip::Version(v.to_string())
+                } else if let Some(m) = value.as_integer().filter(|m| *m >= 0) {
+                    Skip::Micro(m as u64)
+                } else {
+                    bail!(
+                        "'skip' entries must be version strings or non-negative integers, got {}",
+                        value.to_string().trim()
+                    );
+                };
+                config.skip.push(skip);
+            }
+        }
+
+        if let Some(item) = doc.get("channels") {
+            let table = item
+                .as_table_like()
+                .context("'channels' must be a table of branch = channel")?;
+            for (branch, value) in table.iter() {
+                let channel = value
+                    .as_str()
+                    .with_context(|| format!("channel for branch '{}' must be a string", branch))?;
+                config
+                    .channels
+                    .push((branch.to_string(), channel.to_string()));
+            }
+        }
+
+        if let Some(item) = doc.get("allowed-windows") {
+            let array = item
+                .as_array()
+                .context("'allowed-windows' must be an array of window strings")?;
+            for value in array.iter() {
+                let spec = value
+                    .as_str()
+                    .context("'allowed-windows' entries must be strings")?;
+                config.allowed_windows.push(ReleaseWindow::parse(spec)?);
+            }
+        }
+
+        if let Some(item) = doc.get("registry-mirrors") {
+            let array = item
+                .as_array()
+                .context("'registry-mirrors' must be an array of registry URLs")?;
+            for value in array.iter() {
+                let url = value
+                    .as_str()
+                    .context("'registry-mirrors' entries must be strings")?;
+                check_registry_url("registry-mirrors", url)?;
+                config.registry_mirrors.push(url.to_string());
+            }
+        }
+
+        if let Some(item) = doc.get("publish-registries") {
+            let array = item
+                .as_array()
+                .context("'publish-registries' must be an array of registry URLs")?;
+            for value in array.iter() {
+                let url = value
+                    .as_str()
+                    .context("'publish-registries' entries must be strings")?;
+                check_registry_url("publish-registries", url)?;
+                config.publish_registries.push(url.to_string());
+            }
+        }
+
+        if let Some(item) = doc.get("freeze") {
+            if let Some(reason) = item.as_str() {
+                config.freeze = Some(reason.to_string());
+            } else if let Some(frozen) = item.as_bool() {
+                config.freeze = frozen.then(|| "freeze is set in oneup.toml".to_string());
+            } else {
+                bail!("'freeze' must be a boolean or a reason string");
+            }
+        }
+
+        if let Some(item) = doc.get("hotfix-segment") {
+            let segment = item
+                .as_str()
+                .filter(|s| {
+                    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                })
+                .context(
+                    "'hotfix-segment' must be a non-empty string of letters, digits and '-'",
+                )?;
+            config.hotfix_segment = segment.to_string();
+        }
+
+        if let Some(item) = doc.get("clock-skew-hours") {
+            let hours = item
+                .as_integer()
+                .filter(|h| *h >= 0)
+                .context("'clock-skew-hours' must be a non-negative number of hours")?;
+            config.clock_skew_hours = hours as u64;
+        }
+
+        if let Some(item) = doc.get("max-response-mb") {
+            let mb = item
+                .as_integer()
+                .filter(|mb| *mb > 0)
+                .context("'max-response-mb' must be a positive number of megabytes")?;
+            config.max_response_mb = mb as u64;
+        }
+
+        if let Some(item) = doc.get("keep-backups") {
+            let keep = item
+                .as_integer()
+                .filter(|n| *n >= 0)
+                .context("'keep-backups' must be a non-negative number of snapshots")?;
+            config.keep_backups = keep as u64;
+        }
+
+        if let Some(item) = doc.get("extends") {
+            // Resolved before parsing; here only its shape is checked
+            item.as_str().filter(|s| !s.is_empty()).context(
+                "'extends' must be a path or github:owner/repo/path[@ref] to a shared oneup.toml",
+            )?;
+        }
+
+        if let Some(item) = doc.get("policy") {
+            config.policy = Policy::parse(item)?;
+        }
+
+        if let Some(item) = doc.get("announce") {
+            let table = item
+                .as_table_like()
+                .context("'announce' must be a table ([announce.mastodon])")?;
+            if let Some(mastodon) = table.get("mastodon") {
+                config.mastodon = Some(parse_mastodon(mastodon)?);
+            }
+        }
+
+        if let Some(item) = doc.get("targets") {
+            let table = item
+                .as_table_like()
+                .context("'targets' must be a table of target path = settings")?;
+            for (path, value) in table.iter() {
+                let settings = value
+                    .as_table_like()
+                    .with_context(|| format!("settings for target '{}' must be a table", path))?;
+                let mut target = TargetConfig::default();
+                if let Some(format) = settings.get("format") {
+                    let format = format.as_str().with_context(|| {
+                        format!("format for target '{}' must be a string", path)
+                    })?;
+                    target.format = Some(format.to_string());
+                }
+                if let Some(name) = settings.get("publish-name") {
+                    let name = name.as_str().with_context(|| {
+                        format!("publish-name for target '{}' must be a string", path)
+                    })?;
+                    target.publish_name = Some(name.to_string());
+                }
+                if let Some(template) = settings.get("template") {
+                    let template = template.as_str().with_context(|| {
+                        format!("template for target '{}' must be a string", path)
+                    })?;
+                    target.template = Some(template.to_string());
+                }
+                if let Some(from) = settings.get("app-version-from") {
+                    let from = from.as_str().with_context(|| {
+                        format!("app-version-from for target '{}' must be a path", path)
+                    })?;
+                    target.app_version_from = Some(PathBuf::from(from));
+                }
+                if let Some(patterns) = settings.get("patterns") {
+                    let patterns = patterns
+                        .as_array()
+                        .and_then(|a| {
+                            a.iter()
+                                .map(|p| p.as_str().map(String::from))
+                                .collect::<Option<Vec<_>>>()
+                        })
+                        .with_context(|| {
+                            format!("patterns for target '{}' must be a list of strings", path)
+                        })?;
+                    for pattern in &patterns {
+                        docs::check_pattern(pattern)?;
+                    }
+                    target.patterns = patterns;
+                }
+                if let Some(image) = settings.get("image") {
+                    let image = image
+                        .as_str()
+                        .with_context(|| format!("image for target '{}' must be a string", path))?;
+                    target.image = Some(image.to_string());
+                }
+                if let Some(primary) = settings.get("primary") {
+                    target.primary = primary.as_bool().with_context(|| {
+                        format!("primary for target '{}' must be a boolean", path)
+                    })?;
+                }
+                config.targets.push((PathBuf::from(path), target));
+            }
+        }
+
+        if config.targets.iter().filter(|(_, t)| t.primary).count() > 1 {
+            bail!("only one target can be marked primary");
+        }
+
+        Ok(config)
+    }
+
+    /// Target marked `primary = true`, if any.
+    pub fn primary_target(&self) -> Option<&Path> {
+        self.targets
+            .iter()
+            .find(|(_, t)| t.primary)
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Settings for the target at `path` ("./" prefixes are ignored).
+    pub fn target(&self, path: &Path) -> Option<&TargetConfig> {
+        self.targets
+            .iter()
+            .find(|(p, _)| same_path(p, path))
+            .map(|(_, target)| target)
+    }
+
+    /// Prerelease channel for `branch`, or None for stable releases.
+    /// Unmapped branches and the "stable" channel produce stable versions.
+    pub fn channel_for(&self, branch: &str) -> Option<&str> {
+        self.channels
+            .iter()
+            .find(|(pattern, _)| glob_match(pattern, branch))
+            .map(|(_, channel)| channel.as_str())
+            .filter(|channel| !channel.is_empty() && *channel != "stable")
+    }
+}
+
+/// How bad a problem in oneup.toml is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    /// oneup refuses to load the file
+    Error,
+    /// oneup ignores the setting
+    Warning,
+}
+
+/// A problem in oneup.toml at a 1-based line and column
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, content: &str, offset: usize, message: String) -> Self {
+        let before = &content[..offset.min(content.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            severity,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message,
+        }
+    }
+
+    /// `path:line:column: message`, then the offending line with a caret
+    /// under the column.
+    pub fn render(&self, path: &Path, content: &str) -> String {
+        let mut out = format!(
+            "{}:{}:{}: {}",
+            path.display(),
+            self.line,
+            self.column,
+            self.message
+        );
+        if let Some(source) = content.lines().nth(self.line - 1) {
+            let number = self.line.to_string();
+            let gutter = " ".repeat(number.len());
+            out.push_str(&format!(
+                "\n {} | {}\n {} | {}^",
+                number,
+                source,
+                gutter,
+                " ".repeat(self.column - 1)
+            ));
+        }
+        out
+    }
+}
+
+/// Print the warnings of oneup.toml content `name`; fail on its errors.
+fn check(name: &Path, content: &str) -> Result<()> {
+    for diagnostic in validate(content, true) {
+        match diagnostic.severity {
+            Severity::Warning => eprintln!("warning: {}", diagnostic.render(name, content)),
+            Severity::Error => {
+                return Err(anyhow::anyhow!("{}", diagnostic.render(name, content)))
+                    .context(format!("invalid config {}", name.display()))
+                    .kind(ErrorKind::Config);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The config file at `path` as written and with its `extends` chain
+/// merged in, after printing its warnings and failing on its errors; None
+/// when there is no file.
+fn read_config(path: &Path) -> Result<Option<(DocumentMut, DocumentMut)>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    check(path, &content)?;
+    let doc: DocumentMut = content
+        .parse()
+        .with_context(|| format!("invalid config {}", path.display()))
+        .kind(ErrorKind::Config)?;
+    let merged = extend(
+        doc.clone(),
+        &Source::Local(path.to_path_buf()),
+        &mut Vec::new(),
+    )?;
+    Ok(Some((doc, merged)))
+}
+
+/// Where a config file in an `extends` chain comes from
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Local(PathBuf),
+    /// `github:owner/repo/path[@ref]`, read from the default branch without a ref
+    GitHub {
+        repo: String,
+        path: String,
+        rev: Option<String>,
+    },
+}
+
+impl Source {
+    /// The config `spec` names, relative to the file that extends it.
+    fn resolve(&self, spec: &str) -> Result<Source> {
+        if let Some(rest) = spec.strip_prefix("github:") {
+            let (rest, rev) = match rest.rsplit_once('@') {
+                Some((rest, rev)) if !rev.contains('/') => (rest, Some(rev.to_string())),
+                _ => (rest, None),
+            };
+            let mut parts = rest.splitn(3, '/');
+            let (Some(owner), Some(repo), Some(path)) = (parts.next(), parts.next(), parts.next())
+            else {
+                bail!(
+                    "invalid extends '{}': expected github:owner/repo/path/to/oneup.toml[@ref]",
+                    spec
+                );
+            };
+            return Ok(Source::GitHub {
+                repo: format!("{owner}/{repo}"),
+                path: normalize(path),
+                rev,
+            });
+        }
+        Ok(match self {
+            Source::Local(path) => {
+                Source::Local(path.parent().unwrap_or(Path::new(".")).join(spec))
+            }
+            // Relative to the extending file, in the same repository
+            Source::GitHub { repo, path, rev } => {
+                let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+                Source::GitHub {
+                    repo: repo.clone(),
+                    path: normalize(&format!("{dir}/{spec}")),
+                    rev: rev.clone(),
+                }
+            }
+        })
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Source::Local(path) => path.display().to_string(),
+            Source::GitHub { repo, path, rev } => match rev {
+                Some(rev) => format!("github:{repo}/{path}@{rev}"),
+                None => format!("github:{repo}/{path}"),
+            },
+        }
+    }
+
+    /// `name` with local paths canonicalized, so `./x/../oneup.toml` and
+    /// `oneup.toml` are seen as the same file when looking for cycles.
+    fn id(&self) -> String {
+        match self {
+            Source::Local(path) => std::fs::canonicalize(path)
+                .unwrap_or_else(|_| path.clone())
+                .display()
+                .to_string(),
+            Source::GitHub { .. } => self.name(),
+        }
+    }
+
+    fn read(&self) -> Result<String> {
+        match self {
+            Source::Local(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))
+                .kind(ErrorKind::Config),
+            Source::GitHub { repo, path, rev } => {
+                fetch_github(repo, path, rev.as_deref().unwrap_or("HEAD"))
+            }
+        }
+    }
+}
+
+/// A file from a GitHub repository (GITHUB_TOKEN for private ones).
+#[cfg(feature = "network")]
+fn fetch_github(repo: &str, path: &str, rev: &str) -> Result<String> {
+    let url = format!("https://raw.githubusercontent.com/{repo}/{rev}/{path}");
+    let mut req = crate::http::client().get(&url);
+    if let Ok(token) = std::env::var("GITHUB_TOKEN")
+        && !token.is_empty()
+    {
+        req = req.header("Authorization", format!("Bearer {token}"));
+    }
+    let resp = req
+        .send()
+        .with_context(|| format!("failed to fetch {}", url))
+        .kind(ErrorKind::Network)?;
+    match resp.status() {
+        status if status.is_success() => resp
+            .text()
+            .with_context(|| format!("failed to read {}", url))
+            .kind(ErrorKind::Network),
+        // Private repositories answer 404 without a token
+        reqwest::StatusCode::NOT_FOUND => Err(anyhow::anyhow!(
+            "{} not found in {} at {} (private repositories need GITHUB_TOKEN)",
+            path,
+            repo,
+            rev
+        ))
+        .kind(ErrorKind::Config),
+        status => Err(anyhow::anyhow!("failed to fetch {}: HTTP {}", url, status))
+            .kind(ErrorKind::Network),
+    }
+}
+
+#[cfg(not(feature = "network"))]
+fn fetch_github(_: &str, _: &str, _: &str) -> Result<String> {
+    Err(crate::error::not_built("network"))
+}
+
+/// `a/./b/../c` as `a/c`.
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// `doc` (read from `source`) on top of the config it `extends`, and so on
+/// up the chain: tables merge key by key, other values replace the base's.
+fn extend(doc: DocumentMut, source: &Source, chain: &mut Vec<String>) -> Result<DocumentMut> {
+    chain.push(source.id());
+    let Some(spec) = doc.get("extends").and_then(Item::as_str) else {
+        return Ok(doc);
+    };
+    let base_source = source.resolve(spec)?;
+    let name = base_source.name();
+    let id = base_source.id();
+    if chain.contains(&id) {
+        bail!("extends cycle: {} -> {}", chain.join(" -> "), id);
+    }
+    let content = base_source
+        .read()
+        .with_context(|| format!("failed to load {} (extended by {})", name, source.name()))?;
+    check(Path::new(&name), &content)?;
+    let base: DocumentMut = content
+        .parse()
+        .with_context(|| format!("invalid config {}", name))
+        .kind(ErrorKind::Config)?;
+    let mut base = extend(base, &base_source, chain)?;
+    merge(base.as_table_mut(), doc.as_table());
+    Ok(base)
+}
+
+/// Overlay `local` on `base`: tables (inline or not) recursively, everything
+/// else replaced.
+fn merge(base: &mut dyn TableLike, local: &dyn TableLike) {
+    for (key, item) in local.iter() {
+        match (
+            base.get_mut(key).and_then(Item::as_table_like_mut),
+            item.as_table_like(),
+        ) {
+            (Some(base), Some(local)) => merge(base, local),
+            _ => {
+                base.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+/// `doc` with every string value interpolated from the environment and
+/// the current branch (looked up once, and only when used).
+fn interpolate_document(doc: &DocumentMut) -> Result<DocumentMut> {
+    let branch = std::cell::OnceCell::new();
+    let current_branch = || {
+        branch
+            .get_or_init(|| {
+                CiInfo::detect()
+                    .and_then(|ci| ci.branch)
+                    .or_else(|| git::current_branch(Path::new(".")).ok().flatten())
+            })
+            .clone()
+            .context(
+                "{branch} is used, but there is no branch (detached HEAD or not a git repository)",
+            )
+    };
+    let env = |name: &str| std::env::var(name).ok();
+    expand_document(doc, &env, &current_branch)
+}
+
+fn expand_document(
+    doc: &DocumentMut,
+    env: &dyn Fn(&str) -> Option<String>,
+    branch: &dyn Fn() -> Result<String>,
+) -> Result<DocumentMut> {
+    let mut doc = doc.clone();
+    for (_, item) in doc.iter_mut() {
+        interpolate_item(item, env, branch)?;
+    }
+    Ok(doc)
+}
+
+fn interpolate_item(
+    item: &mut Item,
+    env: &dyn Fn(&str) -> Option<String>,
+    branch: &dyn Fn() -> Result<String>,
+) -> Result<()> {
+    match item {
+        Item::Value(value) => interpolate_value(value, env, branch),
+        Item::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                interpolate_item(item, env, branch)?;
+            }
+            Ok(())
+        }
+        Item::ArrayOfTables(tables) => {
+            for table in tables.iter_mut() {
+                for (_, item) in table.iter_mut() {
+                    interpolate_item(item, env, branch)?;
+                }
+            }
+            Ok(())
+        }
+        Item::None => Ok(()),
+    }
+}
+
+fn interpolate_value(
+    value: &mut toml_edit::Value,
+    env: &dyn Fn(&str) -> Option<String>,
+    branch: &dyn Fn() -> Result<String>,
+) -> Result<()> {
+    match value {
+        toml_edit::Value::String(s) => {
+            let expanded = interpolate(s.value(), env, branch)?;
+            if expanded != *s.value() {
+                *s = toml_edit::Formatted::new(expanded);
+            }
+        }
+        toml_edit::Value::Array(array) => {
+            for value in array.iter_mut() {
+                interpolate_value(value, env, branch)?;
+            }
+        }
+        toml_edit::Value::InlineTable(table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate_value(value, env, branch)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand a config string: `${NAME}` from the environment (an error when
+/// unset), `${NAME:-default}` (the default when unset or empty) and
+/// `{branch}`. `$${` is a literal `${` and `{{branch}}` a literal
+/// `{branch}`; other `{...}` are left for the setting to fill in.
+fn interpolate(
+    value: &str,
+    env: &dyn Fn(&str) -> Option<String>,
+    branch: &dyn Fn() -> Result<String>,
+) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(c) = rest.chars().next() {
+        if let Some(tail) = rest.strip_prefix("$${") {
+            out.push_str("${");
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("${") {
+            let end = tail
+                .find('}')
+                .with_context(|| format!("unclosed ${{ in '{}'", value))?;
+            let (name, default) = match tail[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&tail[..end], None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("invalid variable name '{}' in '{}'", name, value);
+            }
+            let expanded = match (env(name), default) {
+                (Some(v), Some(default)) if v.is_empty() => default.to_string(),
+                (Some(v), _) => v,
+                (None, Some(default)) => default.to_string(),
+                (None, None) => bail!(
+                    "${{{}}} is not set (write ${{{}:-default}} for a fallback, or $${{ for a literal ${{)",
+                    name,
+                    name
+                ),
+            };
+            out.push_str(&expanded);
+            rest = &tail[end + 1..];
+        } else if let Some(tail) = rest.strip_prefix("{{branch}}") {
+            out.push_str("{branch}");
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("{branch}") {
+            out.push_str(&branch()?);
+            rest = tail;
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Ok(out)
+}
+
+/// Registry URLs are the registry root: the package name is appended to
+/// them for every lookup, so a `{package}` in the URL would name it twice.
+fn check_registry_url(key: &str, url: &str) -> Result<()> {
+    if url.contains("{package}") {
+        bail!(
+            "'{}' entries are registry URLs and cannot use {{package}}: oneup appends the package name itself",
+            key
+        );
+    }
+    Ok(())
+}
+
+/// `value` with `{package}` replaced by `package` (`{{package}}` stays a
+/// literal `{package}`), for the announcement template.
+#[cfg(feature = "network")]
+pub fn with_package(value: &str, package: &str) -> String {
+    value
+        .split("{{package}}")
+        .map(|part| part.replace("{package}", package))
+        .collect::<Vec<_>>()
+        .join("{package}")
+}
+
+fn parse_mastodon(item: &Item) -> Result<MastodonConfig> {
+    let table = item
+        .as_table_like()
+        .context("'announce.mastodon' must be a table")?;
+    let string = |key: &str| -> Result<Option<String>> {
+        table
+            .get(key)
+            .map(|v| {
+                v.as_str()
+                    .map(String::from)
+                    .with_context(|| format!("announce.mastodon.{} must be a string", key))
+            })
+            .transpose()
+    };
+    let instance = string("instance")?
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+        .context("announce.mastodon.instance must be the server URL, e.g. https://fosstodon.org")?;
+    let visibility = string("visibility")?;
+    if let Some(visibility) = &visibility
+        && !["public", "unlisted", "private", "direct"].contains(&visibility.as_str())
+    {
+        bail!(
+            "announce.mastodon.visibility must be public, unlisted, private or direct, got '{}'",
+            visibility
+        );
+    }
+    Ok(MastodonConfig {
+        instance: instance.trim_end_matches('/').to_string(),
+        template: string("template")?,
+        visibility,
+    })
+}
+
+/// Check oneup.toml content against the settings oneup reads: TOML syntax,
+/// unknown keys, values of the wrong type or shape, and settings that
+/// cancel each other out. Each problem points at the key it concerns.
+///
+/// With `expand`, `${NAME}` and `{branch}` are expanded as a run would,
+/// so unset variables are reported. Without it the result depends on
+/// neither the environment nor git: placeholders are only checked for
+/// syntax, `${NAME:-default}` is checked with its default, and a setting
+/// that needs a variable or the branch is only known at run time.
+pub fn validate(content: &str, expand: bool) -> Vec<Diagnostic> {
+    let doc = match ImDocument::parse(content) {
+        Ok(doc) => doc,
+        Err(err) => {
+            let offset = err.span().map_or(0, |s| s.start);
+            let message = format!("invalid TOML: {}", err.message().trim_end());
+            return vec![Diagnostic::new(Severity::Error, content, offset, message)];
+        }
+    };
+    let offset = |key: Option<&Key>| key.and_then(|k| k.span()).map_or(0, |s| s.start);
+    // Invalid values are pointed at directly, tables at their key
+    let value_offset = |item: &Item, key: usize| match item {
+        Item::Value(value) => value.span().map_or(key, |s| s.start),
+        _ => key,
+    };
+    let mut diagnostics = Vec::new();
+    let mut report = |severity, offset, message| {
+        diagnostics.push(Diagnostic::new(severity, content, offset, message));
+    };
+    // Check a single setting by loading a config that holds only it
+    let check = |doc: DocumentMut| {
+        let result = if expand {
+            Config::from_document(&doc).map(drop)
+        } else {
+            let anything = |_: &str| Some(String::new());
+            let unset = |_: &str| None;
+            let no_branch = || Err(anyhow::anyhow!("no branch"));
+            expand_document(&doc, &anything, &|| Ok(String::new())).and_then(|_| {
+                match expand_document(&doc, &unset, &no_branch) {
+                    Ok(doc) => Config::parse_document(&doc).map(drop),
+                    Err(_) => Ok(()),
+                }
+            })
+        };
+        result.err().map(|e| format!("{:#}", e))
+    };
+
+    let mut primaries = Vec::new();
+    for (key, item) in doc.iter() {
+        let at = offset(doc.key(key));
+        if !KEYS.iter().any(|(k, _)| *k == key) {
+            let hint = suggest::did_you_mean(key, KEYS.iter().map(|(k, _)| *k));
+            report(
+                Severity::Warning,
+                at,
+                format!("unknown key '{}'{}", key, hint),
+            );
+            continue;
+        }
+        if key == "announce"
+            && let Some(mastodon) = item
+                .as_table_like()
+                .and_then(|t| t.get("mastodon"))
+                .and_then(Item::as_table_like)
+        {
+            for (setting, _) in mastodon.iter() {
+                if !MASTODON_KEYS.contains(&setting) {
+                    let hint = suggest::did_you_mean(setting, MASTODON_KEYS.iter().copied());
+                    report(
+                        Severity::Warning,
+                        offset(mastodon.key(setting)),
+                        format!("unknown key '{}' for announce.mastodon{}", setting, hint),
+                    );
+                }
+            }
+        }
+        if key == "policy"
+            && let Some(policy) = item.as_table_like()
+        {
+            for (setting, _) in policy.iter() {
+                if !POLICY_KEYS.contains(&setting) {
+                    let hint = suggest::did_you_mean(setting, POLICY_KEYS.iter().copied());
+                    report(
+                        Severity::Warning,
+                        offset(policy.key(setting)),
+                        format!("unknown key '{}' for policy{}", setting, hint),
+                    );
+                }
+            }
+        }
+        let targets = match item.as_table_like() {
+            Some(targets) if key == "targets" => targets,
+            _ => {
+                let mut single = DocumentMut::new();
+                single.insert(key, item.clone());
+                if let Some(message) = check(single) {
+                    report(Severity::Error, value_offset(item, at), message);
+                }
+                continue;
+            }
+        };
+
+        for (path, settings) in targets.iter() {
+            let at = offset(targets.key(path));
+            let Some(settings) = settings.as_table_like() else {
+                report(
+                    Severity::Error,
+                    at,
+                    format!("settings for target '{}' must be a table", path),
+                );
+                continue;
+            };
+            for (setting, value) in settings.iter() {
+                let at = offset(settings.key(setting));
+                if !TARGET_KEYS.contains(&setting) {
+                    let hint = suggest::did_you_mean(setting, TARGET_KEYS.iter().copied());
+                    report(
+                        Severity::Warning,
+                        at,
+                        format!("unknown key '{}' for target '{}'{}", setting, path, hint),
+                    );
+                    continue;
+                }
+                let mut target = Table::new();
+                target.insert(setting, value.clone());
+                let mut single_targets = Table::new();
+                single_targets.insert(path, Item::Table(target));
+                let mut single = DocumentMut::new();
+                single.insert("targets", Item::Table(single_targets));
+                if let Some(message) = check(single) {
+                    report(Severity::Error, value_offset(value, at), message);
+                    continue;
+                }
+                if setting == "primary" && value.as_bool() == Some(true) {
+                    primaries.push((path, at));
+                }
+                if setting == "app-version-from"
+                    && value
+                        .as_str()
+                        .is_some_and(|from| same_path(Path::new(from), Path::new(path)))
+                {
+                    report(
+                        Severity::Warning,
+                        at,
+                        format!("target '{}' takes its app version from itself", path),
+                    );
+                }
+            }
+
+            // read_targets picks the first of these that is set
+            let kinds = ["patterns", "image", "template"];
+            let set: Vec<&str> = kinds
+                .into_iter()
+                .filter(|k| settings.contains_key(k))
+                .collect();
+            for ignored in set.iter().skip(1) {
+                report(
+                    Severity::Warning,
+                    offset(settings.key(ignored)),
+                    format!(
+                        "'{}' for target '{}' is ignored because '{}' is set",
+                        ignored, path, set[0]
+                    ),
+                );
+            }
+        }
+    }
+
+    if let [(first, _), rest @ ..] = primaries.as_slice() {
+        for (path, at) in rest {
+            report(
+                Severity::Error,
+                *at,
+                format!(
+                    "only one target can be marked primary: '{}' conflicts with '{}'",
+                    path, first
+                ),
+            );
+        }
+    }
+
+    diagnostics
+}
+
+/// Whether two relative paths name the same file, ignoring "./" components.
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    let significant = |c: &Component| *c != Component::CurDir;
+    a.components()
+        .filter(significant)
+        .eq(b.components().filter(significant))
+}
+
+/// Match `text` against a pattern where `*` matches any sequence of characters.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=text.len())
+                .filter(|i| text.is_char_boundary(*i))
+                .any(|i| glob_match(rest, &text[i..]))
+        }
+    }
+}
+
+pub fn run(args: ConfigArgs) -> Result<()> {
+    let path = Path::new(CONFIG_FILE);
+    match args.action {
+        ConfigAction::Get { key } => {
+            let doc = read_document(path)?;
+            let keys = parse_key(&key)?;
+            let item = lookup(&doc, &keys)
+                .with_context(|| format!("{} is not set in {}", key, CONFIG_FILE))?;
+            match item.as_value() {
+                // Strings print bare so scripts can use them as is
+                Some(value) => match value.as_str() {
+                    Some(s) => println!("{}", s),
+                    None => println!("{}", value_repr(value)),
+                },
+                None => {
+                    for (k, v) in entries(item, Some(key_repr(&keys))) {
+                        println!("{} = {}", k, v);
+                    }
+                }
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let mut doc = read_document(path)?;
+            set_key(&mut doc, &key, &value)?;
+            std::fs::write(path, doc.to_string())
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        ConfigAction::List => {
+            let doc = read_document(path)?;
+            for (k, v) in entries(doc.as_item(), None) {
+                println!("{} = {}", k, v);
+            }
+        }
+        ConfigAction::Effective => {
+            let (doc, merged) = read_config(path)?.unwrap_or_default();
+            // Surface config errors before listing anything
+            Config::from_merged(path, &merged)?;
+            let frozen = Path::new(FREEZE_FILE).exists();
+            for (k, v, source) in effective(&doc, &merged, frozen) {
+                println!("{} = {} ; {}", k, v, source);
+            }
+        }
+        ConfigAction::Validate { expand } => {
+            if !path.exists() {
+                println!("{} not found, defaults apply", CONFIG_FILE);
+                return Ok(());
+            }
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let diagnostics = validate(&content, expand);
+            for diagnostic in &diagnostics {
+                let label = match diagnostic.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                eprintln!("{}: {}", label, diagnostic.render(path, &content));
+            }
+            if !diagnostics.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "{} has {} problem(s)",
+                    CONFIG_FILE,
+                    diagnostics.len()
+                ))
+                .kind(ErrorKind::Config);
+            }
+            println!("{} is valid", CONFIG_FILE);
+        }
+    }
+    Ok(())
+}
+
+/// oneup.toml as an editable document; a missing file is an empty one.
+fn read_document(path: &Path) -> Result<DocumentMut> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    content
+        .parse()
+        .with_context(|| format!("invalid config {}: invalid TOML", path.display()))
+}
+
+/// Split a dotted key (`targets."Chart.yaml".format`) and check it names a
+/// setting oneup reads, so typos fail instead of being silently ignored.
+fn parse_key(key: &str) -> Result<Vec<Key>> {
+    let keys = Key::parse(key).with_context(|| format!("invalid key '{}'", key))?;
+    let names: Vec<&str> = keys.iter().map(|k| k.get()).collect();
+    let known = match names.as_slice() {
+        [top] => KEYS.iter().any(|(k, _)| k == top),
+        ["channels", _] | ["targets", _] => true,
+        ["targets", _, setting] => TARGET_KEYS.contains(setting),
+        ["policy", setting] => POLICY_KEYS.contains(setting),
+        ["announce", "mastodon"] => true,
+        ["announce", "mastodon", setting] => MASTODON_KEYS.contains(setting),
+        _ => false,
+    };
+    if !known {
+        let hint = match names.as_slice() {
+            [top] => suggest::did_you_mean(top, KEYS.iter().map(|(k, _)| *k)),
+            ["targets", _, setting] => suggest::did_you_mean(setting, TARGET_KEYS.iter().copied()),
+            ["policy", setting] => suggest::did_you_mean(setting, POLICY_KEYS.iter().copied()),
+            _ => String::new(),
+        };
+        bail!(
+            "unknown key '{}'{} (expected one of {}, channels.<branch> or targets.<path>.<{}>)",
+            key,
+            hint,
+            KEYS.iter().map(|(k, _)| *k).collect::<Vec<_>>().join(", "),
+            TARGET_KEYS.join("|")
+        );
+    }
+    Ok(keys)
+}
+
+/// The item stored at a dotted key, if set.
+fn lookup<'a>(doc: &'a DocumentMut, keys: &[Key]) -> Option<&'a Item> {
+    keys.iter()
+        .try_fold(doc.as_item(), |item, k| item.as_table_like()?.get(k.get()))
+}
+
+/// Set `key` to `value`, read as TOML (`true`, `13`, `["a"]`) and otherwise
+/// taken as a string. Missing tables are created; the result must still be
+/// a valid config, or `doc` is left untouched. Comments and formatting
+/// elsewhere are kept.
+fn set_key(doc: &mut DocumentMut, key: &str, value: &str) -> Result<()> {
+    let keys = parse_key(key)?;
+    let value: toml_edit::Value = value
+        .parse()
+        .unwrap_or_else(|_| toml_edit::Value::from(value));
+
+    let (last, parents) = keys.split_last().context("empty key")?;
+    let mut updated = doc.clone();
+    let mut table: &mut dyn TableLike = updated.as_table_mut();
+    for k in parents {
+        let item = table.entry(k.get()).or_insert_with(|| {
+            let mut t = toml_edit::Table::new();
+            t.set_implicit(true);
+            Item::Table(t)
+        });
+        table = item
+            .as_table_like_mut()
+            .with_context(|| format!("'{}' is not a table", k.get()))?;
+    }
+    table.insert(last.get(), Item::Value(value));
+
+    Config::parse(&updated.to_string()).with_context(|| format!("cannot set {}", key))?;
+    *doc = updated;
+    Ok(())
+}
+
+/// Flatten a TOML item into dotted `key = value` pairs.
+fn entries(item: &Item, prefix: Option<String>) -> Vec<(String, String)> {
+    let Some(table) = item.as_table_like().filter(|_| !item.is_array()) else {
+        return match (prefix, item.as_value()) {
+            (Some(key), Some(value)) => vec![(key, value_repr(value))],
+            _ => Vec::new(),
+        };
+    };
+    table
+        .iter()
+        .flat_map(|(k, v)| {
+            let k = Key::new(k).display_repr().into_owned();
+            let key = match &prefix {
+                Some(p) => format!("{}.{}", p, k),
+                None => k,
+            };
+            entries(v, Some(key))
+        })
+        .collect()
+}
+
+/// A value on one line, without its comments or original layout.
+fn value_repr(value: &toml_edit::Value) -> String {
+    let mut value = value.clone();
+    value.decor_mut().clear();
+    match &mut value {
+        toml_edit::Value::Array(a) => a.fmt(),
+        toml_edit::Value::InlineTable(t) => t.fmt(),
+        _ => {}
+    }
+    value.to_string()
+}
+
+fn key_repr(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|k| Key::new(k.get()).display_repr().into_owned())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Every setting with its effective value and source: oneup.toml keys
+/// (file, .oneup-freeze or default), then the `oneup version` flags
+/// (ONEUP_* environment variable or default).
+fn effective(
+    doc: &DocumentMut,
+    merged: &DocumentMut,
+    frozen: bool,
+) -> Vec<(String, String, String)> {
+    // Settings oneup.toml doesn't set itself come from what it extends
+    let local: HashSet<String> = entries(doc.as_item(), None)
+        .into_iter()
+        .map(|(k, _)| k)
+        .collect();
+    let inherited = doc
+        .get("extends")
+        .and_then(Item::as_str)
+        .unwrap_or(CONFIG_FILE);
+    let mut lines = Vec::new();
+    for (key, default) in KEYS {
+        if *key == "freeze" && frozen {
+            let value = toml_edit::Value::from(FREEZE_FILE);
+            lines.push((key.to_string(), value_repr(&value), FREEZE_FILE.to_string()));
+            continue;
+        }
+        match merged.get(key) {
+            Some(item) => {
+                for (k, v) in entries(item, Some(key.to_string())) {
+                    let source = if local.contains(&k) {
+                        CONFIG_FILE
+                    } else {
+                        inherited
+                    };
+                    lines.push((k, v, source.to_string()));
+                }
+            }
+            None => lines.push((key.to_string(), default.to_string(), "default".to_string())),
+        }
+    }
+
+    let mut cli = Cli::command();
+    cli.build();
+    let Some(version) = cli.find_subcommand("version") else {
+        return lines;
+    };
+    for arg in version.get_arguments() {
+        let (Some(long), Some(env)) = (arg.get_long(), arg.get_env()) else {
+            continue;
+        };
+        let (value, source) = match std::env::var_os(env) {
+            Some(value) => (value, env.to_string_lossy().into_owned()),
+            None => match arg.get_default_values().first() {
+                Some(value) => (value.to_os_string(), "default".to_string()),
+                None => continue,
+            },
+        };
+        let value = value.to_string_lossy();
+        let value = if arg.get_action().takes_values() {
+            value_repr(&toml_edit::Value::from(value.as_ref()))
+        } else {
+            value.into_owned()
+        };
+        lines.push((format!("--{}", long), value, source));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get_keys() {
+        let mut doc: DocumentMut = "# release settings\nskip = [13]\n".parse().unwrap();
+        set_key(&mut doc, "hotfix-segment", "patch").unwrap();
+        set_key(&mut doc, "freeze", "true").unwrap();
+        set_key(&mut doc, "targets.\"Chart.yaml\".format", "YY.MM.MICRO").unwrap();
+        set_key(&mut doc, "channels.develop", "beta").unwrap();
+
+        let content = doc.to_string();
+        assert!(content.starts_with("# release settings\nskip = [13]\n"));
+        assert!(content.contains("hotfix-segment = \"patch\""));
+        assert!(content.contains("freeze = true"));
+        assert!(content.contains("[targets.\"Chart.yaml\"]\nformat = \"YY.MM.MICRO\""));
+        assert!(!content.contains("[targets]"));
+
+        let config = Config::parse(&content).unwrap();
+        assert_eq!(config.hotfix_segment, "patch");
+        assert_eq!(config.channel_for("develop"), Some("beta"));
+
+        let keys = parse_key("targets.\"Chart.yaml\".format").unwrap();
+        let item = lookup(&doc, &keys).unwrap();
+        assert_eq!(item.as_str(), Some("YY.MM.MICRO"));
+        assert!(lookup(&doc, &parse_key("registry-mirrors").unwrap()).is_none());
+    }
+
+    #[test]
+    fn set_rejects_unknown_keys_and_invalid_values() {
+        let mut doc = DocumentMut::new();
+        assert!(set_key(&mut doc, "hotfix-segmnt", "patch").is_err());
+        assert!(set_key(&mut doc, "targets.a.formt", "YY").is_err());
+        let err = set_key(&mut doc, "hotfix-segmnt", "patch").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("unknown key 'hotfix-segmnt' (did you mean 'hotfix-segment'?)")
+        );
+        let err = set_key(&mut doc, "targets.a.formt", "YY").unwrap_err();
+        assert!(err.to_string().contains("(did you mean 'format'?)"));
+        assert!(set_key(&mut doc, "hotfix-segment", "has space").is_err());
+        assert!(set_key(&mut doc, "skip", "[true]").is_err());
+        set_key(&mut doc, "skip", "[\"26.2.3\", 13]").unwrap();
+        assert_eq!(doc.to_string(), "skip = [\"26.2.3\", 13]\n");
+    }
+
+    #[test]
+    fn list_and_effective_entries() {
+        let doc: DocumentMut = r#"skip = [ 13,
+  "26.2.3" ] # never
+[channels]
+main = "stable"
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            entries(doc.as_item(), None),
+            vec![
+                ("skip".to_string(), "[13, \"26.2.3\"]".to_string()),
+                ("channels.main".to_string(), "\"stable\"".to_string()),
+            ]
+        );
+
+        let lines = effective(&doc, &doc, true);
+        let find = |key: &str| lines.iter().find(|(k, _, _)| k == key).unwrap();
+        assert_eq!(find("skip").2, "oneup.toml");
+        assert_eq!(find("hotfix-segment").1, "\"hotfix\"");
+        assert_eq!(find("hotfix-segment").2, "default");
+        assert_eq!(find("freeze").2, FREEZE_FILE);
+        assert!(lines.iter().any(|(k, _, _)| k == "--format"));
+    }
+
+    #[test]
+    fn parse_empty_config() {
+        let config = Config::parse("").unwrap();
+        assert!(config.skip.is_empty());
+    }
+
+    #[test]
+    fn parse_skip_versions_and_micros() {
+        let config = Config::parse(r#"skip = ["26.2.3", 13]"#).unwrap();
+        assert_eq!(
+            config.skip,
+            vec![Skip::Version("26.2.3".to_string()), Skip::Micro(13)]
+        );
+    }
+
+    #[test]
+    fn parse_skip_rejects_bad_entries() {
+        assert!(Config::parse("skip = [-1]").is_err());
+        assert!(Config::parse("skip = [true]").is_err());
+        assert!(Config::parse(r#"skip = "26.2.3""#).is_err());
+    }
+
+    #[test]
+    fn skip_matches_version_or_micro() {
+        let version = Skip::Version("26.2.3".to_string());
+        let micro = Skip::Micro(13);
+        assert!(version.matches("26.2.3", Some(3)));
+        assert!(!version.matches("26.2.4", Some(4)));
+        assert!(micro.matches("26.3.13", Some(13)));
+        assert!(!micro.matches("26.2.0", None));
+    }
+
+    #[test]
+    fn parse_channels_in_order() {
+        let config = Config::parse(
+            r#"
+[channels]
+main = "stable"
+develop = "beta"
+"release/*" = "rc"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.channels.len(), 3);
+        assert_eq!(
+            config.channels[2],
+            ("release/*".to_string(), "rc".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_channels_rejects_non_string() {
+        assert!(
+            Config::parse(
+                "[channels]
+main = 1"
+            )
+            .is_err()
+        );
+        assert!(Config::parse("channels = 1").is_err());
+    }
+
+    #[test]
+    fn channel_for_branch() {
+        let config = Config::parse(
+            r#"channels = { main = "stable", develop = "beta", "release/*" = "rc" }"#,
+        )
+        .unwrap();
+        assert_eq!(config.channel_for("main"), None);
+        assert_eq!(config.channel_for("develop"), Some("beta"));
+        assert_eq!(config.channel_for("release/26.2"), Some("rc"));
+        assert_eq!(config.channel_for("feature/x"), None);
+    }
+
+    #[test]
+    fn parse_allowed_windows() {
+        let config = Config::parse(
+            r#"allowed-windows = ["Mon-Thu 09:00-17:00 Europe/Berlin", "Fri 09:00-12:00"]"#,
+        )
+        .unwrap();
+        assert_eq!(config.allowed_windows.len(), 2);
+        assert!(Config::parse(r#"allowed-windows = ["whenever"]"#).is_err());
+        assert!(Config::parse("allowed-windows = [1]").is_err());
+    }
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("release/*", "release/1"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("feat/*/wip", "feat/a/b/wip"));
+        assert!(!glob_match("release/*", "releases/1"));
+        assert!(!glob_match("main", "main2"));
+    }
+
+    #[test]
+    fn load_missing_file_is_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert!(config.skip.is_empty());
+        assert!(config.freeze.is_none());
+    }
+
+    #[test]
+    fn parse_freeze_flag_or_reason() {
+        assert!(Config::parse("freeze = false").unwrap().freeze.is_none());
+        assert!(Config::parse("freeze = true").unwrap().freeze.is_some());
+        assert_eq!(
+            Config::parse(r#"freeze = "incident 42""#)
+                .unwrap()
+                .freeze
+                .as_deref(),
+            Some("incident 42")
+        );
+        assert!(Config::parse("freeze = 1").is_err());
+    }
+
+    #[test]
+    fn parse_registry_mirrors() {
+        let config = Config::parse(
+            r#"registry-mirrors = ["https://npm-eu.example.com", "https://npm-us.example.com"]"#,
+        )
+        .unwrap();
+        assert_eq!(config.registry_mirrors.len(), 2);
+        assert!(Config::parse("registry-mirrors = [1]").is_err());
+        assert!(Config::parse(r#"registry-mirrors = "https://x""#).is_err());
+    }
+
+    #[test]
+    fn parse_announce_mastodon() {
+        let config = Config::parse(
+            "[announce.mastodon]\ninstance = \"https://fosstodon.org/\"\nvisibility = \"unlisted\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.mastodon,
+            Some(MastodonConfig {
+                instance: "https://fosstodon.org".to_string(),
+                template: None,
+                visibility: Some("unlisted".to_string()),
+            })
+        );
+        assert_eq!(Config::parse("").unwrap().mastodon, None);
+        assert!(Config::parse("[announce.mastodon]\ntemplate = \"x\"\n").is_err());
+        assert!(
+            Config::parse(
+                "[announce.mastodon]\ninstance = \"https://a.b\"\nvisibility = \"loud\"\n"
+            )
+            .is_err()
+        );
+
+        let diagnostics = validate(
+            "[announce.mastodon]\ninstance = \"https://a.b\"\ntempalte = \"x\"\n",
+            false,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("did you mean 'template'"));
+    }
+
+    #[test]
+    fn policy_table_is_read_and_checked() {
+        // The rules themselves are covered in policy::tests
+        let config = Config::parse("[policy]\nmax-micro = 49\n").unwrap();
+        assert_eq!(config.policy.max_micro, Some(49));
+        assert!(parse_key("policy.release-branches").is_ok());
+        assert!(parse_key("policy.max-minor").is_err());
+
+        let diagnostics = validate("[policy]\nblocked-day = [1]\n", false);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("did you mean 'blocked-days'")
+        );
+    }
+
+    #[test]
+    fn extends_merges_base_and_local() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("shared")).unwrap();
+        std::fs::write(
+            dir.path().join("shared/org.toml"),
+            "extends = \"base.toml\"\nhotfix-segment = \"patch\"\n\n[channels]\nmain = \"stable\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("shared/base.toml"),
+            "skip = [13]\nclock-skew-hours = 4\n\n[channels]\ndevelop = \"beta\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "extends = \"shared/org.toml\"\nclock-skew-hours = 30\n\n[channels]\nmain = \"rc\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.skip, vec![Skip::Micro(13)]);
+        assert_eq!(config.hotfix_segment, "patch");
+        assert_eq!(config.clock_skew_hours, 30);
+        assert_eq!(
+            config.channels,
+            vec![
+                ("develop".to_string(), "beta".to_string()),
+                ("main".to_string(), "rc".to_string()),
+            ]
+        );
+
+        let path = dir.path().join(CONFIG_FILE);
+        let doc = read_document(&path).unwrap();
+        let merged = extend(doc.clone(), &Source::Local(path), &mut Vec::new()).unwrap();
+        let lines = effective(&doc, &merged, false);
+        let find = |key: &str| lines.iter().find(|(k, _, _)| k == key).unwrap();
+        assert_eq!(find("skip").2, "shared/org.toml");
+        assert_eq!(find("clock-skew-hours").2, CONFIG_FILE);
+        assert_eq!(find("channels.develop").2, "shared/org.toml");
+        assert_eq!(find("channels.main").2, CONFIG_FILE);
+    }
+
+    #[test]
+    fn extends_cycle_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.toml"), "extends = \"oneup.toml\"\n").unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), "extends = \"a.toml\"\n").unwrap();
+        let err = format!("{:#}", Config::load(dir.path()).err().unwrap());
+        assert!(err.contains("extends cycle"), "{}", err);
+
+        std::fs::write(dir.path().join(CONFIG_FILE), "extends = \"missing.toml\"\n").unwrap();
+        assert!(Config::load(dir.path()).is_err());
+
+        // The same file under another spelling is still a cycle
+        std::fs::create_dir(dir.path().join("x")).unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "extends = \"./x/../oneup.toml\"\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", Config::load(dir.path()).err().unwrap());
+        assert!(err.contains("extends cycle"), "{}", err);
+    }
+
+    #[test]
+    fn extends_merges_inline_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.toml"),
+            "channels = { develop = \"beta\" }\n\n[targets.\"Cargo.toml\"]\nformat = \"YY.MM.MICRO\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "extends = \"base.toml\"\n\n[channels]\nmain = \"stable\"\n\n[targets]\n\"Cargo.toml\" = { primary = true }\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(
+            config.channels,
+            vec![
+                ("develop".to_string(), "beta".to_string()),
+                ("main".to_string(), "stable".to_string()),
+            ]
+        );
+        let target = config.target(Path::new("Cargo.toml")).unwrap();
+        assert_eq!(target.format.as_deref(), Some("YY.MM.MICRO"));
+        assert!(target.primary);
+    }
+
+    #[test]
+    fn extends_sources() {
+        let local = Source::Local(PathBuf::from("./oneup.toml"));
+        assert_eq!(
+            local
+                .resolve("github:circlesac/release-config/oneup.toml@v1")
+                .unwrap(),
+            Source::GitHub {
+                repo: "circlesac/release-config".to_string(),
+                path: "oneup.toml".to_string(),
+                rev: Some("v1".to_string()),
+            }
+        );
+        let shared = local
+            .resolve("github:circlesac/release-config/teams/web.toml")
+            .unwrap();
+        assert_eq!(
+            shared.name(),
+            "github:circlesac/release-config/teams/web.toml"
+        );
+        assert_eq!(
+            shared.resolve("../org.toml").unwrap().name(),
+            "github:circlesac/release-config/org.toml"
+        );
+        assert_eq!(
+            local.resolve("../shared.toml").unwrap(),
+            Source::Local(PathBuf::from("./../shared.toml"))
+        );
+        assert!(local.resolve("github:circlesac/oneup.toml").is_err());
+        assert!(Config::parse("extends = 1").is_err());
+    }
+
+    #[test]
+    fn interpolate_env_and_branch() {
+        let env = |name: &str| match name {
+            "REGISTRY" => Some("https://npm.example.com".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        let branch = || Ok("release/26.10".to_string());
+        let expand = |value| interpolate(value, &env, &branch);
+
+        assert_eq!(
+            expand("${REGISTRY}/npm").unwrap(),
+            "https://npm.example.com/npm"
+        );
+        assert_eq!(expand("${MISSING:-https://x}").unwrap(), "https://x");
+        assert_eq!(expand("${EMPTY:-fallback}").unwrap(), "fallback");
+        assert_eq!(expand("${EMPTY}").unwrap(), "");
+        assert_eq!(
+            expand("frozen on {branch}").unwrap(),
+            "frozen on release/26.10"
+        );
+        assert_eq!(expand("$${HOME} {{branch}}").unwrap(), "${HOME} {branch}");
+        // Setting placeholders are left alone
+        assert_eq!(
+            expand("v{version} {package}").unwrap(),
+            "v{version} {package}"
+        );
+
+        let err = expand("${MISSING}").unwrap_err().to_string();
+        assert!(err.contains("${MISSING} is not set"), "{}", err);
+        assert!(expand("${UNCLOSED").is_err());
+        assert!(expand("${NOT-A-NAME}").is_err());
+
+        let no_branch = || Err(anyhow::anyhow!("no branch"));
+        assert!(interpolate("{branch}", &env, &no_branch).is_err());
+        assert_eq!(interpolate("main", &env, &no_branch).unwrap(), "main");
+    }
+
+    #[test]
+    fn interpolate_config_values() {
+        // cargo sets CARGO_PKG_NAME for the test process
+        let config = Config::parse(
+            "registry-mirrors = [\"https://${CARGO_PKG_NAME}.example.com\"]\nfreeze = \"$${literal}\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.registry_mirrors, vec!["https://oneup.example.com"]);
+        assert_eq!(config.freeze.as_deref(), Some("${literal}"));
+
+        let unset = "freeze = \"${ONEUP_TEST_UNSET_VARIABLE}\"\n";
+        let diagnostics = validate(unset, true);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].column, 10);
+    }
+
+    #[test]
+    fn validate_leaves_placeholders_to_the_run() {
+        let content =
+            "hotfix-segment = \"${ONEUP_TEST_UNSET_VARIABLE}\"\n[channels]\n\"*\" = \"{branch}\"\n";
+        assert!(validate(content, false).is_empty());
+
+        // Defaults are checked, and placeholders must be well formed
+        let content =
+            "hotfix-segment = \"${ONEUP_TEST_UNSET_VARIABLE:-not ok}\"\nfreeze = \"${UNCLOSED\"\n";
+        let diagnostics = validate(content, false);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].line, 1);
+        assert_eq!(diagnostics[1].line, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "network")]
+    fn with_package_placeholder() {
+        assert_eq!(
+            with_package("https://npm.example.com/{package}", "@scope/app"),
+            "https://npm.example.com/@scope/app"
+        );
+        assert_eq!(
+            with_package("{{package}} is {package}", "app"),
+            "{package} is app"
+        );
+    }
+
+    #[test]
+    fn parse_publish_registries() {
+        let config =
+            Config::parse(r#"publish-registries = ["https://npm.pkg.github.com"]"#).unwrap();
+        assert_eq!(
+            config.publish_registries,
+            vec!["https://npm.pkg.github.com"]
+        );
+        assert!(Config::parse("publish-registries = [1]").is_err());
+        let err = Config::parse(r#"registry-mirrors = ["https://npm.example.com/{package}"]"#)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("cannot use {package}"), "{}", err);
+        assert!(
+            Config::parse(r#"publish-registries = ["https://{package}.example.com"]"#).is_err()
+        );
+        assert!(Config::parse("").unwrap().publish_registries.is_empty());
+    }
+
+    #[test]
+    fn parse_hotfix_segment() {
+        assert_eq!(Config::parse("").unwrap().hotfix_segment, "hotfix");
+        assert_eq!(
+            Config::parse(r#"hotfix-segment = "patch""#)
+                .unwrap()
+                .hotfix_segment,
+            "patch"
+        );
+        assert!(Config::parse(r#"hotfix-segment = "a.b""#).is_err());
+        assert!(Config::parse(r#"hotfix-segment = """#).is_err());
+    }
+
+    #[test]
+    fn parse_max_response_mb() {
+        assert_eq!(Config::parse("").unwrap().max_response_mb, 256);
+        assert_eq!(
+            Config::parse("max-response-mb = 1024")
+                .unwrap()
+                .max_response_mb,
+            1024
+        );
+        assert!(Config::parse("max-response-mb = 0").is_err());
+    }
+
+    #[test]
+    fn parse_keep_backups() {
+        assert_eq!(Config::parse("").unwrap().keep_backups, 10);
+        assert_eq!(Config::parse("keep-backups = 0").unwrap().keep_backups, 0);
+        assert!(Config::parse("keep-backups = -1").is_err());
+    }
+
+    #[test]
+    fn parse_clock_skew_hours() {
+        assert_eq!(Config::parse("").unwrap().clock_skew_hours, 26);
+        assert_eq!(
+            Config::parse("clock-skew-hours = 0")
+                .unwrap()
+                .clock_skew_hours,
+            0
+        );
+        assert!(Config::parse("clock-skew-hours = -1").is_err());
+        assert!(Config::parse(r#"clock-skew-hours = "26""#).is_err());
+    }
+
+    #[test]
+    fn parse_target_formats() {
+        let config = Config::parse(
+            r#"
+[targets."Cargo.toml"]
+format = "YY.MM.MICRO"
+
+[targets."app/package.json"]
+format = "YYYY.MM.DD"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.target(Path::new("./app/package.json")),
+            Some(&TargetConfig {
+                format: Some("YYYY.MM.DD".to_string()),
+                ..Default::default()
+            })
+        );
+        assert!(config.target(Path::new("package.json")).is_none());
+        let config =
+            Config::parse(r#"targets = { "VERSION.json" = { publish-name = "@org/app" } }"#)
+                .unwrap();
+        assert_eq!(
+            config
+                .target(Path::new("VERSION.json"))
+                .unwrap()
+                .publish_name
+                .as_deref(),
+            Some("@org/app")
+        );
+        let config = Config::parse(
+            r#"targets = { "chart/Chart.yaml" = { app-version-from = "package.json" } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config
+                .target(Path::new("chart/Chart.yaml"))
+                .unwrap()
+                .app_version_from,
+            Some(PathBuf::from("package.json"))
+        );
+        let config =
+            Config::parse(r#"targets = { "README.md" = { patterns = ["my-tool@{version}"] } }"#)
+                .unwrap();
+        assert_eq!(
+            config.target(Path::new("README.md")).unwrap().patterns,
+            vec!["my-tool@{version}".to_string()]
+        );
+        assert!(Config::parse(r#"targets = { "README.md" = { patterns = ["x"] } }"#).is_err());
+        let config =
+            Config::parse(r#"targets = { "compose.yaml" = { image = "ghcr.io/org/app" } }"#)
+                .unwrap();
+        assert_eq!(
+            config
+                .target(Path::new("compose.yaml"))
+                .unwrap()
+                .image
+                .as_deref(),
+            Some("ghcr.io/org/app")
+        );
+        assert!(Config::parse(r#"targets = { "a.json" = 1 }"#).is_err());
+        assert!(Config::parse(r#"targets = { "a.json" = { format = 1 } }"#).is_err());
+    }
+
+    #[test]
+    fn parse_primary_target() {
+        let config = Config::parse(r#"targets = { "a/Cargo.toml" = { primary = true } }"#).unwrap();
+        assert_eq!(config.primary_target(), Some(Path::new("a/Cargo.toml")));
+        assert!(Config::parse("").unwrap().primary_target().is_none());
+        assert!(
+            Config::parse(
+                r#"targets = { "a.json" = { primary = true }, "b.json" = { primary = true } }"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn load_freeze_file_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(FREEZE_FILE),
+            "INC-1234: database migration\n",
+        )
+        .unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(
+            config.freeze.as_deref(),
+            Some("INC-1234: database migration")
+        );
+
+        std::fs::write(dir.path().join(FREEZE_FILE), "").unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.freeze.as_deref(), Some(".oneup-freeze is present"));
+    }
+
+    #[test]
+    fn validate_points_at_problems() {
+        let content = "skip = [13]\nhotfix-segmnt = \"patch\"\nfreeze = 1\n\n[targets.\"a.json\"]\nprimary = true\nformt = \"YY\"\n\n[targets.\"b.json\"]\nprimary = true\n";
+        let found: Vec<(Severity, usize, usize, String)> = validate(content, false)
+            .into_iter()
+            .map(|d| (d.severity, d.line, d.column, d.message))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    Severity::Warning,
+                    2,
+                    1,
+                    "unknown key 'hotfix-segmnt' (did you mean 'hotfix-segment'?)".to_string()
+                ),
+                (
+                    Severity::Error,
+                    3,
+                    10,
+                    "'freeze' must be a boolean or a reason string".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    7,
+                    1,
+                    "unknown key 'formt' for target 'a.json' (did you mean 'format'?)".to_string()
+                ),
+                (
+                    Severity::Error,
+                    10,
+                    1,
+                    "only one target can be marked primary: 'b.json' conflicts with 'a.json'"
+                        .to_string()
+                ),
+            ]
+        );
+        assert!(
+            validate(
+                "skip = [13]\n[targets.\"a.json\"]\nformat = \"YY\"\n",
+                false
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn validate_reports_syntax_and_ignored_settings() {
+        let diagnostics = validate("skip = [13\n", false);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, 2);
+        assert!(diagnostics[0].message.starts_with("invalid TOML"));
+
+        let content = "[targets.\"README.md\"]\npatterns = [\"v{version}\"]\ntemplate = \"x\"\n";
+        let diagnostics = validate(content, false);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "'template' for target 'README.md' is ignored because 'patterns' is set"
+        );
+        assert_eq!(
+            diagnostics[0].render(Path::new("oneup.toml"), content),
+            "oneup.toml:3:1: 'template' for target 'README.md' is ignored because 'patterns' is set\n 3 | template = \"x\"\n   | ^"
+        );
+    }
+
+    #[test]
+    fn load_fails_with_location_and_warns_on_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "timezone = 1\nskip = [true]\n",
+        )
+        .unwrap();
+        let err = Config::load(dir.path()).err().unwrap();
+        assert!(format!("{:#}", err).contains("oneup.toml:2:8: 'skip' entries must be"));
+
+        std::fs::write(dir.path().join(CONFIG_FILE), "timezone = 1\n").unwrap();
+        assert!(Config::load(dir.path()).is_ok());
+    }
+}
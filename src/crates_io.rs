@@ -1,5 +1,8 @@
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow};
 
+use crate::error::{ErrorKind, ResultExt};
+use crate::http;
+use crate::redact::redact;
 use crate::registry::PackageInfo;
 
 pub struct CratesIoClient {
@@ -10,10 +13,7 @@ pub struct CratesIoClient {
 impl CratesIoClient {
     pub fn new(registry_url: Option<&str>) -> Self {
         Self {
-            http: reqwest::blocking::Client::builder()
-                .user_agent("oneup (https://github.com/circlesac/oneup)")
-                .build()
-                .expect("failed to build HTTP client"),
+            http: http::client(),
             registry_url: registry_url
                 .unwrap_or("https://crates.io")
                 .trim_end_matches('/')
@@ -25,14 +25,15 @@ impl CratesIoClient {
         let url = format!("{}/api/v1/crates/{}", self.registry_url, crate_name);
 
         if verbose {
-            eprintln!("[registry] GET {}", url);
+            eprintln!("[registry] GET {}", redact(&url));
         }
 
         let resp = self
             .http
             .get(&url)
             .send()
-            .with_context(|| format!("failed to query crates.io for {}", crate_name))?;
+            .with_context(|| format!("failed to query crates.io for {}", crate_name))
+            .kind(ErrorKind::Network)?;
 
         if resp.status() == reqwest::StatusCode::NOT_FOUND {
             if verbose {
@@ -42,10 +43,14 @@ impl CratesIoClient {
         }
 
         if !resp.status().is_success() {
-            bail!("failed to query crates.io: HTTP {}", resp.status());
+            return Err(anyhow!("failed to query crates.io: HTTP {}", resp.status()))
+                .kind(ErrorKind::Network);
         }
 
-        let body: serde_json::Value = resp.json().context("failed to parse crates.io response")?;
+        let body: serde_json::Value = resp
+            .json()
+            .context("failed to parse crates.io response")
+            .kind(ErrorKind::Network)?;
 
         let latest = body
             .pointer("/crate/max_version")
@@ -69,6 +74,24 @@ impl CratesIoClient {
             eprintln!("[registry] total versions: {}", versions.len());
         }
 
-        Ok(PackageInfo::Found { versions, latest })
+        let published = body
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| {
+                        let num = v.get("num")?.as_str()?;
+                        let created_at = v.get("created_at")?.as_str()?;
+                        Some((num.to_string(), created_at.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(PackageInfo::Found {
+            versions,
+            latest,
+            published,
+        })
     }
 }
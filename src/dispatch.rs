@@ -0,0 +1,100 @@
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::cli::DispatchArgs;
+use crate::config::Config;
+use crate::error::{ErrorKind, ResultExt};
+use crate::github::GitHubClient;
+use crate::version::load_targets;
+
+pub fn run(args: DispatchArgs) -> Result<()> {
+    if args.event.is_none() && args.deployment.is_none() {
+        return Err(anyhow!(
+            "nothing to dispatch: pass --event <TYPE> and/or --deployment <ENVIRONMENT>"
+        ))
+        .kind(ErrorKind::Config);
+    }
+    let repo = match &args.repo {
+        Some(repo) => repo.clone(),
+        None => std::env::var("GITHUB_REPOSITORY")
+            .context("dispatch needs --repo owner/repo outside GitHub Actions")
+            .kind(ErrorKind::Config)?,
+    };
+
+    let (version, package) = match &args.version {
+        Some(v) => (v.clone(), None),
+        None => {
+            let config = Config::load(Path::new("."))?;
+            let primary = args.primary.as_deref().or(config.primary_target());
+            let targets = load_targets(&args.target, primary, &config, None)?;
+            let target = &targets[0].1;
+            (target.version.clone(), Some(target.package_name.clone()))
+        }
+    };
+    let git_ref = args
+        .git_ref
+        .clone()
+        .unwrap_or_else(|| format!("v{version}"));
+    let payload = payload(&version, package.as_deref(), &git_ref);
+    if args.verbose {
+        eprintln!("[github] repository: {}", repo);
+        eprintln!("[github] payload: {}", payload);
+    }
+
+    if args.dry_run {
+        if let Some(event) = &args.event {
+            eprintln!("[github] would fire repository_dispatch '{}'", event);
+        }
+        if let Some(environment) = &args.deployment {
+            eprintln!("[github] would deploy {} to {}", git_ref, environment);
+        }
+    } else {
+        let client = GitHubClient::new(&repo)?;
+        if let Some(event) = &args.event {
+            client.dispatch(event, &payload)?;
+            eprintln!("[github] fired repository_dispatch '{}' on {}", event, repo);
+        }
+        if let Some(environment) = &args.deployment {
+            let id = client.create_deployment(&git_ref, environment, &payload)?;
+            eprintln!(
+                "[github] created deployment {} of {} to {}",
+                id, git_ref, environment
+            );
+        }
+    }
+
+    println!("{}", version);
+    Ok(())
+}
+
+/// What downstream workflows read from `client_payload` or the deployment's
+/// `payload`: the version, the release tag and, when known, the package.
+fn payload(version: &str, package: Option<&str>, git_ref: &str) -> Value {
+    let mut payload = json!({ "version": version, "ref": git_ref });
+    if let Some(package) = package.filter(|p| !p.is_empty()) {
+        payload["package"] = json!(package);
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_carries_version_and_ref() {
+        assert_eq!(
+            payload("26.10.3", Some("@circlesac/oneup"), "v26.10.3"),
+            json!({ "version": "26.10.3", "ref": "v26.10.3", "package": "@circlesac/oneup" })
+        );
+        assert_eq!(
+            payload("26.10.3", Some(""), "main"),
+            json!({ "version": "26.10.3", "ref": "main" })
+        );
+        assert_eq!(
+            payload("26.10.3", None, "v26.10.3"),
+            json!({ "version": "26.10.3", "ref": "v26.10.3" })
+        );
+    }
+}
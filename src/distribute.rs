@@ -0,0 +1,589 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::cli::DistributeArgs;
+use crate::config::Config;
+use crate::error::{ErrorKind, ResultExt};
+use crate::github::GitHubClient;
+use crate::http;
+use crate::redact::redact;
+use crate::release_manifest::sha256_hex;
+use crate::version::load_targets;
+
+pub fn run(args: DistributeArgs) -> Result<()> {
+    let version = match &args.version {
+        Some(v) => v.clone(),
+        None => {
+            let config = Config::load(Path::new("."))?;
+            let primary = args.primary.as_deref().or(config.primary_target());
+            let targets = load_targets(&args.target, primary, &config, None)?;
+            targets[0].1.version.clone()
+        }
+    };
+    let client = GitHubClient::new(&args.repo)?;
+    let branch = args.branch.as_deref();
+
+    // Formulae list the same archive for several platforms; fetch each once
+    let http = http::client();
+    let mut digests: HashMap<String, String> = HashMap::new();
+    let mut digest = |url: &str| -> Result<String> {
+        if let Some(digest) = digests.get(url) {
+            return Ok(digest.clone());
+        }
+        if args.verbose {
+            eprintln!("[distribute] GET {}", redact(url));
+        }
+        let resp = http
+            .get(url)
+            .send()
+            .with_context(|| format!("failed to download {}", redact(url)))
+            .kind(ErrorKind::Network)?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "failed to download {}: HTTP {}",
+                redact(url),
+                resp.status()
+            ))
+            .kind(ErrorKind::Network);
+        }
+        let bytes = resp
+            .bytes()
+            .with_context(|| format!("failed to download {}", redact(url)))
+            .kind(ErrorKind::Network)?;
+        let digest = sha256_hex(&bytes);
+        digests.insert(url.to_string(), digest.clone());
+        Ok(digest)
+    };
+
+    for path in &args.path {
+        let manifest = Manifest::from_path(path)?;
+        if args.verbose {
+            eprintln!(
+                "[distribute] {}: {} in {}",
+                path,
+                manifest.name(),
+                args.repo
+            );
+        }
+        let (content, sha) = client.get_file(path, branch)?;
+        let Some(updated) = manifest
+            .update(&content, &version, &mut digest)
+            .with_context(|| format!("failed to update {} in {}", path, args.repo))?
+        else {
+            eprintln!("[distribute] {} is already at {}", path, version);
+            continue;
+        };
+
+        if args.dry_run {
+            eprintln!("[distribute] would commit {}:", path);
+            for (old, new) in content.lines().zip(updated.lines()) {
+                if old != new {
+                    eprintln!("-{}", old);
+                    eprintln!("+{}", new);
+                }
+            }
+            continue;
+        }
+        let stem = Path::new(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(path);
+        let message = format!("{} {}", stem, version);
+        let commit = client.put_file(path, &updated, &sha, &message, branch)?;
+        eprintln!(
+            "[distribute] committed {} to {} ({})",
+            path,
+            args.repo,
+            &commit[..commit.len().min(7)]
+        );
+    }
+
+    println!("{}", version);
+    Ok(())
+}
+
+/// A package-manager manifest, told apart by its extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Manifest {
+    /// Homebrew formula or cask (Ruby)
+    Homebrew,
+    /// Scoop app manifest (JSON)
+    Scoop,
+    /// winget manifest (YAML)
+    Winget,
+}
+
+/// Where a manifest keeps its version and its downloads, as byte spans of
+/// the values (quotes excluded)
+#[derive(Debug, Default)]
+struct Fields {
+    version: Vec<Range<usize>>,
+    /// Each download URL with the SHA-256 that goes with it
+    sources: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl Manifest {
+    fn from_path(path: &str) -> Result<Self> {
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("rb") => Ok(Self::Homebrew),
+            Some("json") => Ok(Self::Scoop),
+            Some("yaml" | "yml") => Ok(Self::Winget),
+            _ => Err(anyhow!(
+                "cannot tell what {} is: expected a Homebrew formula (.rb), Scoop manifest (.json) or winget manifest (.yaml)",
+                path
+            ))
+            .kind(ErrorKind::Config),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Homebrew => "Homebrew formula",
+            Self::Scoop => "Scoop manifest",
+            Self::Winget => "winget manifest",
+        }
+    }
+
+    /// `content` moved to `version`: the version field, every download URL
+    /// that names the old version, and the SHA-256 of each of those URLs
+    /// (from `digest`). None when it is already at `version`.
+    fn update(
+        self,
+        content: &str,
+        version: &str,
+        digest: &mut dyn FnMut(&str) -> Result<String>,
+    ) -> Result<Option<String>> {
+        let fields = match self {
+            Self::Homebrew => homebrew_fields(content),
+            Self::Scoop => scoop_fields(content)?,
+            Self::Winget => winget_fields(content),
+        };
+        let old = match fields.version.first() {
+            Some(span) => content[span.clone()].to_string(),
+            // Formulae usually let Homebrew read the version from the URL
+            None => fields
+                .sources
+                .iter()
+                .find_map(|(url, _)| version_in_url(&content[url.clone()]))
+                .with_context(|| format!("no version in the {}", self.name()))
+                .kind(ErrorKind::Config)?,
+        };
+        if old == version {
+            return Ok(None);
+        }
+
+        let mut edits: Vec<(Range<usize>, String)> = fields
+            .version
+            .iter()
+            .map(|span| (span.clone(), version.to_string()))
+            .collect();
+        for (url, hash) in &fields.sources {
+            let current = &content[url.clone()];
+            let moved = current.replace(&old, version);
+            let download = self.expand(&moved, version);
+            // Resources pinned to their own version stay as they are
+            if download == self.expand(current, &old) {
+                continue;
+            }
+            let sha256 = digest(&download)?;
+            edits.push((hash.clone(), keep_style(&content[hash.clone()], &sha256)));
+            if moved != current {
+                edits.push((url.clone(), moved));
+            }
+        }
+
+        let mut updated = content.to_string();
+        edits.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+        for (span, value) in edits {
+            updated.replace_range(span, &value);
+        }
+        Ok(Some(updated))
+    }
+
+    /// The URL that is downloaded: formulae may interpolate `#{version}`
+    fn expand(self, url: &str, version: &str) -> String {
+        match self {
+            Self::Homebrew => url.replace("#{version}", version),
+            Self::Scoop | Self::Winget => url.to_string(),
+        }
+    }
+}
+
+/// The release tag in a GitHub download URL, without its `v`
+fn version_in_url(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("/releases/download/")?;
+    let tag = rest.split('/').next()?;
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| version.to_string())
+}
+
+/// `sha256` written the way `current` is: upper or lower case, and with its
+/// `sha256:` prefix if it had one
+fn keep_style(current: &str, sha256: &str) -> String {
+    let (prefix, hex) = match current.split_once(':') {
+        Some((prefix, hex)) => (&current[..=prefix.len()], hex),
+        None => ("", current),
+    };
+    let upper =
+        hex.chars().any(|c| c.is_ascii_uppercase()) && !hex.chars().any(|c| c.is_ascii_lowercase());
+    if upper {
+        format!("{prefix}{}", sha256.to_ascii_uppercase())
+    } else {
+        format!("{prefix}{sha256}")
+    }
+}
+
+/// Lines of `content` with the offset each starts at
+fn lines_with_offsets(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line.trim_end_matches(['\n', '\r'])))
+    })
+}
+
+/// `version "…"`, `url "…"` and `sha256 "…"` stanzas; each URL goes with
+/// the sha256 that follows it. Bottle checksums (`sha256 cellar: …`) and
+/// `head` URLs have no version of the release and are left alone.
+fn homebrew_fields(content: &str) -> Fields {
+    let mut fields = Fields::default();
+    let mut url = None;
+    for (offset, line) in lines_with_offsets(content) {
+        let indent = line.len() - line.trim_start().len();
+        let stanza = &line[indent..];
+        let Some((keyword, rest)) = stanza.split_once(' ') else {
+            continue;
+        };
+        let Some(value) = rest.strip_prefix('"').and_then(|r| r.find('"')) else {
+            continue;
+        };
+        let start = offset + indent + keyword.len() + 2;
+        let span = start..start + value;
+        match keyword {
+            "version" if fields.version.is_empty() => fields.version.push(span),
+            "url" => url = Some(span),
+            "sha256" => {
+                if let Some(url) = url.take() {
+                    fields.sources.push((url, span));
+                }
+            }
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// `PackageVersion`, `InstallerUrl` and `InstallerSha256`, each URL paired
+/// with the checksum of the same installer (either may come first)
+fn winget_fields(content: &str) -> Fields {
+    let mut fields = Fields::default();
+    let (mut url, mut hash) = (None, None);
+    for (offset, line) in lines_with_offsets(content) {
+        let item = line.trim_start().trim_start_matches("- ").trim_start();
+        let Some((key, value)) = item.split_once(':') else {
+            continue;
+        };
+        let trimmed = value.trim();
+        let unquoted = trimmed.trim_matches(|c| c == '"' || c == '\'');
+        if unquoted.is_empty() {
+            continue;
+        }
+        // `item` and `value` borrow from `line`, so their offsets carry over
+        let start = offset + (unquoted.as_ptr() as usize - line.as_ptr() as usize);
+        let span = start..start + unquoted.len();
+        match key {
+            "PackageVersion" => fields.version.push(span),
+            "InstallerUrl" => match hash.take() {
+                Some(hash) => fields.sources.push((span, hash)),
+                None => url = Some(span),
+            },
+            "InstallerSha256" => match url.take() {
+                Some(url) => fields.sources.push((url, span)),
+                None => hash = Some(span),
+            },
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// The top-level `version`, and each `url` with the `hash` beside it, also
+/// per `architecture`. `autoupdate` is Scoop's own template and is left
+/// alone.
+fn scoop_fields(content: &str) -> Result<Fields> {
+    serde_json::from_str::<serde_json::Value>(content)
+        .context("invalid JSON")
+        .kind(ErrorKind::Config)?;
+    let mut scanner = JsonStrings {
+        text: content.as_bytes(),
+        pos: 0,
+        path: Vec::new(),
+        found: Vec::new(),
+    };
+    scanner.value();
+
+    let mut fields = Fields::default();
+    let mut urls: Vec<(Vec<&str>, Range<usize>)> = Vec::new();
+    let mut hashes: Vec<(Vec<&str>, Range<usize>)> = Vec::new();
+    for (path, span) in scanner.found {
+        let Some((last, parent)) = path.split_last() else {
+            continue;
+        };
+        if parent.first() == Some(&"autoupdate") {
+            continue;
+        }
+        match (*last, parent) {
+            ("version", []) => fields.version.push(span),
+            ("url", _) => urls.push((parent.to_vec(), span)),
+            ("hash", _) => hashes.push((parent.to_vec(), span)),
+            _ => {}
+        }
+    }
+    // `url` and `hash` may both be arrays; they pair up by position
+    for (parent, url) in urls {
+        if let Some(i) = hashes.iter().position(|(p, _)| *p == parent) {
+            fields.sources.push((url, hashes.remove(i).1));
+        }
+    }
+    Ok(fields)
+}
+
+/// Walks a JSON document that is known to be valid, collecting the span of
+/// every string value (quotes excluded) with the object keys leading to it
+struct JsonStrings<'a> {
+    text: &'a [u8],
+    pos: usize,
+    path: Vec<&'a str>,
+    found: Vec<(Vec<&'a str>, Range<usize>)>,
+}
+
+impl<'a> JsonStrings<'a> {
+    fn value(&mut self) {
+        self.skip_whitespace();
+        match self.text[self.pos] {
+            b'{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    if self.text[self.pos] == b'}' {
+                        self.pos += 1;
+                        break;
+                    }
+                    let key = self.string();
+                    self.skip_whitespace();
+                    self.pos += 1; // ':'
+                    // Keys are ASCII, so the span is on a char boundary
+                    self.path
+                        .push(std::str::from_utf8(&self.text[key]).unwrap_or_default());
+                    self.value();
+                    self.path.pop();
+                    self.separator();
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    if self.text[self.pos] == b']' {
+                        self.pos += 1;
+                        break;
+                    }
+                    self.value();
+                    self.separator();
+                }
+            }
+            b'"' => {
+                let span = self.string();
+                self.found.push((self.path.clone(), span));
+            }
+            // Numbers, true, false, null
+            _ => {
+                while !matches!(self.text[self.pos], b',' | b'}' | b']')
+                    && !self.text[self.pos].is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// A string starting at `pos`; the span of its contents
+    fn string(&mut self) -> Range<usize> {
+        self.pos += 1;
+        let start = self.pos;
+        while self.text[self.pos] != b'"' {
+            if self.text[self.pos] == b'\\' {
+                self.pos += 1;
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        start..self.pos - 1
+    }
+
+    fn separator(&mut self) {
+        self.skip_whitespace();
+        if self.text[self.pos] == b',' {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.text.len() && self.text[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A digest that names the URL it was asked for
+    fn fake_digest(url: &str) -> Result<String> {
+        Ok(sha256_hex(url.as_bytes()))
+    }
+
+    #[test]
+    fn updates_homebrew_formula() {
+        let formula = r#"class Oneup < Formula
+  desc "Calendar versioning"
+  version "26.10.2"
+  on_macos do
+    url "https://github.com/circlesac/oneup/releases/download/v#{version}/oneup-aarch64-apple-darwin.tar.gz"
+    sha256 "1111111111111111111111111111111111111111111111111111111111111111"
+  end
+  on_linux do
+    url "https://github.com/circlesac/oneup/releases/download/v26.10.2/oneup-x86_64-unknown-linux-gnu.tar.gz"
+    sha256 "2222222222222222222222222222222222222222222222222222222222222222"
+  end
+  resource "completions" do
+    url "https://example.com/completions-1.0.tar.gz"
+    sha256 "3333333333333333333333333333333333333333333333333333333333333333"
+  end
+  bottle do
+    sha256 cellar: :any, arm64_sonoma: "4444444444444444444444444444444444444444444444444444444444444444"
+  end
+end
+"#;
+        let mac = "https://github.com/circlesac/oneup/releases/download/v26.10.3/oneup-aarch64-apple-darwin.tar.gz";
+        let linux = "https://github.com/circlesac/oneup/releases/download/v26.10.3/oneup-x86_64-unknown-linux-gnu.tar.gz";
+        let expected = formula
+            .replace("version \"26.10.2\"", "version \"26.10.3\"")
+            .replace("v26.10.2/", "v26.10.3/")
+            .replace(&"1".repeat(64), &sha256_hex(mac.as_bytes()))
+            .replace(&"2".repeat(64), &sha256_hex(linux.as_bytes()));
+        let updated = Manifest::Homebrew
+            .update(formula, "26.10.3", &mut fake_digest)
+            .unwrap();
+        assert_eq!(updated.as_deref(), Some(expected.as_str()));
+
+        assert_eq!(
+            Manifest::Homebrew
+                .update(&expected, "26.10.3", &mut fake_digest)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn homebrew_version_from_release_url() {
+        let formula = "  url \"https://github.com/o/r/releases/download/v1.2.0/r.tar.gz\"\n  sha256 \"abc\"\n";
+        let updated = Manifest::Homebrew
+            .update(formula, "1.3.0", &mut fake_digest)
+            .unwrap()
+            .unwrap();
+        assert!(updated.contains("download/v1.3.0/r.tar.gz"));
+
+        let unversioned = "  url \"https://example.com/r.tar.gz\"\n  sha256 \"abc\"\n";
+        assert!(
+            Manifest::Homebrew
+                .update(unversioned, "1.3.0", &mut fake_digest)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn updates_scoop_manifest() {
+        let manifest = r#"{
+    "version": "26.10.2",
+    "description": "oneup 26.10.2",
+    "architecture": {
+        "64bit": {
+            "url": "https://example.com/v26.10.2/oneup-x64.zip",
+            "hash": "sha256:AAAA"
+        },
+        "arm64": {
+            "url": ["https://example.com/v26.10.2/oneup-arm64.zip", "https://example.com/extra.zip"],
+            "hash": ["bbbb", "cccc"]
+        }
+    },
+    "autoupdate": {
+        "url": "https://example.com/v26.10.2/oneup.zip",
+        "hash": "dddd"
+    }
+}"#;
+        let x64 = sha256_hex(b"https://example.com/v26.10.3/oneup-x64.zip").to_ascii_uppercase();
+        let arm64 = sha256_hex(b"https://example.com/v26.10.3/oneup-arm64.zip");
+        let updated = Manifest::Scoop
+            .update(manifest, "26.10.3", &mut fake_digest)
+            .unwrap()
+            .unwrap();
+        let expected = manifest
+            .replace("\"version\": \"26.10.2\"", "\"version\": \"26.10.3\"")
+            .replace("v26.10.2/oneup-x64", "v26.10.3/oneup-x64")
+            .replace("v26.10.2/oneup-arm64", "v26.10.3/oneup-arm64")
+            .replace("sha256:AAAA", &format!("sha256:{x64}"))
+            .replace("bbbb", &arm64);
+        assert_eq!(updated, expected);
+    }
+
+    #[test]
+    fn updates_winget_manifest() {
+        let manifest = "PackageIdentifier: Circlesac.Oneup
+PackageVersion: 26.10.2
+Installers:
+- Architecture: x64
+  InstallerSha256: AAAA
+  InstallerUrl: https://example.com/v26.10.2/oneup-x64.zip
+- Architecture: arm64
+  InstallerUrl: \"https://example.com/v26.10.2/oneup-arm64.zip\"
+  InstallerSha256: BBBB
+ManifestVersion: 1.6.0
+";
+        let x64 = sha256_hex(b"https://example.com/v26.10.3/oneup-x64.zip").to_ascii_uppercase();
+        let arm64 =
+            sha256_hex(b"https://example.com/v26.10.3/oneup-arm64.zip").to_ascii_uppercase();
+        let updated = Manifest::Winget
+            .update(manifest, "26.10.3", &mut fake_digest)
+            .unwrap()
+            .unwrap();
+        let expected = manifest
+            .replace("26.10.2", "26.10.3")
+            .replace("AAAA", &x64)
+            .replace("BBBB", &arm64);
+        assert_eq!(updated, expected);
+    }
+
+    #[test]
+    fn manifest_kind_from_extension() {
+        assert_eq!(
+            Manifest::from_path("Formula/oneup.rb").unwrap(),
+            Manifest::Homebrew
+        );
+        assert_eq!(
+            Manifest::from_path("bucket/oneup.json").unwrap(),
+            Manifest::Scoop
+        );
+        assert_eq!(
+            Manifest::from_path("manifests/c/Circlesac/Oneup/Circlesac.Oneup.installer.yaml")
+                .unwrap(),
+            Manifest::Winget
+        );
+        assert!(Manifest::from_path("oneup.nuspec").is_err());
+    }
+}
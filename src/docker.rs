@@ -0,0 +1,204 @@
+use anyhow::{Result, bail};
+use std::ops::Range;
+
+use crate::scan;
+
+const VERSION_LABEL: &str = "org.opencontainers.image.version=";
+
+/// Docker's limit on tag length
+const MAX_TAG_LEN: usize = 128;
+
+/// Current `org.opencontainers.image.version` label in a Dockerfile.
+pub fn label_version(content: &str) -> Option<&str> {
+    label_values(content)
+        .into_iter()
+        .next()
+        .map(|r| &content[r])
+}
+
+/// Set every `org.opencontainers.image.version` label to `version`.
+pub fn update_dockerfile(content: &str, version: &str) -> Result<String> {
+    let ranges = label_values(content);
+    if ranges.is_empty() {
+        bail!("no LABEL {}... found", VERSION_LABEL);
+    }
+    Ok(replace_all(content, ranges, version))
+}
+
+/// Current tag of `image` in a docker-compose, Kubernetes or kustomization file.
+pub fn image_tag<'a>(content: &'a str, image: &str) -> Option<&'a str> {
+    tag_values(content, image)
+        .into_iter()
+        .next()
+        .map(|r| &content[r])
+}
+
+/// Set every tag of `image` to `version`: `image:tag` references
+/// (docker-compose `image:`, Kubernetes `image:`) and kustomize `newTag`
+/// entries under `name: image`.
+pub fn update_image_tag(content: &str, image: &str, version: &str) -> Result<String> {
+    check_tag(version)?;
+    let ranges = tag_values(content, image);
+    if ranges.is_empty() {
+        bail!("no reference to image {} found", image);
+    }
+    Ok(replace_all(content, ranges, version))
+}
+
+/// Image tags are up to 128 letters, digits, '_', '.' and '-', not starting
+/// with '.' or '-' (so no `+build` suffix).
+fn check_tag(version: &str) -> Result<()> {
+    let valid = !version.is_empty()
+        && version.len() <= MAX_TAG_LEN
+        && !version.starts_with(['.', '-'])
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid {
+        bail!(
+            "{} is not a valid image tag (letters, digits, '_', '.' and '-' only); \
+             drop the +SHA suffix from the format for image targets",
+            version
+        );
+    }
+    Ok(())
+}
+
+fn replace_all(content: &str, mut ranges: Vec<Range<usize>>, value: &str) -> String {
+    ranges.sort_by_key(|r| r.start);
+    let mut output = content.to_string();
+    for range in ranges.into_iter().rev() {
+        output.replace_range(range, value);
+    }
+    output
+}
+
+/// Byte ranges of every version label value (inside quotes if quoted).
+/// Values taken from a build argument (`$VERSION`, `${VERSION}`) are set
+/// by `docker build --build-arg` and left alone.
+fn label_values(content: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = content[from..].find(VERSION_LABEL) {
+        let start = from + pos + VERSION_LABEL.len();
+        from = start;
+        let rest = &content[start..];
+        let range = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                rest[1..].find(quote).map(|len| start + 1..start + 1 + len)
+            }
+            _ => {
+                let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                Some(start..start + len)
+            }
+        };
+        ranges.extend(range.filter(|r| !content[r.clone()].starts_with('$')));
+    }
+    ranges
+}
+
+/// Byte ranges of the tags of `image`.
+fn tag_values(content: &str, image: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+
+    // image:tag references
+    let reference = format!("{}:", image);
+    for start in scan::word_starts(content, &reference, &['/', '.', '-', '_']) {
+        let from = start + reference.len();
+        let rest = &content[from..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+            .unwrap_or(rest.len());
+        if len > 0 {
+            ranges.push(from..from + len);
+        }
+    }
+
+    // kustomize images: - name: image / newTag: tag
+    let mut offset = 0;
+    let mut in_item = false;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+        let item = trimmed.strip_prefix("- ");
+        if let Some(name) = item.unwrap_or(trimmed).strip_prefix("name:") {
+            in_item = name.trim().trim_matches(['"', '\'']) == image;
+            continue;
+        }
+        if item.is_some() {
+            in_item = false;
+        }
+        if !in_item {
+            continue;
+        }
+        if let Some(tag) = item.unwrap_or(trimmed).strip_prefix("newTag:") {
+            let tag = tag.trim().trim_matches(['"', '\'']);
+            let tag_start = start + line.find(tag).unwrap_or(0);
+            if !tag.is_empty() {
+                ranges.push(tag_start..tag_start + tag.len());
+            }
+            in_item = false;
+        }
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dockerfile_label() {
+        let content = "FROM alpine\nLABEL org.opencontainers.image.title=\"app\" \\\n      org.opencontainers.image.version=\"26.2.1\"\nLABEL org.opencontainers.image.version=26.2.1\n";
+        assert_eq!(label_version(content), Some("26.2.1"));
+        let updated = update_dockerfile(content, "26.2.5+abc").unwrap();
+        assert!(updated.contains("image.version=\"26.2.5+abc\"\n"));
+        assert!(updated.ends_with("LABEL org.opencontainers.image.version=26.2.5+abc\n"));
+        assert!(update_dockerfile("FROM alpine\n", "26.2.5").is_err());
+    }
+
+    #[test]
+    fn dockerfile_label_from_build_arg_is_left_alone() {
+        let content = "ARG VERSION\nLABEL org.opencontainers.image.version=$VERSION\nLABEL org.opencontainers.image.version=\"${VERSION}\"\nLABEL org.opencontainers.image.version=26.2.1\n";
+        assert_eq!(label_version(content), Some("26.2.1"));
+        let updated = update_dockerfile(content, "26.2.5").unwrap();
+        assert!(updated.contains("image.version=$VERSION\n"));
+        assert!(updated.contains("image.version=\"${VERSION}\"\n"));
+        assert!(updated.ends_with("image.version=26.2.5\n"));
+        assert!(
+            update_dockerfile(
+                "LABEL org.opencontainers.image.version=$VERSION\n",
+                "26.2.5"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn compose_image_references() {
+        let content = "services:\n  app:\n    image: ghcr.io/org/app:26.2.1\n  worker:\n    image: \"ghcr.io/org/app-worker:26.2.1\"\n  proxy:\n    image: mirror/ghcr.io/org/app:1.0\n";
+        assert_eq!(image_tag(content, "ghcr.io/org/app"), Some("26.2.1"));
+        let updated = update_image_tag(content, "ghcr.io/org/app", "26.2.5").unwrap();
+        assert!(updated.contains("image: ghcr.io/org/app:26.2.5\n"));
+        assert!(updated.contains("app-worker:26.2.1"));
+        assert!(updated.contains("mirror/ghcr.io/org/app:1.0"));
+    }
+
+    #[test]
+    fn kustomize_new_tag() {
+        let content = "images:\n  - name: ghcr.io/org/other\n    newTag: \"1.0\"\n  - name: ghcr.io/org/app\n    newTag: \"26.2.1\"\n";
+        assert_eq!(image_tag(content, "ghcr.io/org/app"), Some("26.2.1"));
+        let updated = update_image_tag(content, "ghcr.io/org/app", "26.2.5").unwrap();
+        assert!(updated.contains("newTag: \"1.0\""));
+        assert!(updated.ends_with("newTag: \"26.2.5\"\n"));
+    }
+
+    #[test]
+    fn rejects_invalid_tags() {
+        let content = "image: app:1.0\n";
+        assert!(update_image_tag(content, "app", "26.2.5+abc").is_err());
+        assert!(update_image_tag(content, "other", "26.2.5").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::cli::DockerTagArgs;
+use crate::config::Config;
+use crate::format::VersionFormat;
+use crate::version::load_targets;
+
+pub fn run(args: DockerTagArgs) -> Result<()> {
+    let fmt = VersionFormat::parse(&args.format)?;
+    let version = match &args.version {
+        Some(v) => v.clone(),
+        None => {
+            let config = Config::load(Path::new("."))?;
+            let primary = args.primary.as_deref().or(config.primary_target());
+            let targets = load_targets(&args.target, primary, &config, None)?;
+            targets[0].1.version.clone()
+        }
+    };
+    if args.verbose {
+        eprintln!("[docker] version: {}", version);
+    }
+
+    for tag in tags(&version, &fmt) {
+        println!("{}:{}", args.image, tag);
+    }
+    Ok(())
+}
+
+/// Image tags for `version`: the exact version, then for stable releases the
+/// date prefix (the version without MICRO) and `latest`, or for prereleases
+/// the moving channel tag (`beta`, `rc`, `pr12`). Build metadata is joined
+/// with '-' since tags cannot contain '+'.
+fn tags(version: &str, fmt: &VersionFormat) -> Vec<String> {
+    let mut tags = vec![version.replace('+', "-")];
+
+    let core = version.split('+').next().unwrap_or(version);
+    match core.split_once('-') {
+        Some((_, prerelease)) => {
+            let channel = prerelease.split('.').next().unwrap_or(prerelease);
+            tags.push(channel.to_string());
+        }
+        None => {
+            if fmt.has_micro() {
+                let date_parts = fmt.components.len() - 1;
+                let prefix: Vec<&str> = core.split('.').take(date_parts).collect();
+                tags.push(prefix.join("."));
+            } else if core != tags[0] {
+                tags.push(core.to_string());
+            }
+            tags.push("latest".to_string());
+        }
+    }
+
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags_for(version: &str, format: &str) -> Vec<String> {
+        tags(version, &VersionFormat::parse(format).unwrap())
+    }
+
+    #[test]
+    fn stable_release_tags() {
+        assert_eq!(
+            tags_for("26.2.5", "YY.MM.MICRO"),
+            vec!["26.2.5", "26.2", "latest"]
+        );
+        assert_eq!(
+            tags_for("2026.2.17.0", "YYYY.MM.DD.MICRO"),
+            vec!["2026.2.17.0", "2026.2.17", "latest"]
+        );
+        assert_eq!(
+            tags_for("26.2.5+a1b2c3d", "YY.MM.MICRO+SHA"),
+            vec!["26.2.5-a1b2c3d", "26.2", "latest"]
+        );
+        assert_eq!(
+            tags_for("2026.2.17", "YYYY.MM.DD"),
+            vec!["2026.2.17", "latest"]
+        );
+    }
+
+    #[test]
+    fn prerelease_tags() {
+        assert_eq!(
+            tags_for("26.2.5-beta.1", "YY.MM.MICRO"),
+            vec!["26.2.5-beta.1", "beta"]
+        );
+        assert_eq!(
+            tags_for("0.0.0-pr12.3", "YY.MM.MICRO"),
+            vec!["0.0.0-pr12.3", "pr12"]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::{Result, bail};
+use std::ops::Range;
+
+use crate::target::VERSION_PLACEHOLDER;
+
+/// Check that a docs pattern has a `{version}` placeholder with literal
+/// text before it to anchor the match.
+pub fn check_pattern(pattern: &str) -> Result<()> {
+    match pattern.split_once(VERSION_PLACEHOLDER) {
+        Some((prefix, _)) if !prefix.is_empty() => Ok(()),
+        Some(_) => bail!(
+            "docs pattern '{}' needs text before {}",
+            pattern,
+            VERSION_PLACEHOLDER
+        ),
+        None => bail!(
+            "docs pattern '{}' has no {} placeholder",
+            pattern,
+            VERSION_PLACEHOLDER
+        ),
+    }
+}
+
+/// Version at the first match of any pattern.
+pub fn version<'a>(content: &'a str, patterns: &[String]) -> Option<&'a str> {
+    patterns
+        .iter()
+        .find_map(|p| matches(content, p).into_iter().next())
+        .map(|r| &content[r])
+}
+
+/// Replace the version at every match of every pattern. A pattern that
+/// matches nothing is an error, so stale patterns do not go unnoticed.
+pub fn update(content: &str, patterns: &[String], version: &str) -> Result<String> {
+    let mut ranges = Vec::new();
+    for pattern in patterns {
+        let found = matches(content, pattern);
+        if found.is_empty() {
+            bail!("docs pattern '{}' matched nothing", pattern);
+        }
+        ranges.extend(found);
+    }
+    ranges.sort_by_key(|r| r.start);
+    ranges.dedup();
+
+    let mut output = content.to_string();
+    for range in ranges.into_iter().rev() {
+        output.replace_range(range, version);
+    }
+    Ok(output)
+}
+
+/// Byte ranges of the version wherever `pattern` matches: the pattern's
+/// text around `{version}` with a version (starting with a digit) between.
+fn matches(content: &str, pattern: &str) -> Vec<Range<usize>> {
+    let Some((prefix, suffix)) = pattern.split_once(VERSION_PLACEHOLDER) else {
+        return Vec::new();
+    };
+    if prefix.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = content[from..].find(prefix) {
+        let start = from + pos + prefix.len();
+        from = start;
+
+        let rest = &content[start..];
+        let run = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')))
+            .unwrap_or(rest.len());
+        // The version ends where the suffix begins (badges put "-blue" right after it)
+        let len = if suffix.is_empty() {
+            Some(run)
+        } else {
+            (0..=run).find(|&i| rest.is_char_boundary(i) && rest[i..].starts_with(suffix))
+        };
+        if let Some(len) = len
+            && rest.starts_with(|c: char| c.is_ascii_digit())
+        {
+            ranges.push(start..start + len);
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = "\
+# my-tool
+
+![version](https://img.shields.io/badge/version-26.2.1-blue)
+
+```sh
+cargo add my-tool@26.2.1
+npm i my-tool@26.2.1
+```
+
+Pin `my-tool@26.2.1` in CI.
+";
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn updates_every_match() {
+        let patterns = patterns(&["badge/version-{version}-blue", "my-tool@{version}"]);
+        assert_eq!(version(README, &patterns), Some("26.2.1"));
+        let updated = update(README, &patterns, "26.2.5").unwrap();
+        assert!(updated.contains("badge/version-26.2.5-blue)"));
+        assert!(updated.contains("cargo add my-tool@26.2.5\n"));
+        assert!(updated.contains("npm i my-tool@26.2.5\n"));
+        assert!(updated.contains("`my-tool@26.2.5`"));
+    }
+
+    #[test]
+    fn unmatched_pattern_fails() {
+        let patterns = patterns(&["my-tool@{version}", "other@{version}"]);
+        assert!(update(README, &patterns, "26.2.5").is_err());
+        assert_eq!(version("my-tool@latest", &patterns), None);
+    }
+
+    #[test]
+    fn check_pattern_rules() {
+        assert!(check_pattern("version: {version}").is_ok());
+        assert!(check_pattern("{version}").is_err());
+        assert!(check_pattern("no placeholder").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use serde_json::json;
+use std::fmt;
+
+use crate::redact::redact;
+
+/// Failure category with a stable code and exit status, so CI can branch
+/// on why oneup failed (retry network errors, page on auth errors, ...).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    /// oneup.toml, .npmrc or command-line settings are invalid or refuse the run
+    Config,
+    /// The registry could not be reached or answered unexpectedly
+    Network,
+    /// The registry rejected the credentials
+    Auth,
+    /// A git command failed
+    Git,
+    /// The version format is invalid
+    Format,
+    /// The version collides with what is already published
+    Conflict,
+    /// A check the command runs failed (a preflight check, a taken version
+    /// in `available`, targets out of sync); the command has already
+    /// printed its verdict
+    Check,
+}
+
+impl ErrorKind {
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::Config => "config",
+            ErrorKind::Network => "network",
+            ErrorKind::Auth => "auth",
+            ErrorKind::Git => "git",
+            ErrorKind::Format => "format",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::Check => "check",
+        }
+    }
+
+    /// Process exit status; uncategorized errors and failed checks exit with 1.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Config => 10,
+            ErrorKind::Network => 11,
+            ErrorKind::Auth => 12,
+            ErrorKind::Git => 13,
+            ErrorKind::Format => 14,
+            ErrorKind::Conflict => 15,
+            ErrorKind::Check => 1,
+        }
+    }
+}
+
+/// Shown only when the kind is printed as part of an error chain; `report`
+/// skips it and puts the code in front instead.
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[oneup:{}]", self.code())
+    }
+}
+
+pub trait ResultExt<T> {
+    /// Attach a category to the error, if any.
+    fn kind(self, kind: ErrorKind) -> anyhow::Result<T>;
+}
+
+impl<T> ResultExt<T> for anyhow::Result<T> {
+    fn kind(self, kind: ErrorKind) -> anyhow::Result<T> {
+        self.map_err(|err| err.context(kind))
+    }
+}
+
+/// A backend this binary was built without (`--no-default-features` and
+/// only some of `npm`, `crates`, `network`, `git`).
+pub fn not_built(feature: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "this oneup was built without the `{feature}` feature; rebuild with `--features {feature}` or install a release build"
+    )
+    .context(ErrorKind::Config)
+}
+
+/// The outermost category attached to `err`.
+pub fn kind_of(err: &anyhow::Error) -> Option<ErrorKind> {
+    err.downcast_ref::<ErrorKind>().copied()
+}
+
+/// Messages of the error chain, outermost first, without category markers.
+fn messages(err: &anyhow::Error) -> Vec<String> {
+    let markers: Vec<String> = [
+        ErrorKind::Config,
+        ErrorKind::Network,
+        ErrorKind::Auth,
+        ErrorKind::Git,
+        ErrorKind::Format,
+        ErrorKind::Conflict,
+        ErrorKind::Check,
+    ]
+    .iter()
+    .map(|k| k.to_string())
+    .collect();
+    err.chain()
+        .map(|e| e.to_string())
+        .filter(|m| !markers.contains(m))
+        .map(|m| redact(&m))
+        .collect()
+}
+
+/// Print `err` to stderr as `Error [code]: ...` with its causes, or as a
+/// JSON object on stdout for commands run with --json, and return the
+/// process exit status. A failed check goes to stderr even with --json,
+/// as stdout already holds the command's report.
+pub fn report(err: &anyhow::Error, json: bool) -> i32 {
+    let kind = kind_of(err);
+    let code = kind.map_or("error", ErrorKind::code);
+    let mut messages = messages(err);
+    let message = if messages.is_empty() {
+        String::new()
+    } else {
+        messages.remove(0)
+    };
+
+    if json && kind != Some(ErrorKind::Check) {
+        let report = json!({
+            "error": {
+                "code": code,
+                "message": message,
+                "causes": messages,
+            }
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+    } else {
+        match kind {
+            Some(kind) => eprintln!("Error [{}]: {}", kind.code(), message),
+            None => eprintln!("Error: {}", message),
+        }
+        if !messages.is_empty() {
+            eprintln!("\nCaused by:");
+            for (i, cause) in messages.iter().enumerate() {
+                eprintln!("    {}: {}", i, cause);
+            }
+        }
+    }
+
+    kind.map_or(1, ErrorKind::exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Context, anyhow};
+
+    #[test]
+    fn outermost_kind_wins() {
+        let err = Err::<(), _>(anyhow!("connection refused"))
+            .context("failed to query registry")
+            .kind(ErrorKind::Network)
+            .context("while computing the version")
+            .unwrap_err();
+        assert_eq!(kind_of(&err), Some(ErrorKind::Network));
+        assert_eq!(
+            messages(&err),
+            vec![
+                "while computing the version",
+                "failed to query registry",
+                "connection refused"
+            ]
+        );
+
+        let err = Err::<(), _>(anyhow!("HTTP 401"))
+            .kind(ErrorKind::Auth)
+            .kind(ErrorKind::Network)
+            .unwrap_err();
+        assert_eq!(kind_of(&err), Some(ErrorKind::Network));
+        assert_eq!(messages(&err), vec!["HTTP 401"]);
+    }
+
+    #[test]
+    fn uncategorized_errors() {
+        let err = anyhow!("something else");
+        assert_eq!(kind_of(&err), None);
+        assert_eq!(report(&err, false), 1);
+        assert_eq!(
+            report(&Err::<(), _>(err).kind(ErrorKind::Git).unwrap_err(), false),
+            13
+        );
+
+        let err = Err::<(), _>(anyhow!("1.0.0 is already published"))
+            .kind(ErrorKind::Check)
+            .unwrap_err();
+        assert_eq!(messages(&err), vec!["1.0.0 is already published"]);
+        assert_eq!(report(&err, true), 1);
+    }
+}
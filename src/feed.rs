@@ -0,0 +1,304 @@
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use std::path::Path;
+
+use crate::cli::FeedArgs;
+use crate::config::Config;
+use crate::git;
+use crate::redact::redact;
+use crate::release_manifest::pick_tag;
+use crate::target::write_atomic;
+use crate::version::load_targets;
+
+/// Commit subjects listed per entry
+const MAX_SUBJECTS: usize = 20;
+
+/// One release in the feed
+struct Entry {
+    package: String,
+    version: String,
+    updated: String,
+    /// Web page of the release tag, when the repository URL is known
+    link: Option<String>,
+    /// Commit subjects since the previous tag, newest first
+    changes: Vec<String>,
+}
+
+pub fn run(args: FeedArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let primary = args.primary.as_deref().or(config.primary_target());
+    let targets = load_targets(&args.target, primary, &config, None)?;
+    let (primary_path, primary_target) = &targets[0];
+    let project_dir = primary_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let package = primary_target.package_name.clone();
+    let version = primary_target.version.clone();
+
+    // The release is usually tagged after the bump; link the tag it will get
+    let tags = git::tags_at_head(project_dir).unwrap_or_default();
+    let tag = pick_tag(&tags, &version).unwrap_or_else(|| format!("v{version}"));
+    let changes = match previous_tag(project_dir, &tag) {
+        Ok(since) => git::commit_subjects(project_dir, since.as_deref()),
+        Err(err) => Err(err),
+    };
+    let changes = changes.unwrap_or_else(|err| {
+        eprintln!(
+            "warning: no changes listed, git unavailable: {}",
+            redact(&format!("{:#}", err))
+        );
+        Vec::new()
+    });
+
+    let repo_url = args.link.clone().or_else(repository_url);
+    let entry = Entry {
+        link: repo_url.as_deref().map(|url| tag_url(url, &tag)),
+        package: package.clone(),
+        version: version.clone(),
+        updated: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        changes,
+    };
+
+    let existing = match std::fs::read_to_string(&args.out) {
+        Ok(text) => entries(&text),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", args.out.display()));
+        }
+    };
+    let title = args
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("{package} releases"));
+    let feed = feed(
+        &title,
+        repo_url.as_deref(),
+        &entry,
+        &existing,
+        args.max_entries,
+    );
+    write_atomic(&args.out, feed.as_bytes())?;
+    if args.verbose {
+        eprintln!("[feed] wrote {} ({})", args.out.display(), tag);
+    }
+
+    println!("{}", version);
+    Ok(())
+}
+
+/// The tag the changes are counted from: the last tag, or the one before
+/// it when the release is already tagged.
+fn previous_tag(dir: &Path, tag: &str) -> Result<Option<String>> {
+    match git::last_tag(dir)? {
+        Some(last) if last == tag => {
+            let tags = git::tags(dir)?;
+            let position = tags.iter().position(|(name, _)| name == tag);
+            Ok(position
+                .and_then(|i| i.checked_sub(1))
+                .map(|i| tags[i].0.clone()))
+        }
+        last => Ok(last),
+    }
+}
+
+/// Web URL of the repository from the CI environment.
+pub fn repository_url() -> Option<String> {
+    let get = |key| std::env::var(key).ok().filter(|v| !v.is_empty());
+    match (get("GITHUB_SERVER_URL"), get("GITHUB_REPOSITORY")) {
+        (Some(server), Some(repo)) => Some(format!("{server}/{repo}")),
+        _ => get("CI_PROJECT_URL"),
+    }
+}
+
+/// Page of `tag` on GitHub, or on GitLab (`/-/` routes) for GitLab URLs.
+pub fn tag_url(repo_url: &str, tag: &str) -> String {
+    let repo_url = repo_url.trim_end_matches('/');
+    let gitlab = repo_url.contains("gitlab")
+        || std::env::var("CI_PROJECT_URL").is_ok_and(|url| url.trim_end_matches('/') == repo_url);
+    if gitlab {
+        format!("{repo_url}/-/tags/{tag}")
+    } else {
+        format!("{repo_url}/releases/tag/{tag}")
+    }
+}
+
+/// The `<entry>` elements of an existing feed, verbatim.
+fn entries(feed: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut rest = feed;
+    while let Some(start) = rest.find("<entry>") {
+        let Some(len) = rest[start..].find("</entry>") else {
+            break;
+        };
+        let end = start + len + "</entry>".len();
+        entries.push(rest[start..end].to_string());
+        rest = &rest[end..];
+    }
+    entries
+}
+
+/// The feed with `entry` first, replacing an earlier entry for the same
+/// version (a rerun), then the existing entries up to `max_entries`.
+fn feed(
+    title: &str,
+    repo_url: Option<&str>,
+    entry: &Entry,
+    existing: &[String],
+    max_entries: usize,
+) -> String {
+    let id = entry_id(&entry.package, &entry.version);
+    let id_element = format!("<id>{}</id>", escape(&id));
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    out.push_str(&format!("  <title>{}</title>\n", escape(title)));
+    out.push_str(&format!(
+        "  <id>{}</id>\n",
+        escape(&format!("urn:oneup:{}", entry.package))
+    ));
+    if let Some(url) = repo_url {
+        out.push_str(&format!("  <link href=\"{}\"/>\n", escape(url)));
+    }
+    out.push_str(&format!("  <updated>{}</updated>\n", entry.updated));
+    out.push_str(&format!(
+        "  <author><name>{}</name></author>\n",
+        escape(&entry.package)
+    ));
+    out.push_str(&format!(
+        "  <generator>oneup {}</generator>\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    out.push_str(&render_entry(entry));
+    for existing in existing
+        .iter()
+        .filter(|e| !e.contains(&id_element))
+        .take(max_entries.saturating_sub(1))
+    {
+        out.push_str(&format!("  {}\n", existing));
+    }
+    out.push_str("</feed>\n");
+    out
+}
+
+fn render_entry(entry: &Entry) -> String {
+    let mut out = String::from("  <entry>\n");
+    out.push_str(&format!(
+        "    <id>{}</id>\n",
+        escape(&entry_id(&entry.package, &entry.version))
+    ));
+    out.push_str(&format!(
+        "    <title>{} {}</title>\n",
+        escape(&entry.package),
+        escape(&entry.version)
+    ));
+    out.push_str(&format!("    <updated>{}</updated>\n", entry.updated));
+    if let Some(link) = &entry.link {
+        out.push_str(&format!("    <link href=\"{}\"/>\n", escape(link)));
+    }
+    if !entry.changes.is_empty() {
+        // HTML content, escaped once more as the element's text
+        let mut html = String::from("<ul>");
+        for subject in entry.changes.iter().take(MAX_SUBJECTS) {
+            html.push_str(&format!("<li>{}</li>", escape(subject)));
+        }
+        if entry.changes.len() > MAX_SUBJECTS {
+            html.push_str(&format!(
+                "<li>and {} more</li>",
+                entry.changes.len() - MAX_SUBJECTS
+            ));
+        }
+        html.push_str("</ul>");
+        out.push_str(&format!(
+            "    <content type=\"html\">{}</content>\n",
+            escape(&html)
+        ));
+    }
+    out.push_str("  </entry>\n");
+    out
+}
+
+/// Stable entry id, so readers don't show a rerun as a new release.
+fn entry_id(package: &str, version: &str) -> String {
+    format!("urn:oneup:{package}:{version}")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(version: &str) -> Entry {
+        Entry {
+            package: "@circlesac/oneup".to_string(),
+            version: version.to_string(),
+            updated: "2026-10-17T09:00:00Z".to_string(),
+            link: Some(format!(
+                "https://github.com/circlesac/oneup/releases/tag/v{version}"
+            )),
+            changes: vec!["Fix <script> & friends".to_string()],
+        }
+    }
+
+    #[test]
+    fn new_entries_go_first_and_reruns_replace() {
+        let first = feed("Releases", None, &entry("26.10.2"), &[], 50);
+        let second = feed("Releases", None, &entry("26.10.3"), &entries(&first), 50);
+        let rerun = feed("Releases", None, &entry("26.10.3"), &entries(&second), 50);
+
+        let ids: Vec<_> = entries(&rerun)
+            .iter()
+            .map(|e| e.lines().nth(1).unwrap().trim().to_string())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "<id>urn:oneup:@circlesac/oneup:26.10.3</id>",
+                "<id>urn:oneup:@circlesac/oneup:26.10.2</id>",
+            ]
+        );
+        assert_eq!(rerun, second);
+        assert_eq!(
+            entries(&feed(
+                "Releases",
+                None,
+                &entry("26.10.4"),
+                &entries(&rerun),
+                2
+            ))
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn entry_is_escaped() {
+        let rendered = render_entry(&entry("26.10.3"));
+        assert!(rendered.contains("<title>@circlesac/oneup 26.10.3</title>"));
+        assert!(
+            rendered.contains(
+                "<link href=\"https://github.com/circlesac/oneup/releases/tag/v26.10.3\"/>"
+            )
+        );
+        assert!(rendered.contains(
+            "<content type=\"html\">&lt;ul&gt;&lt;li&gt;Fix &amp;lt;script&amp;gt; &amp;amp; friends&lt;/li&gt;&lt;/ul&gt;</content>"
+        ));
+    }
+
+    #[test]
+    fn tag_urls() {
+        assert_eq!(
+            tag_url("https://github.com/circlesac/oneup/", "v26.10.3"),
+            "https://github.com/circlesac/oneup/releases/tag/v26.10.3"
+        );
+        assert_eq!(
+            tag_url("https://gitlab.com/circlesac/oneup", "v26.10.3"),
+            "https://gitlab.com/circlesac/oneup/-/tags/v26.10.3"
+        );
+    }
+}
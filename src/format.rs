@@ -1,10 +1,20 @@
 use anyhow::{Result, bail};
 use chrono::Datelike;
 
-/// A parsed version format like "YY.MM.MICRO"
+use crate::error::{ErrorKind, ResultExt};
+use crate::suggest;
+
+/// A parsed version format like "YY.MM.MICRO" or "YY.MM.MICRO+SHA"
 pub struct VersionFormat {
     pub components: Vec<Component>,
     pub micro_index: Option<usize>,
+    /// Append the short commit hash as build metadata ("+SHA")
+    pub sha: bool,
+    /// The date versions are built for, captured once when the format is
+    /// parsed so a run spanning midnight uses a single day throughout
+    pub today: chrono::NaiveDate,
+    /// `today` follows the UTC date rather than the local one
+    pub utc: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +29,10 @@ pub enum Component {
 impl VersionFormat {
     /// Parse a format string like "YY.MM.MICRO"
     pub fn parse(format: &str) -> Result<Self> {
+        Self::parse_tokens(format).kind(ErrorKind::Format)
+    }
+
+    fn parse_tokens(format: &str) -> Result<Self> {
         // Only dot separator is allowed
         if format.contains('-') || format.contains('_') {
             bail!(
@@ -27,7 +41,22 @@ impl VersionFormat {
             );
         }
 
-        let parts: Vec<&str> = format.split('.').collect();
+        // Optional build metadata: only the SHA token is allowed after '+'
+        let (core, build) = match format.split_once('+') {
+            Some((core, build)) => (core, Some(build)),
+            None => (format, None),
+        };
+        if let Some(build) = build
+            && build != "SHA"
+        {
+            bail!(
+                "invalid format '{}': only SHA is allowed after '+', got '{}'",
+                format,
+                build
+            );
+        }
+
+        let parts: Vec<&str> = core.split('.').collect();
         if parts.is_empty() {
             bail!("invalid format '{}': empty format", format);
         }
@@ -48,19 +77,24 @@ impl VersionFormat {
                     micro_index = Some(i);
                     Component::Micro
                 }
-                other => bail!("invalid format '{}': unknown token '{}'", format, other),
+                other => bail!(
+                    "invalid format '{}': unknown token '{}'{}",
+                    format,
+                    other,
+                    token_hint(other)
+                ),
             };
             components.push(component);
         }
 
         // MICRO, if present, must be the last component
-        if let Some(idx) = micro_index {
-            if idx != components.len() - 1 {
-                bail!(
-                    "invalid format '{}': MICRO must be the last component",
-                    format
-                );
-            }
+        if let Some(idx) = micro_index
+            && idx != components.len() - 1
+        {
+            bail!(
+                "invalid format '{}': MICRO must be the last component",
+                format
+            );
         }
 
         // Must have at least one date component
@@ -91,9 +125,41 @@ impl VersionFormat {
         Ok(Self {
             components,
             micro_index,
+            sha: build.is_some(),
+            today: chrono::Local::now().date_naive(),
+            utc: false,
         })
     }
 
+    /// Build versions for `today` instead of the date the format was parsed.
+    pub fn with_today(mut self, today: chrono::NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Build versions for the UTC date instead of the local one, so runners
+    /// in every timezone agree on the period.
+    pub fn in_utc(mut self) -> Self {
+        self.today = chrono::Utc::now().date_naive();
+        self.utc = true;
+        self
+    }
+
+    /// The current date on the clock `today` was taken from.
+    pub fn current_date(&self) -> chrono::NaiveDate {
+        if self.utc {
+            chrono::Utc::now().date_naive()
+        } else {
+            chrono::Local::now().date_naive()
+        }
+    }
+
+    /// Whether the date has moved into another period since the format was
+    /// parsed, so versions built now would carry different date parts.
+    pub fn rolled_over(&self) -> bool {
+        self.date_values(self.current_date()) != self.today_values()
+    }
+
     /// Whether this format has a MICRO component (allows multiple publishes per period).
     pub fn has_micro(&self) -> bool {
         self.micro_index.is_some()
@@ -101,7 +167,11 @@ impl VersionFormat {
 
     /// Compute today's date values for all components.
     fn today_values(&self) -> Vec<u64> {
-        let now = chrono::Local::now();
+        self.date_values(self.today)
+    }
+
+    /// Values of all components for `date` (MICRO is 0).
+    fn date_values(&self, now: chrono::NaiveDate) -> Vec<u64> {
         self.components
             .iter()
             .map(|c| match c {
@@ -138,8 +208,10 @@ impl VersionFormat {
     }
 
     /// Extract component values from a version string.
+    /// Build metadata ("+...") is ignored.
     /// Returns None if the version doesn't match the format structure.
     pub fn extract_values(&self, version: &str) -> Option<Vec<u64>> {
+        let version = version.split_once('+').map_or(version, |(core, _)| core);
         let parts: Vec<&str> = version.split('.').collect();
 
         // Accept versions with exactly format_len components,
@@ -164,16 +236,8 @@ impl VersionFormat {
             // Validate date components
             if self.micro_index != Some(i) {
                 match self.components[i] {
-                    Component::Mm => {
-                        if val < 1 || val > 12 {
-                            return None;
-                        }
-                    }
-                    Component::Dd => {
-                        if val < 1 || val > 31 {
-                            return None;
-                        }
-                    }
+                    Component::Mm if !(1..=12).contains(&val) => return None,
+                    Component::Dd if !(1..=31).contains(&val) => return None,
                     _ => {}
                 }
             }
@@ -196,9 +260,26 @@ impl VersionFormat {
         true
     }
 
-    /// Check if a version's date parts are ahead of today.
-    pub fn ahead_of_today(&self, version_values: &[u64]) -> bool {
+    /// Check if a version's date parts belong to a period before today's.
+    pub fn before_today(&self, version_values: &[u64]) -> bool {
         let today = self.today_values();
+        for (i, (v, t)) in version_values.iter().zip(today.iter()).enumerate() {
+            if self.micro_index == Some(i) {
+                continue;
+            }
+            if v != t {
+                return v < t;
+            }
+        }
+        false
+    }
+
+    /// Check if a version's date parts are ahead of today, even allowing for
+    /// `skew_hours` of clock or timezone difference with the publisher.
+    pub fn ahead_of_today(&self, version_values: &[u64], skew_hours: u64) -> bool {
+        let skew = chrono::TimeDelta::hours(skew_hours.min(i64::MAX as u64 / 3600) as i64);
+        let latest_ok = (chrono::Local::now() + skew).date_naive().max(self.today);
+        let today = self.date_values(latest_ok);
         for (i, (v, t)) in version_values.iter().zip(today.iter()).enumerate() {
             if self.micro_index == Some(i) {
                 continue;
@@ -213,12 +294,39 @@ impl VersionFormat {
         false
     }
 
+    /// First day of today's date period: today for formats with DD,
+    /// the first of the month with MM, otherwise January 1st.
+    pub fn period_start(&self) -> chrono::NaiveDate {
+        let today = self.today;
+        if self.components.contains(&Component::Dd) {
+            today
+        } else if self.components.contains(&Component::Mm) {
+            today.with_day(1).unwrap()
+        } else {
+            today.with_day(1).and_then(|d| d.with_month(1)).unwrap()
+        }
+    }
+
     /// Get the MICRO value from parsed version values.
     pub fn micro_value(&self, version_values: &[u64]) -> Option<u64> {
         self.micro_index.map(|idx| version_values[idx])
     }
 }
 
+/// Tokens accepted in a version format, in the order suggestions prefer them.
+const TOKENS: &[&str] = &["YYYY", "YY", "MM", "DD", "MICRO"];
+
+/// A suggestion for an unknown format token: SemVer's counters map to
+/// MICRO, anything else to the nearest known token.
+fn token_hint(token: &str) -> String {
+    match token.to_uppercase().as_str() {
+        "PATCH" | "MINOR" | "MAJOR" | "BUILD" | "N" => {
+            " (did you mean 'MICRO'? it counts releases within the date period)".to_string()
+        }
+        _ => suggest::did_you_mean(token, TOKENS.iter().copied()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,6 +387,24 @@ mod tests {
         assert!(!fmt.has_micro());
     }
 
+    #[test]
+    fn parse_sha_build_metadata() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO+SHA").unwrap();
+        assert_eq!(
+            fmt.components,
+            vec![Component::Yy, Component::Mm, Component::Micro]
+        );
+        assert!(fmt.sha);
+        assert!(!VersionFormat::parse("YY.MM.MICRO").unwrap().sha);
+    }
+
+    #[test]
+    fn parse_error_unknown_build_metadata() {
+        assert!(VersionFormat::parse("YY.MM.MICRO+BUILD").is_err());
+        assert!(VersionFormat::parse("YY+SHA.MM").is_err());
+        assert!(VersionFormat::parse("YY.MM+SHA+SHA").is_err());
+    }
+
     #[test]
     fn parse_error_dash_separator() {
         assert!(VersionFormat::parse("YY-MM").is_err());
@@ -292,6 +418,12 @@ mod tests {
     #[test]
     fn parse_error_unknown_token() {
         assert!(VersionFormat::parse("YY.MM.PATCH").is_err());
+        let message = |format: &str| format!("{:#}", VersionFormat::parse(format).err().unwrap());
+        assert!(message("YY.MM.PATCH").contains("did you mean 'MICRO'?"));
+        assert!(message("YYY.MM.MICRO").contains("did you mean 'YYYY'?"));
+        assert!(message("yy.mm.MICRO").contains("did you mean 'YY'?"));
+        assert!(message("YY.M.MICRO").contains("did you mean 'MM'?"));
+        assert!(!message("YY.MM.QUUX").contains("did you mean"));
     }
 
     #[test]
@@ -366,6 +498,16 @@ mod tests {
         assert!(fmt.extract_values("26.2.5").is_none());
     }
 
+    #[test]
+    fn extract_values_ignores_build_metadata() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO+SHA").unwrap();
+        assert_eq!(
+            fmt.extract_values("26.2.5+a1b2c3d").unwrap(),
+            vec![26, 2, 5]
+        );
+        assert_eq!(fmt.extract_values("26.2.5").unwrap(), vec![26, 2, 5]);
+    }
+
     #[test]
     fn extract_values_too_few_parts() {
         let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
@@ -424,9 +566,9 @@ mod tests {
         let yy = (now.year() % 100) as u64;
         let mm = now.month() as u64;
 
-        assert!(fmt.ahead_of_today(&[yy + 1, 1, 0]));
-        assert!(!fmt.ahead_of_today(&[yy, mm, 0])); // same = not ahead
-        assert!(!fmt.ahead_of_today(&[yy - 1, mm, 0])); // past
+        assert!(fmt.ahead_of_today(&[yy + 1, 1, 0], 0));
+        assert!(!fmt.ahead_of_today(&[yy, mm, 0], 0)); // same = not ahead
+        assert!(!fmt.ahead_of_today(&[yy - 1, mm, 0], 0)); // past
     }
 
     #[test]
@@ -437,13 +579,89 @@ mod tests {
         let mm = now.month() as u64;
 
         if mm < 12 {
-            assert!(fmt.ahead_of_today(&[yy, mm + 1, 0]));
+            assert!(fmt.ahead_of_today(&[yy, mm + 1, 0], 0));
         }
         if mm > 1 {
-            assert!(!fmt.ahead_of_today(&[yy, mm - 1, 0]));
+            assert!(!fmt.ahead_of_today(&[yy, mm - 1, 0], 0));
         }
     }
 
+    // --- captured date ---
+
+    #[test]
+    fn builds_for_the_captured_date() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+        let fmt = VersionFormat::parse("YYYY.MM.DD.MICRO")
+            .unwrap()
+            .with_today(date);
+        assert_eq!(fmt.build_version(4), "2026.2.28.4");
+        assert!(fmt.matches_today(&[2026, 2, 28, 0]));
+        assert_eq!(fmt.period_start(), date);
+    }
+
+    #[test]
+    fn rolled_over_by_period() {
+        let today = chrono::Local::now().date_naive();
+        let other_day = today
+            .with_day(if today.day() == 1 { 2 } else { 1 })
+            .unwrap();
+
+        let monthly = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        assert!(!monthly.rolled_over());
+        assert!(!monthly.with_today(other_day).rolled_over());
+
+        let daily = VersionFormat::parse("YY.MM.DD.MICRO").unwrap();
+        assert!(daily.with_today(other_day).rolled_over());
+
+        let utc = VersionFormat::parse("YY.MM.DD.MICRO").unwrap().in_utc();
+        assert_eq!(utc.today, chrono::Utc::now().date_naive());
+        assert_eq!(utc.current_date(), utc.today);
+        assert!(!utc.rolled_over());
+    }
+
+    #[test]
+    fn ahead_of_today_within_skew() {
+        let fmt = VersionFormat::parse("YYYY.MM.DD.MICRO").unwrap();
+        let tomorrow = chrono::Local::now().date_naive() + chrono::TimeDelta::days(1);
+        let values = [
+            tomorrow.year() as u64,
+            tomorrow.month() as u64,
+            tomorrow.day() as u64,
+            0,
+        ];
+        assert!(fmt.ahead_of_today(&values, 0));
+        assert!(!fmt.ahead_of_today(&values, 26));
+
+        let later = tomorrow + chrono::TimeDelta::days(2);
+        let values = [
+            later.year() as u64,
+            later.month() as u64,
+            later.day() as u64,
+            0,
+        ];
+        assert!(fmt.ahead_of_today(&values, 26));
+    }
+
+    // --- period_start ---
+
+    #[test]
+    fn period_start_by_finest_component() {
+        let today = chrono::Local::now().date_naive();
+
+        let daily = VersionFormat::parse("YY.MM.DD.MICRO").unwrap();
+        assert_eq!(daily.period_start(), today);
+
+        let monthly = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        assert_eq!(monthly.period_start(), today.with_day(1).unwrap());
+
+        let yearly = VersionFormat::parse("YYYY.MICRO").unwrap();
+        let start = yearly.period_start();
+        assert_eq!(
+            (start.year(), start.month(), start.day()),
+            (today.year(), 1, 1)
+        );
+    }
+
     // --- micro_value ---
 
     #[test]
@@ -0,0 +1,421 @@
+use anyhow::{Context, Result, bail};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{CiProvider, GenerateArgs, GenerateCiArgs, GenerateCommand};
+use crate::config::Config;
+use crate::error::{ErrorKind, ResultExt};
+use crate::format::VersionFormat;
+use crate::npmrc::nerf_dart;
+use crate::target::write_atomic;
+use crate::version::{load_targets, registry_route};
+
+const DEFAULT_FORMAT: &str = "YY.MM.MICRO";
+const GITHUB_WORKFLOW: &str = ".github/workflows/release.yml";
+const GITLAB_CI: &str = ".gitlab-ci.yml";
+/// Where the GitLab job goes when the project already has a pipeline
+const GITLAB_INCLUDE: &str = ".gitlab/oneup-release.yml";
+const NPMJS: &str = "https://registry.npmjs.org";
+
+pub fn run(args: GenerateArgs) -> Result<()> {
+    match args.what {
+        GenerateCommand::Ci(args) => ci(args),
+    }
+}
+
+/// What the generated workflow runs, from the project's targets
+#[derive(Debug, Default)]
+struct Plan {
+    /// `--target` arguments for `oneup version`; empty when auto-detected
+    targets: Vec<String>,
+    /// Non-default version format, passed as ONEUP_FORMAT
+    format: Option<String>,
+    /// npm registry package.json publishes to
+    npm_registry: Option<String>,
+    /// Scope of the npm package (`@scope`), if any
+    npm_scope: Option<String>,
+    cargo: bool,
+    vscode: bool,
+    /// Targets that get the version but no publish step
+    unpublished: Vec<String>,
+}
+
+fn ci(args: GenerateCiArgs) -> Result<()> {
+    let dir = Path::new(".");
+    VersionFormat::parse(&args.format)?;
+    let config = Config::load(dir)?;
+    let targets = load_targets(&args.target, None, &config, None)?;
+
+    let mut plan = Plan {
+        targets: args
+            .target
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect(),
+        format: (args.format != DEFAULT_FORMAT).then(|| args.format.clone()),
+        ..Plan::default()
+    };
+    for (path, target) in &targets {
+        if target.is_vscode() {
+            plan.vscode = true;
+        } else if target.is_cargo() {
+            plan.cargo = true;
+        } else if target.is_published() {
+            let (registry, _) = registry_route(path, target, None, None)?;
+            plan.npm_scope = target.package_name.starts_with('@').then(|| {
+                target
+                    .package_name
+                    .split('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            });
+            plan.npm_registry = Some(registry);
+        } else {
+            plan.unpublished.push(path.display().to_string());
+        }
+    }
+
+    let provider = args.provider.unwrap_or_else(|| detect_provider(dir));
+    let (content, default_output) = match provider {
+        CiProvider::Github => (github(&plan), PathBuf::from(GITHUB_WORKFLOW)),
+        CiProvider::Gitlab => {
+            // Never clobber an existing pipeline by default: write a file to include
+            let output = if dir.join(GITLAB_CI).exists() {
+                PathBuf::from(GITLAB_INCLUDE)
+            } else {
+                PathBuf::from(GITLAB_CI)
+            };
+            (gitlab(&plan), output)
+        }
+    };
+
+    if args.dry_run {
+        print!("{content}");
+        return Ok(());
+    }
+
+    let output = args.out.unwrap_or(default_output);
+    if output.exists() && !args.force {
+        bail!(
+            "{} already exists; pass --force to overwrite it or --out to write elsewhere",
+            output.display()
+        );
+    }
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))
+            .kind(ErrorKind::Config)?;
+    }
+    write_atomic(&output, content.as_bytes())?;
+
+    if provider == CiProvider::Gitlab && output != Path::new(GITLAB_CI) {
+        eprintln!(
+            "add to {}:\n  include:\n    - local: {}",
+            GITLAB_CI,
+            output.display()
+        );
+    }
+    let secrets: Vec<&str> = [
+        (plan.npm_registry.is_some(), "NPM_TOKEN"),
+        (plan.cargo, "CARGO_REGISTRY_TOKEN"),
+        (plan.vscode, "VSCE_PAT"),
+    ]
+    .into_iter()
+    .filter_map(|(needed, name)| needed.then_some(name))
+    .collect();
+    if !secrets.is_empty() {
+        eprintln!(
+            "set {} as {}",
+            secrets.join(", "),
+            match provider {
+                CiProvider::Github => "repository secrets",
+                CiProvider::Gitlab => "masked CI/CD variables",
+            }
+        );
+    }
+    println!("{}", output.display());
+    Ok(())
+}
+
+/// GitLab when the project already has a GitLab pipeline, else GitHub.
+fn detect_provider(dir: &Path) -> CiProvider {
+    if dir.join(GITLAB_CI).exists() {
+        CiProvider::Gitlab
+    } else {
+        CiProvider::Github
+    }
+}
+
+/// The `oneup version` command line, ending in `| tail -1`.
+fn version_command(oneup: &str, plan: &Plan) -> String {
+    let mut command = format!("{oneup} version");
+    for target in &plan.targets {
+        let _ = write!(command, " --target {}", shell_quote(target));
+    }
+    command + " | tail -1"
+}
+
+/// `npm publish` with --access public for a first publish of a scoped package.
+fn npm_publish(plan: &Plan, extra: &str) -> String {
+    let access = if plan.npm_scope.is_some() {
+        " --access public"
+    } else {
+        ""
+    };
+    format!("npm publish{extra}{access}")
+}
+
+/// GitHub Actions workflow: run by hand from the Actions tab.
+fn github(plan: &Plan) -> String {
+    let mut y = String::new();
+    y.push_str("# Generated by `oneup generate ci`: run it from the Actions tab\n");
+    y.push_str("name: Release\n\non:\n  workflow_dispatch:\n\n");
+    y.push_str("permissions:\n  contents: write\n");
+    if plan.npm_registry.is_some() {
+        y.push_str("  id-token: write\n");
+    }
+    y.push_str("\njobs:\n  release:\n    runs-on: ubuntu-latest\n    steps:\n");
+    y.push_str("      - uses: actions/checkout@v4\n        with:\n          fetch-depth: 0\n\n");
+
+    y.push_str(
+        "      - uses: actions/setup-node@v4\n        with:\n          node-version: \"lts/*\"\n",
+    );
+    if let Some(registry) = &plan.npm_registry {
+        let _ = writeln!(y, "          registry-url: {registry}");
+        if let Some(scope) = plan.npm_scope.as_ref().filter(|_| registry != NPMJS) {
+            let _ = writeln!(y, "          scope: \"{scope}\"");
+        }
+    }
+    y.push('\n');
+
+    y.push_str("      - name: Calculate version\n        id: version\n        run: |\n");
+    let _ = writeln!(
+        y,
+        "          VERSION=$({})",
+        version_command("npx --yes @circlesac/oneup", plan)
+    );
+    y.push_str("          echo \"version=$VERSION\" >> \"$GITHUB_OUTPUT\"\n");
+    let mut env = Vec::new();
+    if let Some(format) = &plan.format {
+        env.push(format!("ONEUP_FORMAT: \"{format}\""));
+    }
+    if plan.npm_registry.is_some() {
+        env.push("NODE_AUTH_TOKEN: ${{ secrets.NPM_TOKEN }}".to_string());
+    }
+    if !env.is_empty() {
+        y.push_str("        env:\n");
+        for line in env {
+            let _ = writeln!(y, "          {line}");
+        }
+    }
+
+    if plan.npm_registry.is_some() {
+        let _ = write!(
+            y,
+            "\n      - name: Publish to npm\n        run: {}\n        env:\n          NODE_AUTH_TOKEN: ${{{{ secrets.NPM_TOKEN }}}}\n",
+            npm_publish(plan, " --provenance")
+        );
+    }
+    if plan.cargo {
+        y.push_str(
+            "\n      - name: Publish to crates.io\n        run: cargo publish --allow-dirty\n",
+        );
+        y.push_str(
+            "        env:\n          CARGO_REGISTRY_TOKEN: ${{ secrets.CARGO_REGISTRY_TOKEN }}\n",
+        );
+    }
+    if plan.vscode {
+        y.push_str("\n      - name: Publish to the VS Code Marketplace\n        run: npx --yes @vscode/vsce publish\n");
+        y.push_str("        env:\n          VSCE_PAT: ${{ secrets.VSCE_PAT }}\n");
+    }
+    for path in &plan.unpublished {
+        let _ = writeln!(
+            y,
+            "\n      # {path} gets the version; add its publish step here"
+        );
+    }
+
+    y.push_str("\n      - name: Push tag\n        run: |\n");
+    y.push_str("          git tag \"v$VERSION\"\n          git push origin \"v$VERSION\"\n");
+    y.push_str("        env:\n          VERSION: ${{ steps.version.outputs.version }}\n");
+
+    y.push_str("\n      - name: Release notes\n");
+    y.push_str(
+        "        run: gh release create \"v$VERSION\" --title \"v$VERSION\" --generate-notes\n",
+    );
+    y.push_str("        env:\n          GH_TOKEN: ${{ github.token }}\n");
+    y.push_str("          VERSION: ${{ steps.version.outputs.version }}\n");
+    y
+}
+
+/// GitLab CI jobs: run from Build > Pipelines > Run pipeline. The release
+/// job creates the tag through the API, so no push credentials are needed.
+fn gitlab(plan: &Plan) -> String {
+    let npm = plan.npm_registry.is_some() || plan.vscode;
+    let mut y = String::new();
+    y.push_str("# Generated by `oneup generate ci`: run a pipeline from Build > Pipelines\n");
+    y.push_str("release:\n  stage: deploy\n");
+    let _ = writeln!(
+        y,
+        "  image: {}",
+        if plan.cargo {
+            "rust:latest"
+        } else {
+            "node:lts"
+        }
+    );
+    y.push_str("  rules:\n    - if: $CI_PIPELINE_SOURCE == \"web\"\n");
+    y.push_str("  variables:\n    GIT_DEPTH: 0\n");
+    if let Some(format) = &plan.format {
+        let _ = writeln!(y, "    ONEUP_FORMAT: \"{format}\"");
+    }
+
+    // npx where node is the image; the release binary otherwise
+    let oneup = if plan.cargo {
+        y.push_str("  before_script:\n");
+        y.push_str("    - curl -fsSL https://github.com/circlesac/oneup/releases/latest/download/install.sh | sh\n");
+        if npm {
+            y.push_str("    - apt-get update && apt-get install -y nodejs npm\n");
+        }
+        "oneup"
+    } else {
+        "npx --yes @circlesac/oneup"
+    };
+
+    y.push_str("  script:\n");
+    let _ = writeln!(y, "    - VERSION=$({})", version_command(oneup, plan));
+    y.push_str("    - echo \"VERSION=$VERSION\" > release.env\n");
+    if let Some(registry) = &plan.npm_registry {
+        let _ = writeln!(
+            y,
+            "    - npm config set -- \"{}:_authToken\" \"$NPM_TOKEN\"",
+            nerf_dart(registry)
+        );
+        let _ = writeln!(
+            y,
+            "    - {}",
+            npm_publish(plan, &format!(" --registry {registry}"))
+        );
+    }
+    if plan.cargo {
+        y.push_str("    - cargo publish --allow-dirty\n");
+    }
+    if plan.vscode {
+        y.push_str("    - npx --yes @vscode/vsce publish\n");
+    }
+    for path in &plan.unpublished {
+        let _ = writeln!(
+            y,
+            "    # {path} gets the version; add its publish step here"
+        );
+    }
+    y.push_str("  artifacts:\n    reports:\n      dotenv: release.env\n\n");
+
+    y.push_str("release-notes:\n  stage: deploy\n");
+    y.push_str("  image: registry.gitlab.com/gitlab-org/release-cli:latest\n");
+    y.push_str("  needs: [release]\n");
+    y.push_str("  rules:\n    - if: $CI_PIPELINE_SOURCE == \"web\"\n");
+    y.push_str("  script:\n    - echo \"Releasing v$VERSION\"\n");
+    y.push_str("  release:\n    tag_name: v$VERSION\n    name: v$VERSION\n");
+    y.push_str("    description: \"Release v$VERSION\"\n");
+    y
+}
+
+/// Single-quote `s` for the shell unless it is a plain path.
+fn shell_quote(s: &str) -> String {
+    if s.chars()
+        .all(|c| c.is_ascii_alphanumeric() || "./_-@".contains(c))
+    {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn npm_plan() -> Plan {
+        Plan {
+            npm_registry: Some(NPMJS.to_string()),
+            npm_scope: Some("@circlesac".to_string()),
+            ..Plan::default()
+        }
+    }
+
+    #[test]
+    fn github_workflow_for_npm() {
+        let y = github(&npm_plan());
+        assert!(y.contains("VERSION=$(npx --yes @circlesac/oneup version | tail -1)"));
+        assert!(y.contains("registry-url: https://registry.npmjs.org\n"));
+        assert!(!y.contains("scope:"));
+        assert!(y.contains("run: npm publish --provenance --access public\n"));
+        assert!(y.contains("id-token: write"));
+        assert!(y.contains("git push origin \"v$VERSION\""));
+        assert!(y.contains("gh release create"));
+        assert!(!y.contains("cargo publish"));
+        assert!(!y.contains("ONEUP_FORMAT"));
+    }
+
+    #[test]
+    fn github_workflow_for_crate_with_targets_and_format() {
+        let plan = Plan {
+            targets: vec![
+                "Cargo.toml".to_string(),
+                "charts/app/Chart.yaml".to_string(),
+            ],
+            format: Some("YYYY.MM.DD.MICRO".to_string()),
+            cargo: true,
+            unpublished: vec!["charts/app/Chart.yaml".to_string()],
+            ..Plan::default()
+        };
+        let y = github(&plan);
+        assert!(y.contains("version --target Cargo.toml --target charts/app/Chart.yaml | tail -1"));
+        assert!(y.contains("ONEUP_FORMAT: \"YYYY.MM.DD.MICRO\""));
+        assert!(y.contains("cargo publish --allow-dirty"));
+        assert!(y.contains("# charts/app/Chart.yaml gets the version"));
+        assert!(!y.contains("npm publish"));
+        assert!(!y.contains("id-token"));
+    }
+
+    #[test]
+    fn gitlab_jobs_for_npm_registry() {
+        let plan = Plan {
+            npm_registry: Some("https://gitlab.com/api/v4/projects/42/packages/npm".to_string()),
+            ..Plan::default()
+        };
+        let y = gitlab(&plan);
+        assert!(y.contains("image: node:lts"));
+        assert!(y.contains(
+            "npm config set -- \"//gitlab.com/api/v4/projects/42/packages/npm/:_authToken\" \"$NPM_TOKEN\""
+        ));
+        assert!(y.contains(
+            "npm publish --registry https://gitlab.com/api/v4/projects/42/packages/npm\n"
+        ));
+        assert!(y.contains("dotenv: release.env"));
+        assert!(y.contains("tag_name: v$VERSION"));
+    }
+
+    #[test]
+    fn gitlab_jobs_for_crate_install_the_binary() {
+        let y = gitlab(&Plan {
+            cargo: true,
+            ..Plan::default()
+        });
+        assert!(y.contains("image: rust:latest"));
+        assert!(y.contains("install.sh | sh"));
+        assert!(y.contains("VERSION=$(oneup version | tail -1)"));
+        assert!(!y.contains("apt-get"));
+    }
+
+    #[test]
+    fn quotes_unusual_paths() {
+        assert_eq!(
+            shell_quote("packages/a/package.json"),
+            "packages/a/package.json"
+        );
+        assert_eq!(shell_quote("my app/package.json"), "'my app/package.json'");
+    }
+}
@@ -0,0 +1,228 @@
+// The git CLI by default; with the gitoxide feature the same queries run
+// in-process, for images that have no git binary
+#[cfg(not(feature = "gitoxide"))]
+mod cli;
+#[cfg(not(feature = "gitoxide"))]
+pub use cli::*;
+#[cfg(feature = "gitoxide")]
+mod gitoxide;
+#[cfg(feature = "gitoxide")]
+pub use gitoxide::*;
+
+#[cfg(all(test, feature = "git"))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::process::Command;
+
+    /// Fixtures are set up with the git CLI, whichever backend answers
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {} failed", args.join(" "));
+    }
+
+    fn init_repo(dir: &Path) {
+        for args in [
+            vec!["init", "-q"],
+            vec!["config", "user.email", "test@example.com"],
+            vec!["config", "user.name", "test"],
+            vec!["config", "commit.gpgsign", "false"],
+        ] {
+            git(dir, &args);
+        }
+    }
+
+    fn commit(dir: &Path, date: &str) {
+        let status = Command::new("git")
+            .args(["commit", "-q", "--allow-empty", "-m", "c"])
+            .env("GIT_COMMITTER_DATE", date)
+            .env("GIT_AUTHOR_DATE", date)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn commit_count_since_date() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        commit(dir.path(), "2026-01-15T12:00:00");
+        commit(dir.path(), "2026-02-01T09:00:00");
+        commit(dir.path(), "2026-02-03T09:00:00");
+
+        assert_eq!(
+            commit_count_since(dir.path(), "2026-02-01 00:00:00").unwrap(),
+            2
+        );
+        assert_eq!(
+            commit_count_since(dir.path(), "2026-01-01 00:00:00").unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn short_sha_of_head() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        commit(dir.path(), "2026-01-15T12:00:00");
+
+        let sha = short_sha(dir.path()).unwrap();
+        assert!(sha.len() >= 7);
+        assert!(sha.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn current_branch_name() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        commit(dir.path(), "2026-01-15T12:00:00");
+        git(dir.path(), &["checkout", "-q", "-b", "develop"]);
+        assert_eq!(
+            current_branch(dir.path()).unwrap().as_deref(),
+            Some("develop")
+        );
+
+        git(dir.path(), &["checkout", "-q", "--detach"]);
+        assert_eq!(current_branch(dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn uncommitted_changes_and_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        commit(dir.path(), "2026-01-15T12:00:00");
+        assert!(uncommitted_changes(dir.path()).unwrap().is_empty());
+
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            uncommitted_changes(dir.path()).unwrap(),
+            vec!["package.json"]
+        );
+
+        let root = repo_root(dir.path()).unwrap();
+        assert_eq!(
+            root.canonicalize().unwrap(),
+            dir.path().canonicalize().unwrap()
+        );
+
+        git(dir.path(), &["tag", "v26.2.5"]);
+        assert!(tag_exists(dir.path(), "v26.2.5").unwrap());
+        assert_eq!(tags_at_head(dir.path()).unwrap(), vec!["v26.2.5"]);
+        assert!(!tag_exists(dir.path(), "26.2.5").unwrap());
+    }
+
+    #[test]
+    fn uncommitted_changes_unquoted_and_renamed() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        commit(dir.path(), "2026-01-15T12:00:00");
+
+        git(dir.path(), &["mv", "Cargo.toml", "crate.toml"]);
+        std::fs::write(dir.path().join("my app.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("versión.txt"), "1").unwrap();
+        let mut changes = uncommitted_changes(dir.path()).unwrap();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec!["Cargo.toml", "crate.toml", "my app.json", "versión.txt"]
+        );
+    }
+
+    #[test]
+    fn uncommitted_changes_skip_ignored_and_list_untracked_files() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        commit(dir.path(), "2026-01-15T12:00:00");
+        // Would hide untracked files from a plain `git status`
+        git(dir.path(), &["config", "status.showUntrackedFiles", "no"]);
+
+        std::fs::create_dir_all(dir.path().join("target/release")).unwrap();
+        std::fs::write(dir.path().join("target/release/oneup"), "").unwrap();
+        std::fs::create_dir_all(dir.path().join("docs/api")).unwrap();
+        std::fs::write(dir.path().join("docs/api/index.md"), "").unwrap();
+        assert_eq!(
+            uncommitted_changes(dir.path()).unwrap(),
+            vec!["docs/api/index.md"]
+        );
+    }
+
+    #[test]
+    fn commits_since_last_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        commit(dir.path(), "2026-01-15T12:00:00");
+        assert_eq!(last_tag(dir.path()).unwrap(), None);
+
+        git(dir.path(), &["tag", "v26.1.0"]);
+        std::fs::create_dir(dir.path().join("a")).unwrap();
+        std::fs::write(dir.path().join("a/package.json"), "{}").unwrap();
+        git(dir.path(), &["add", "."]);
+        commit(dir.path(), "2026-01-16T12:00:00");
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+        git(dir.path(), &["add", "."]);
+        commit(dir.path(), "2026-01-17T12:00:00");
+
+        assert_eq!(last_tag(dir.path()).unwrap().as_deref(), Some("v26.1.0"));
+        let since = Some("v26.1.0");
+        assert_eq!(commit_subjects(dir.path(), since).unwrap(), vec!["c", "c"]);
+        assert_eq!(commit_subjects(dir.path(), None).unwrap().len(), 3);
+        assert_eq!(
+            commits_touching(dir.path(), since, Path::new("a")).unwrap(),
+            1
+        );
+        assert_eq!(
+            commits_touching(dir.path(), since, Path::new(".")).unwrap(),
+            2
+        );
+        assert_eq!(
+            commits_touching(dir.path(), None, Path::new("a")).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn tag_dates_and_lead_range() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        commit(dir.path(), "2026-01-15T12:00:00+00:00");
+        git(dir.path(), &["tag", "v26.1.0"]);
+        commit(dir.path(), "2026-02-01T09:00:00+00:00");
+        commit(dir.path(), "2026-02-03T09:00:00+00:00");
+        git(dir.path(), &["tag", "v26.2.0"]);
+
+        assert_eq!(
+            tags(dir.path()).unwrap(),
+            vec![
+                ("v26.1.0".to_string(), 1768478400),
+                ("v26.2.0".to_string(), 1770109200)
+            ]
+        );
+        assert_eq!(
+            oldest_commit_time(dir.path(), Some("v26.1.0"), "v26.2.0").unwrap(),
+            Some(1769936400)
+        );
+        assert_eq!(
+            oldest_commit_time(dir.path(), None, "v26.1.0").unwrap(),
+            Some(1768478400)
+        );
+        assert_eq!(
+            oldest_commit_time(dir.path(), Some("v26.2.0"), "v26.2.0").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn commit_count_outside_repo_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(commit_count_since(dir.path(), "2026-01-01").is_err());
+    }
+}
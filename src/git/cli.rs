@@ -0,0 +1,171 @@
+use anyhow::{Context, Result, anyhow};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{ErrorKind, ResultExt, not_built};
+
+/// Run a git command in `dir` and return its trimmed stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    Ok(git_raw(dir, args)?.trim().to_string())
+}
+
+/// Run a git command in `dir` and return its stdout as is.
+fn git_raw(dir: &Path, args: &[&str]) -> Result<String> {
+    if !cfg!(feature = "git") {
+        return Err(not_built("git"));
+    }
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("failed to run git")
+        .kind(ErrorKind::Git)?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .kind(ErrorKind::Git);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Number of commits reachable from HEAD committed at or after `since`
+/// (a date/time string git understands, e.g. "2026-02-01 00:00:00").
+pub fn commit_count_since(dir: &Path, since: &str) -> Result<u64> {
+    let since = format!("--since={since}");
+    let count = git(dir, &["rev-list", "--count", &since, "HEAD"])?;
+    count
+        .parse()
+        .with_context(|| format!("unexpected git rev-list output: {}", count))
+}
+
+/// Abbreviated hash of the HEAD commit.
+pub fn short_sha(dir: &Path) -> Result<String> {
+    git(dir, &["rev-parse", "--short", "HEAD"])
+}
+
+/// Full hash of the HEAD commit.
+pub fn head_sha(dir: &Path) -> Result<String> {
+    git(dir, &["rev-parse", "HEAD"])
+}
+
+/// Tags pointing at HEAD.
+pub fn tags_at_head(dir: &Path) -> Result<Vec<String>> {
+    let tags = git(dir, &["tag", "--points-at", "HEAD"])?;
+    Ok(tags.lines().map(String::from).collect())
+}
+
+/// Name of the checked-out branch, or None on a detached HEAD.
+pub fn current_branch(dir: &Path) -> Result<Option<String>> {
+    let branch = git(dir, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    Ok((branch != "HEAD").then_some(branch))
+}
+
+/// Top-level directory of the repository containing `dir`.
+pub fn repo_root(dir: &Path) -> Result<PathBuf> {
+    git(dir, &["rev-parse", "--show-toplevel"]).map(PathBuf::from)
+}
+
+/// Paths with uncommitted changes (staged, unstaged or untracked), relative
+/// to the repository root. Ignored files are left out; untracked files are
+/// listed one by one, never as their directory.
+pub fn uncommitted_changes(dir: &Path) -> Result<Vec<String>> {
+    // -z keeps paths unquoted: "XY path\0", and a rename or copy is
+    // followed by the path it came from. The untracked and ignored modes
+    // are explicit so status.showUntrackedFiles in the user's config can't
+    // hide files or report whole directories.
+    let status = git_raw(
+        dir,
+        &[
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--untracked-files=all",
+            "--ignored=no",
+        ],
+    )?;
+    let mut entries = status.split('\0').filter(|entry| !entry.is_empty());
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next() {
+        let (code, path) = entry
+            .split_at_checked(3)
+            .with_context(|| format!("unexpected git status output: {}", entry))?;
+        paths.push(path.to_string());
+        if code.contains(['R', 'C']) {
+            let source = entries
+                .next()
+                .with_context(|| format!("unexpected git status output: {}", entry))?;
+            // A copy leaves its source as it was
+            if code.contains('R') {
+                paths.push(source.to_string());
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Whether a tag named `name` exists locally.
+pub fn tag_exists(dir: &Path, name: &str) -> Result<bool> {
+    Ok(!git(dir, &["tag", "--list", name])?.is_empty())
+}
+
+/// Most recently created tag reachable from HEAD, or None without tags.
+pub fn last_tag(dir: &Path) -> Result<Option<String>> {
+    let tags = git(dir, &["tag", "--merged", "HEAD", "--sort=-creatordate"])?;
+    Ok(tags.lines().next().map(String::from))
+}
+
+/// Every tag with its creation time (seconds since the epoch), oldest
+/// first. Annotated tags date from the tagger, lightweight ones from their commit.
+pub fn tags(dir: &Path) -> Result<Vec<(String, i64)>> {
+    let refs = git(
+        dir,
+        &[
+            "for-each-ref",
+            "--sort=creatordate",
+            "--format=%(creatordate:unix) %(refname:short)",
+            "refs/tags",
+        ],
+    )?;
+    refs.lines()
+        .map(|line| {
+            let (time, name) = line
+                .split_once(' ')
+                .with_context(|| format!("unexpected git for-each-ref output: {}", line))?;
+            let time = time
+                .parse()
+                .with_context(|| format!("unexpected git for-each-ref output: {}", line))?;
+            Ok((name.to_string(), time))
+        })
+        .collect()
+}
+
+/// Commit time of the oldest commit reachable from `to` but not from
+/// `from` (all of history when None), or None when the range is empty.
+pub fn oldest_commit_time(dir: &Path, from: Option<&str>, to: &str) -> Result<Option<i64>> {
+    let range = from.map_or_else(|| to.to_string(), |from| format!("{from}..{to}"));
+    let times = git(dir, &["log", "--format=%ct", &range])?;
+    Ok(times.lines().filter_map(|t| t.parse().ok()).min())
+}
+
+/// Subject lines of the commits after `since` (all of history when None),
+/// newest first.
+pub fn commit_subjects(dir: &Path, since: Option<&str>) -> Result<Vec<String>> {
+    let range = since.map_or_else(|| "HEAD".to_string(), |tag| format!("{tag}..HEAD"));
+    let log = git(dir, &["log", "--format=%s", &range])?;
+    Ok(log.lines().map(String::from).collect())
+}
+
+/// Commits after `since` (all of history when None) that touch `path`.
+pub fn commits_touching(dir: &Path, since: Option<&str>, path: &Path) -> Result<u64> {
+    let range = since.map_or_else(|| "HEAD".to_string(), |tag| format!("{tag}..HEAD"));
+    let path = path.to_string_lossy();
+    let count = git(dir, &["rev-list", "--count", &range, "--", &path])?;
+    count
+        .parse()
+        .with_context(|| format!("unexpected git rev-list output: {}", count))
+}
@@ -0,0 +1,338 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use gix::ObjectId;
+use gix::bstr::ByteSlice;
+use gix::revision::walk::Sorting;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{ErrorKind, ResultExt};
+
+/// The repository containing `dir`.
+fn open(dir: &Path) -> Result<gix::Repository> {
+    gix::discover(dir)
+        .with_context(|| format!("{} is not in a git repository", dir.display()))
+        .kind(ErrorKind::Git)
+}
+
+/// Run a query, reporting failures like the git command it stands in for.
+fn query<T>(command: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    f().with_context(|| format!("git {command} failed"))
+        .kind(ErrorKind::Git)
+}
+
+/// Number of commits reachable from HEAD committed at or after `since`
+/// (local time, "2026-02-01 00:00:00" or "2026-02-01", as git reads it).
+pub fn commit_count_since(dir: &Path, since: &str) -> Result<u64> {
+    let since = parse_since(since)?;
+    let repo = open(dir)?;
+    query("rev-list --count --since", || {
+        let walk = repo
+            .head_id()?
+            .ancestors()
+            .sorting(Sorting::ByCommitTimeCutoff {
+                order: Default::default(),
+                seconds: since,
+            })
+            .all()?;
+        let mut count = 0;
+        for info in walk {
+            // The cutoff prunes parents; HEAD itself may still be older
+            if info?.commit_time() >= since {
+                count += 1;
+            }
+        }
+        Ok(count)
+    })
+}
+
+/// Abbreviated hash of the HEAD commit.
+pub fn short_sha(dir: &Path) -> Result<String> {
+    let repo = open(dir)?;
+    query("rev-parse --short HEAD", || {
+        Ok(repo.head_id()?.shorten()?.to_string())
+    })
+}
+
+/// Full hash of the HEAD commit.
+pub fn head_sha(dir: &Path) -> Result<String> {
+    let repo = open(dir)?;
+    query("rev-parse HEAD", || Ok(repo.head_id()?.to_string()))
+}
+
+/// Tags pointing at HEAD.
+pub fn tags_at_head(dir: &Path) -> Result<Vec<String>> {
+    let repo = open(dir)?;
+    query("tag --points-at HEAD", || {
+        let head = repo.head_id()?.detach();
+        let mut tags = Vec::new();
+        for tag in repo.references()?.tags()? {
+            let mut tag = tag.map_err(|e| anyhow!(e))?;
+            if tag.peel_to_id()?.detach() == head {
+                tags.push(tag.name().shorten().to_string());
+            }
+        }
+        tags.sort();
+        Ok(tags)
+    })
+}
+
+/// Name of the checked-out branch, or None on a detached HEAD.
+pub fn current_branch(dir: &Path) -> Result<Option<String>> {
+    let repo = open(dir)?;
+    query("rev-parse --abbrev-ref HEAD", || {
+        Ok(repo.head_name()?.map(|name| name.shorten().to_string()))
+    })
+}
+
+/// Top-level directory of the repository containing `dir`.
+pub fn repo_root(dir: &Path) -> Result<PathBuf> {
+    let repo = open(dir)?;
+    query("rev-parse --show-toplevel", || {
+        repo.workdir()
+            .map(Path::to_path_buf)
+            .context("bare repository has no working tree")
+    })
+}
+
+/// Paths with uncommitted changes (staged, unstaged or untracked), relative
+/// to the repository root. Ignored files are left out; untracked files are
+/// listed one by one, never as their directory.
+pub fn uncommitted_changes(dir: &Path) -> Result<Vec<String>> {
+    let repo = open(dir)?;
+    query("status --porcelain --untracked-files=all", || {
+        // status() drops the directory walk when status.showUntrackedFiles
+        // is "no"; put it back so untracked files are still seen
+        let dirwalk = repo.dirwalk_options()?;
+        let status = repo
+            .status(gix::progress::Discard)?
+            .index_worktree_options_mut(|options| options.dirwalk_options = Some(dirwalk))
+            .untracked_files(gix::status::UntrackedFiles::Files)
+            .into_iter(None)?;
+        let mut paths = Vec::new();
+        for item in status {
+            let item = item?;
+            paths.push(item.location().to_str_lossy().into_owned());
+            // A rename changes the path it came from too, as git reports
+            if let gix::status::Item::TreeIndex(gix::diff::index::Change::Rewrite {
+                source_location,
+                copy: false,
+                ..
+            }) = &item
+            {
+                paths.push(source_location.to_str_lossy().into_owned());
+            }
+        }
+        // A file both staged and modified is reported once, as git does
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    })
+}
+
+/// Whether a tag named `name` exists locally.
+pub fn tag_exists(dir: &Path, name: &str) -> Result<bool> {
+    let repo = open(dir)?;
+    query("tag --list", || {
+        Ok(repo
+            .try_find_reference(format!("refs/tags/{name}").as_str())?
+            .is_some())
+    })
+}
+
+/// Most recently created tag reachable from HEAD, or None without tags.
+/// Annotated tags date from the tagger, lightweight ones from their commit.
+pub fn last_tag(dir: &Path) -> Result<Option<String>> {
+    let repo = open(dir)?;
+    query("tag --merged HEAD", || {
+        let mut reachable = HashSet::new();
+        for info in repo.head_id()?.ancestors().all()? {
+            reachable.insert(info?.id);
+        }
+
+        let mut tags: Vec<_> = tag_times(&repo)?
+            .into_iter()
+            .filter(|(_, commit, _)| reachable.contains(commit))
+            .collect();
+        // Newest first; ties in name order, like --sort=-creatordate
+        tags.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        Ok(tags.into_iter().next().map(|(name, _, _)| name))
+    })
+}
+
+/// Every tag with its creation time (seconds since the epoch), oldest
+/// first. Annotated tags date from the tagger, lightweight ones from their commit.
+pub fn tags(dir: &Path) -> Result<Vec<(String, i64)>> {
+    let repo = open(dir)?;
+    query("for-each-ref refs/tags", || {
+        let mut tags = tag_times(&repo)?;
+        tags.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+        Ok(tags
+            .into_iter()
+            .map(|(name, _, time)| (name, time))
+            .collect())
+    })
+}
+
+/// Commit time of the oldest commit reachable from `to` but not from
+/// `from` (all of history when None), or None when the range is empty.
+pub fn oldest_commit_time(dir: &Path, from: Option<&str>, to: &str) -> Result<Option<i64>> {
+    let repo = open(dir)?;
+    query("log", || {
+        let to = repo.rev_parse_single(to)?.object()?.peel_to_commit()?;
+        // Date order, so each commit comes with its time
+        let mut walk = repo
+            .rev_walk([to.id])
+            .sorting(Sorting::ByCommitTime(Default::default()));
+        if let Some(from) = from {
+            let from = repo.rev_parse_single(from)?.object()?.peel_to_commit()?;
+            walk = walk.with_hidden([from.id]);
+        }
+        let mut oldest = None;
+        for info in walk.all()? {
+            let time = info?.commit_time();
+            oldest = Some(oldest.map_or(time, |t: i64| t.min(time)));
+        }
+        Ok(oldest)
+    })
+}
+
+/// Name, commit and creation time of every tag.
+fn tag_times(repo: &gix::Repository) -> Result<Vec<(String, ObjectId, i64)>> {
+    let mut tags = Vec::new();
+    for tag in repo.references()?.tags()? {
+        let mut tag = tag.map_err(|e| anyhow!(e))?;
+        let target = tag.target().try_id().map(ToOwned::to_owned);
+        let commit = tag.peel_to_id()?.detach();
+        let created = match target {
+            Some(id) => created_at(repo, id)?,
+            None => 0,
+        };
+        tags.push((tag.name().shorten().to_string(), commit, created));
+    }
+    Ok(tags)
+}
+
+/// Subject lines of the commits after `since` (all of history when None),
+/// newest first.
+pub fn commit_subjects(dir: &Path, since: Option<&str>) -> Result<Vec<String>> {
+    let repo = open(dir)?;
+    query("log", || {
+        let mut walk = repo
+            .head_id()?
+            .ancestors()
+            .sorting(Sorting::ByCommitTime(Default::default()));
+        if let Some(since) = since {
+            let since = repo.rev_parse_single(since)?.object()?.peel_to_commit()?;
+            walk = walk.with_hidden([since.id]);
+        }
+        let mut subjects = Vec::new();
+        for info in walk.all()? {
+            let commit = info?.object()?;
+            subjects.push(commit.message()?.summary().to_str_lossy().into_owned());
+        }
+        Ok(subjects)
+    })
+}
+
+/// Commits after `since` (all of history when None) that touch `path`.
+pub fn commits_touching(dir: &Path, since: Option<&str>, path: &Path) -> Result<u64> {
+    let repo = open(dir)?;
+    let path = relative_path(&repo, dir, path)?;
+    query("rev-list --count", || {
+        let mut walk = repo.head_id()?.ancestors();
+        if let Some(since) = since {
+            let since = repo.rev_parse_single(since)?.object()?.peel_to_commit()?;
+            walk = walk.with_hidden([since.id]);
+        }
+
+        let mut count = 0;
+        for info in walk.all()? {
+            let info = info?;
+            let entry = tree_entry(&repo, info.id, &path)?;
+            // Like git's history simplification: a commit counts when it
+            // differs from every parent at `path`
+            let mut touched = true;
+            let mut parents = info.parent_ids.iter().peekable();
+            if parents.peek().is_none() {
+                touched = entry.is_some();
+            }
+            for parent in parents {
+                if tree_entry(&repo, *parent, &path)? == entry {
+                    touched = false;
+                    break;
+                }
+            }
+            if touched {
+                count += 1;
+            }
+        }
+        Ok(count)
+    })
+}
+
+/// Object at `path` in the tree of `commit`; the root tree for an empty path.
+fn tree_entry(repo: &gix::Repository, commit: ObjectId, path: &Path) -> Result<Option<ObjectId>> {
+    let tree = repo.find_commit(commit)?.tree()?;
+    if path.as_os_str().is_empty() {
+        return Ok(Some(tree.id));
+    }
+    Ok(tree.lookup_entry_by_path(path)?.map(|e| e.object_id()))
+}
+
+/// `path` (relative to `dir`, as a git pathspec would be) relative to the
+/// repository root.
+fn relative_path(repo: &gix::Repository, dir: &Path, path: &Path) -> Result<PathBuf> {
+    let root = repo
+        .workdir()
+        .context("bare repository has no working tree")
+        .kind(ErrorKind::Git)?
+        .canonicalize()?;
+    let full = dir
+        .canonicalize()
+        .with_context(|| format!("failed to resolve {}", dir.display()))?
+        .join(path);
+    let relative = full
+        .strip_prefix(&root)
+        .with_context(|| format!("{} is outside the repository", path.display()))
+        .kind(ErrorKind::Git)?;
+    let mut normal = PathBuf::new();
+    for component in relative.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+    Ok(normal)
+}
+
+/// Creation time of the object a tag points at directly: the tagger date of
+/// an annotated tag, the committer date of a commit.
+fn created_at(repo: &gix::Repository, id: ObjectId) -> Result<i64> {
+    let object = repo.find_object(id)?;
+    if object.kind == gix::object::Kind::Tag {
+        let tag = object.into_tag();
+        return Ok(match tag.tagger()? {
+            Some(tagger) => tagger.time()?.seconds,
+            None => 0,
+        });
+    }
+    Ok(object.peel_to_commit()?.time()?.seconds)
+}
+
+/// `since` in seconds since the epoch, read in local time like git does.
+fn parse_since(since: &str) -> Result<i64> {
+    let time = NaiveDateTime::parse_from_str(since, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(since, "%Y-%m-%d").map(|d| d.and_time(Default::default()))
+        })
+        .with_context(|| format!("invalid date for git: {since}"))?;
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|t| t.timestamp())
+        .with_context(|| format!("{since} does not exist in the local time zone"))
+}
@@ -0,0 +1,368 @@
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine;
+use serde_json::Value;
+
+use crate::error::{ErrorKind, ResultExt};
+use crate::http;
+use crate::redact::redact;
+use crate::registry::PackageInfo;
+use crate::resolve::compare_versions;
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+const PER_PAGE: usize = 100;
+
+/// A GitHub repository: its tags read as published versions, the events
+/// that announce a release, and files committed through the contents API
+pub struct GitHubClient {
+    http: reqwest::blocking::Client,
+    api_url: String,
+    repo: String,
+    token: Option<String>,
+}
+
+impl GitHubClient {
+    /// `repo` is `owner/name`. GITHUB_API_URL (set by Actions on GitHub
+    /// Enterprise Server) and GITHUB_TOKEN (or GH_TOKEN) are picked up from
+    /// the environment.
+    pub fn new(repo: &str) -> Result<Self> {
+        if repo.split('/').count() != 2 || repo.starts_with('/') || repo.ends_with('/') {
+            bail!("invalid GitHub repository '{}' (expected owner/repo)", repo);
+        }
+        let api_url = std::env::var("GITHUB_API_URL")
+            .unwrap_or_else(|_| DEFAULT_API_URL.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        Ok(Self {
+            http: http::client(),
+            api_url,
+            repo: repo.to_string(),
+            token: ["GITHUB_TOKEN", "GH_TOKEN"]
+                .iter()
+                .find_map(|name| std::env::var(name).ok().filter(|t| !t.is_empty())),
+        })
+    }
+
+    pub fn get_package(&self, verbose: bool) -> Result<PackageInfo> {
+        let mut names = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "{}/repos/{}/tags?per_page={}&page={}",
+                self.api_url, self.repo, PER_PAGE, page
+            );
+            if verbose {
+                eprintln!("[registry] GET {}", redact(&url));
+            }
+
+            let mut req = self
+                .http
+                .get(&url)
+                .header("Accept", "application/vnd.github+json");
+            if let Some(token) = &self.token {
+                req = req.header("Authorization", format!("Bearer {token}"));
+            }
+            let resp = req
+                .send()
+                .with_context(|| format!("failed to list tags of {}", self.repo))
+                .kind(ErrorKind::Network)?;
+
+            if resp.status() == reqwest::StatusCode::NOT_FOUND {
+                return Err(anyhow!(
+                    "GitHub repository {} not found (private repositories need GITHUB_TOKEN)",
+                    self.repo
+                ))
+                .kind(ErrorKind::Config);
+            }
+            if !resp.status().is_success() {
+                let kind = match resp.status() {
+                    reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                        ErrorKind::Auth
+                    }
+                    _ => ErrorKind::Network,
+                };
+                return Err(anyhow!(
+                    "failed to list tags of {}: HTTP {}",
+                    self.repo,
+                    resp.status()
+                ))
+                .kind(kind);
+            }
+
+            let body: serde_json::Value = resp
+                .json()
+                .context("failed to parse GitHub tags")
+                .kind(ErrorKind::Network)?;
+            let tags = body
+                .as_array()
+                .context("GitHub tags response is not a list")?;
+            names.extend(
+                tags.iter()
+                    .filter_map(|t| t.get("name").and_then(|n| n.as_str()))
+                    .map(String::from),
+            );
+            if tags.len() < PER_PAGE {
+                break;
+            }
+        }
+
+        let info = versions_from_tags(&names);
+        if verbose {
+            match &info {
+                PackageInfo::Found {
+                    versions, latest, ..
+                } => {
+                    eprintln!("[registry] latest: {}", latest);
+                    eprintln!("[registry] total versions: {}", versions.len());
+                }
+                PackageInfo::NotFound => eprintln!("[registry] no version tags"),
+            }
+        }
+        Ok(info)
+    }
+
+    /// Fire a `repository_dispatch` event of type `event` carrying `payload`.
+    pub fn dispatch(&self, event: &str, payload: &Value) -> Result<()> {
+        let body = serde_json::json!({ "event_type": event, "client_payload": payload });
+        self.send(
+            reqwest::Method::POST,
+            "dispatches",
+            Some(&body),
+            "fire repository_dispatch",
+        )?;
+        Ok(())
+    }
+
+    /// Create a deployment of `git_ref` to `environment`; returns its id.
+    pub fn create_deployment(
+        &self,
+        git_ref: &str,
+        environment: &str,
+        payload: &Value,
+    ) -> Result<u64> {
+        let body = serde_json::json!({
+            "ref": git_ref,
+            "environment": environment,
+            "payload": payload,
+            "description": payload.get("version").and_then(Value::as_str).map(|v| format!("oneup {v}")),
+            // The release is already tagged; don't merge the default branch in
+            "auto_merge": false,
+            "required_contexts": [],
+        });
+        let answer = self.send(
+            reqwest::Method::POST,
+            "deployments",
+            Some(&body),
+            "create a deployment",
+        )?;
+        answer
+            .get("id")
+            .and_then(Value::as_u64)
+            .with_context(|| {
+                format!(
+                    "GitHub did not create the deployment: {}",
+                    answer
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("no id in the answer")
+                )
+            })
+            .kind(ErrorKind::Network)
+    }
+
+    /// Read `path` from the repository at `branch` (default branch if None);
+    /// its text and blob SHA, which [`put_file`](Self::put_file) needs to
+    /// replace it.
+    pub fn get_file(&self, path: &str, branch: Option<&str>) -> Result<(String, String)> {
+        let mut endpoint = format!("contents/{}", path.trim_start_matches('/'));
+        if let Some(branch) = branch {
+            endpoint = format!("{endpoint}?ref={branch}");
+        }
+        let answer = self.send(
+            reqwest::Method::GET,
+            &endpoint,
+            None,
+            &format!("read {path}"),
+        )?;
+        let (Some(content), Some(sha)) = (
+            answer.get("content").and_then(Value::as_str),
+            answer.get("sha").and_then(Value::as_str),
+        ) else {
+            return Err(anyhow!("{} in {} is not a file", path, self.repo)).kind(ErrorKind::Config);
+        };
+        // The API wraps the base64 at 60 columns
+        let content: String = content.split_whitespace().collect();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(content)
+            .with_context(|| format!("failed to decode {} from {}", path, self.repo))
+            .kind(ErrorKind::Network)?;
+        let text = String::from_utf8(bytes)
+            .with_context(|| format!("{} in {} is not UTF-8", path, self.repo))
+            .kind(ErrorKind::Config)?;
+        Ok((text, sha.to_string()))
+    }
+
+    /// Commit `content` as `path` on `branch` (default branch if None),
+    /// replacing the blob `sha`; returns the new commit's SHA.
+    pub fn put_file(
+        &self,
+        path: &str,
+        content: &str,
+        sha: &str,
+        message: &str,
+        branch: Option<&str>,
+    ) -> Result<String> {
+        let mut body = serde_json::json!({
+            "message": message,
+            "content": base64::engine::general_purpose::STANDARD.encode(content),
+            "sha": sha,
+        });
+        if let Some(branch) = branch {
+            body["branch"] = Value::from(branch);
+        }
+        let answer = self.send(
+            reqwest::Method::PUT,
+            &format!("contents/{}", path.trim_start_matches('/')),
+            Some(&body),
+            &format!("commit {path}"),
+        )?;
+        answer
+            .pointer("/commit/sha")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .with_context(|| format!("GitHub did not commit {}", path))
+            .kind(ErrorKind::Network)
+    }
+
+    /// Send a request to `/repos/{repo}/{path}` with `body` as JSON; the JSON
+    /// answer (null when empty). Anything but GET needs a token. `what` names
+    /// the action in errors.
+    fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&Value>,
+        what: &str,
+    ) -> Result<Value> {
+        if self.token.is_none() && method != reqwest::Method::GET {
+            return Err(anyhow!(
+                "cannot {} on {} without GITHUB_TOKEN (or GH_TOKEN)",
+                what,
+                self.repo
+            ))
+            .kind(ErrorKind::Auth);
+        }
+        let url = format!("{}/repos/{}/{}", self.api_url, self.repo, path);
+        let mut req = self
+            .http
+            .request(method, &url)
+            .header("Accept", "application/vnd.github+json");
+        if let Some(token) = &self.token {
+            req = req.header("Authorization", format!("Bearer {token}"));
+        }
+        if let Some(body) = body {
+            req = req.json(body);
+        }
+        let resp = req
+            .send()
+            .with_context(|| format!("failed to {} on {}", what, self.repo))
+            .kind(ErrorKind::Network)?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let kind = match status {
+                reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                    ErrorKind::Auth
+                }
+                // Unknown repository or ref, or a token without access to it
+                reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::UNPROCESSABLE_ENTITY => {
+                    ErrorKind::Config
+                }
+                _ => ErrorKind::Network,
+            };
+            let message = resp
+                .json::<Value>()
+                .ok()
+                .and_then(|v| v.get("message").and_then(Value::as_str).map(String::from))
+                .unwrap_or_default();
+            return Err(anyhow!(
+                "failed to {} on {}: HTTP {} {}",
+                what,
+                self.repo,
+                status,
+                message
+            ))
+            .kind(kind);
+        }
+        let text = resp
+            .text()
+            .with_context(|| format!("failed to read the response from {}", url))
+            .kind(ErrorKind::Network)?;
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text)
+            .with_context(|| format!("failed to parse the response from {}", url))
+            .kind(ErrorKind::Network)
+    }
+}
+
+/// Versions from tag names (`v26.2.5` and `26.2.5`; other tags are ignored).
+/// The latest release is the highest tag without a prerelease suffix.
+fn versions_from_tags(names: &[String]) -> PackageInfo {
+    let versions: Vec<String> = names
+        .iter()
+        .map(|n| n.strip_prefix('v').unwrap_or(n))
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+        .map(String::from)
+        .collect();
+    if versions.is_empty() {
+        return PackageInfo::NotFound;
+    }
+
+    let latest = versions
+        .iter()
+        .filter(|v| !v.contains('-'))
+        .max_by(|a, b| compare_versions(a, b))
+        .cloned()
+        .unwrap_or_else(|| "0.0.0".to_string());
+    PackageInfo::Found {
+        versions,
+        latest,
+        published: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_from_tag_names() {
+        let names: Vec<String> = ["v26.2.1", "26.2.3", "v26.2.4-beta.0", "nightly", "v26.1.9"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        let PackageInfo::Found {
+            versions, latest, ..
+        } = versions_from_tags(&names)
+        else {
+            panic!("expected versions");
+        };
+        assert_eq!(
+            versions,
+            vec!["26.2.1", "26.2.3", "26.2.4-beta.0", "26.1.9"]
+        );
+        assert_eq!(latest, "26.2.3");
+        assert!(matches!(
+            versions_from_tags(&["nightly".to_string()]),
+            PackageInfo::NotFound
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_repo() {
+        assert!(GitHubClient::new("owner").is_err());
+        assert!(GitHubClient::new("owner/repo/extra").is_err());
+        assert!(GitHubClient::new("owner/repo").is_ok());
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::{Context, Result, bail};
+
+const DEFAULT_API_URL: &str = "https://gitlab.com/api/v4";
+
+/// Expand a GitLab registry shorthand passed as --registry:
+/// `gitlab` (this CI project), `gitlab:project/<id or path>`,
+/// `gitlab:group/<id or path>` or `gitlab:instance`. Returns None for plain URLs.
+/// The API root comes from CI_API_V4_URL when set, else gitlab.com.
+pub fn expand_registry(spec: &str) -> Result<Option<String>> {
+    if spec != "gitlab" && !spec.starts_with("gitlab:") {
+        return Ok(None);
+    }
+    let api_url = std::env::var("CI_API_V4_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+    let project_id = std::env::var("CI_PROJECT_ID").ok();
+    registry_url(spec, &api_url, project_id.as_deref()).map(Some)
+}
+
+fn registry_url(spec: &str, api_url: &str, project_id: Option<&str>) -> Result<String> {
+    let api_url = api_url.trim_end_matches('/');
+    let scope = spec.strip_prefix("gitlab:").unwrap_or("");
+    let url = match scope.split_once('/') {
+        _ if scope.is_empty() => {
+            let id = project_id
+                .context("--registry gitlab needs CI_PROJECT_ID (or use gitlab:project/<id>)")?;
+            format!("{}/projects/{}/packages/npm", api_url, id)
+        }
+        _ if scope == "instance" => format!("{}/packages/npm", api_url),
+        Some(("project", id)) if !id.is_empty() => {
+            format!("{}/projects/{}/packages/npm", api_url, encode_path(id))
+        }
+        Some(("group", id)) if !id.is_empty() => {
+            format!("{}/groups/{}/-/packages/npm", api_url, encode_path(id))
+        }
+        _ => bail!(
+            "invalid GitLab registry '{}' (expected gitlab, gitlab:project/<id>, gitlab:group/<id> or gitlab:instance)",
+            spec
+        ),
+    };
+    Ok(url)
+}
+
+/// Whether `url` is a GitLab npm package registry endpoint.
+#[cfg(feature = "npm")]
+pub fn is_registry_url(url: &str) -> bool {
+    url.contains("/api/v4/") && url.trim_end_matches('/').ends_with("/packages/npm")
+}
+
+/// GitLab-specific auth header when no .npmrc token applies: the CI job
+/// token (`Job-Token`) inside GitLab CI, else GITLAB_TOKEN (`Private-Token`).
+#[cfg(feature = "npm")]
+pub fn auth_header() -> Option<(&'static str, String)> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    var("CI_JOB_TOKEN")
+        .map(|t| ("Job-Token", t))
+        .or_else(|| var("GITLAB_TOKEN").map(|t| ("Private-Token", t)))
+}
+
+/// Project and group paths go into the URL as one encoded segment.
+fn encode_path(id: &str) -> String {
+    id.replace('/', "%2F")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const API: &str = "https://gitlab.example.com/api/v4";
+
+    #[test]
+    fn registry_shorthands() {
+        assert_eq!(
+            registry_url("gitlab", API, Some("42")).unwrap(),
+            "https://gitlab.example.com/api/v4/projects/42/packages/npm"
+        );
+        assert!(registry_url("gitlab", API, None).is_err());
+        assert_eq!(
+            registry_url("gitlab:project/org/app", API, None).unwrap(),
+            "https://gitlab.example.com/api/v4/projects/org%2Fapp/packages/npm"
+        );
+        assert_eq!(
+            registry_url("gitlab:group/org", API, None).unwrap(),
+            "https://gitlab.example.com/api/v4/groups/org/-/packages/npm"
+        );
+        assert_eq!(
+            registry_url("gitlab:instance", API, None).unwrap(),
+            "https://gitlab.example.com/api/v4/packages/npm"
+        );
+        assert!(registry_url("gitlab:team/x", API, None).is_err());
+        assert!(
+            expand_registry("https://registry.npmjs.org")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "npm")]
+    fn detects_registry_urls() {
+        assert!(is_registry_url(
+            "https://gitlab.com/api/v4/projects/42/packages/npm/"
+        ));
+        assert!(is_registry_url(
+            "https://gitlab.com/api/v4/groups/org/-/packages/npm"
+        ));
+        assert!(!is_registry_url("https://registry.npmjs.org"));
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result, bail};
+use std::ops::Range;
+
+use crate::scan;
+
+/// Google Play's upper bound for versionCode
+const MAX_VERSION_CODE: u64 = 2_100_000_000;
+
+/// Current `versionName` in a build.gradle / build.gradle.kts file.
+pub fn version_name(content: &str) -> Option<&str> {
+    find_value(content, "versionName", true).map(|r| &content[r])
+}
+
+/// Current `versionCode`, if the file sets one.
+pub fn version_code(content: &str) -> Option<u64> {
+    find_value(content, "versionCode", false).and_then(|r| content[r].parse().ok())
+}
+
+/// Set `versionName` to `version` and, when the file has a `versionCode`,
+/// replace it with the code derived from `version`. The new code must be
+/// higher than the current one.
+pub fn update(content: &str, version: &str) -> Result<String> {
+    let name = find_value(content, "versionName", true)
+        .context("no versionName assignment found (expected versionName \"...\")")?;
+
+    let mut edits = vec![(name, version.to_string())];
+    if let Some(range) = find_value(content, "versionCode", false) {
+        let code = derive_code(version)?;
+        if let Some(current) = version_code(content)
+            && code <= current
+        {
+            bail!(
+                "versionCode {} derived from {} does not increase over the current {}",
+                code,
+                version,
+                current
+            );
+        }
+        edits.push((range, code.to_string()));
+    }
+
+    // Apply back to front so earlier ranges stay valid
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    let mut output = content.to_string();
+    for (range, value) in edits {
+        output.replace_range(range, &value);
+    }
+    Ok(output)
+}
+
+/// versionCode for a CalVer version: the year as two digits followed by
+/// every other component as two digits, e.g. 2026.2.17.5 → 26021705
+/// (YYMMDDNN) and 26.2.5 → 260205. Prerelease and build suffixes are ignored.
+pub fn derive_code(version: &str) -> Result<u64> {
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|p| p.parse())
+        .collect::<Result<_, _>>()
+        .with_context(|| format!("cannot derive versionCode from {}", version))?;
+
+    let mut code: u64 = 0;
+    for (i, part) in parts.iter().enumerate() {
+        let part = if i == 0 { part % 100 } else { *part };
+        if part > 99 {
+            bail!(
+                "cannot derive versionCode from {}: component {} exceeds two digits",
+                version,
+                part
+            );
+        }
+        code = code * 100 + part;
+    }
+
+    if code == 0 || code > MAX_VERSION_CODE {
+        bail!(
+            "versionCode {} derived from {} is outside Google Play's range (1..={})",
+            code,
+            version,
+            MAX_VERSION_CODE
+        );
+    }
+    Ok(code)
+}
+
+/// Byte range of the value assigned to `key` (`key "v"`, `key = "v"`,
+/// `key 12`, `key = 12`): the text inside the quotes, or the digits.
+fn find_value(content: &str, key: &str, quoted: bool) -> Option<Range<usize>> {
+    for start in scan::word_starts(content, key, &['_', '.']) {
+        let from = start + key.len();
+        let rest = &content[from..];
+        let after_key = rest.trim_start_matches([' ', '\t']);
+        let after_eq = after_key
+            .strip_prefix('=')
+            .map_or(after_key, |r| r.trim_start_matches([' ', '\t']));
+        if after_eq.len() == rest.len() {
+            // Not followed by whitespace or '=' — part of a longer identifier
+            continue;
+        }
+        let value_start = from + (rest.len() - after_eq.len());
+
+        if quoted {
+            let quote = after_eq
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')?;
+            let len = after_eq[1..].find(quote)?;
+            return Some(value_start + 1..value_start + 1 + len);
+        }
+
+        let len = after_eq
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after_eq.len());
+        if len > 0 {
+            return Some(value_start..value_start + len);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROOVY: &str = r#"android {
+    defaultConfig {
+        applicationId "com.example.app"
+        versionCode 26020100
+        versionName "26.2.1.0"
+    }
+}
+"#;
+
+    const KOTLIN: &str = r#"android {
+    defaultConfig {
+        versionCode = 260201
+        versionName = "26.2.1"
+    }
+}
+"#;
+
+    #[test]
+    fn read_groovy_and_kotlin_dsl() {
+        assert_eq!(version_name(GROOVY), Some("26.2.1.0"));
+        assert_eq!(version_code(GROOVY), Some(26020100));
+        assert_eq!(version_name(KOTLIN), Some("26.2.1"));
+        assert_eq!(version_code(KOTLIN), Some(260201));
+    }
+
+    #[test]
+    fn update_sets_name_and_code() {
+        let updated = update(KOTLIN, "26.2.5").unwrap();
+        assert!(updated.contains(r#"versionName = "26.2.5""#));
+        assert!(updated.contains("versionCode = 260205"));
+
+        let updated = update(GROOVY, "2026.2.17.5").unwrap();
+        assert!(updated.contains(r#"versionName "2026.2.17.5""#));
+        assert!(updated.contains("versionCode 26021705"));
+    }
+
+    #[test]
+    fn update_rejects_decreasing_code() {
+        assert!(update(KOTLIN, "26.2.1").is_err());
+        assert!(update(KOTLIN, "26.1.9").is_err());
+    }
+
+    #[test]
+    fn update_without_version_code() {
+        let updated = update("versionName 'old'\n", "26.2.5").unwrap();
+        assert_eq!(updated, "versionName '26.2.5'\n");
+        assert!(update("android {}\n", "26.2.5").is_err());
+    }
+
+    #[test]
+    fn derive_code_limits() {
+        assert_eq!(derive_code("26.2.5-beta.1").unwrap(), 260205);
+        assert!(derive_code("26.2.100").is_err());
+        assert!(derive_code("26.2.5.1.1").is_err());
+    }
+
+    #[test]
+    fn find_value_skips_longer_identifiers() {
+        let content = "versionNameSuffix \"-dev\"\nversionName \"1.0\"\n";
+        assert_eq!(version_name(content), Some("1.0"));
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result, bail};
+
+use crate::resolve::is_semver;
+use crate::yaml::{line_end, top_level_value};
+
+/// Current chart `version` in a Chart.yaml.
+pub fn chart_version(content: &str) -> Option<&str> {
+    top_level_value(content, "version").map(|r| &content[r])
+}
+
+/// Set the chart `version` and, when given, `appVersion`. A missing
+/// `appVersion` is added right after `version`. Everything else (comments,
+/// quoting, key order) is kept as is.
+pub fn update(content: &str, version: &str, app_version: Option<&str>) -> Result<String> {
+    check_version(version)?;
+
+    let range = top_level_value(content, "version")
+        .context("no top-level version found (expected version: ...)")?;
+
+    let mut output = content.to_string();
+    match app_version.map(|v| (v, top_level_value(content, "appVersion"))) {
+        None => output.replace_range(range, version),
+        Some((app, Some(app_range))) => {
+            // Apply back to front so the earlier range stays valid
+            let mut edits = [(range, version), (app_range, app)];
+            edits.sort_by_key(|(r, _)| std::cmp::Reverse(r.start));
+            for (r, value) in edits {
+                output.replace_range(r, value);
+            }
+        }
+        Some((app, None)) => {
+            output.insert_str(
+                line_end(content, range.end),
+                &format!("\nappVersion: \"{}\"", app),
+            );
+            output.replace_range(range, version);
+        }
+    }
+    Ok(output)
+}
+
+/// Helm requires chart versions to be SemVer 2.
+fn check_version(version: &str) -> Result<()> {
+    if !is_semver(version) {
+        bail!(
+            "{} is not a valid Helm chart version (SemVer 2: exactly three integers \
+             without leading zeros); use a format like YY.MM.MICRO",
+            version
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHART: &str = r#"apiVersion: v2
+name: my-app
+description: A chart
+version: 26.2.1 # chart release
+appVersion: "26.2.1"
+dependencies:
+  - name: redis
+    version: 18.0.0
+"#;
+
+    #[test]
+    fn read_top_level_values() {
+        assert_eq!(chart_version(CHART), Some("26.2.1"));
+        assert_eq!(chart_version("name: a\n  version: 1.0.0\n"), None);
+    }
+
+    #[test]
+    fn update_version_and_app_version() {
+        let updated = update(CHART, "26.2.5", Some("2026.2.17.5")).unwrap();
+        assert!(updated.contains("version: 26.2.5 # chart release\n"));
+        assert!(updated.contains("appVersion: \"2026.2.17.5\"\n"));
+        assert!(updated.contains("    version: 18.0.0\n"));
+
+        let updated = update(CHART, "26.2.5", None).unwrap();
+        assert!(updated.contains("appVersion: \"26.2.1\"\n"));
+    }
+
+    #[test]
+    fn update_adds_missing_app_version() {
+        let updated = update("name: a\nversion: 0.1.0\n", "26.2.5", Some("26.2.5")).unwrap();
+        assert_eq!(
+            updated,
+            "name: a\nversion: 26.2.5\nappVersion: \"26.2.5\"\n"
+        );
+        let updated = update("version: 0.1.0", "26.2.5", Some("26.2.5")).unwrap();
+        assert_eq!(updated, "version: 26.2.5\nappVersion: \"26.2.5\"");
+    }
+
+    #[test]
+    fn rejects_non_semver_chart_versions() {
+        assert!(update(CHART, "2026.2.17.5", None).is_err());
+        assert!(update(CHART, "26.02.5", None).is_err());
+        assert!(update(CHART, "26.2.5-beta.1", None).is_ok());
+        assert!(update("name: a\n", "26.2.5", None).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// User-Agent sent with every request (crates.io and GitHub require one)
+const USER_AGENT: &str = "oneup (https://github.com/circlesac/oneup)";
+
+/// How long to wait for a connection before giving up on a host
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The HTTP client shared by every registry backend in a run. Built once,
+/// so connections (and TLS sessions) to a registry are reused across
+/// packages; proxies come from HTTPS_PROXY / HTTP_PROXY / NO_PROXY.
+pub fn client() -> reqwest::blocking::Client {
+    static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+    // Clones share the connection pool
+    CLIENT
+        .get_or_init(|| {
+            let builder = reqwest::blocking::Client::builder()
+                .user_agent(USER_AGENT)
+                .connect_timeout(CONNECT_TIMEOUT);
+            // rustls otherwise, even with both compiled in
+            #[cfg(feature = "native-tls")]
+            let builder = builder.use_native_tls();
+            builder.build().expect("failed to build HTTP client")
+        })
+        .clone()
+}
@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+
+use crate::npmrc::nerf_dart;
+use crate::redact;
+
+/// Service name for oneup's entries in the OS credential store
+const SERVICE: &str = "oneup";
+
+/// Account name for a registry's token, the way .npmrc keys name
+/// registries (`//host/path/`)
+fn account(registry_url: &str) -> String {
+    nerf_dart(registry_url)
+}
+
+/// Token stored for `registry_url` with `oneup login`, if any. A missing or
+/// unavailable credential store (headless CI) counts as no token.
+pub fn token(registry_url: &str, verbose: bool) -> Option<String> {
+    let entry = keyring::Entry::new(SERVICE, &account(registry_url)).ok()?;
+    match entry.get_password() {
+        Ok(token) => {
+            redact::register(&token);
+            if verbose {
+                eprintln!("[registry] token from OS credential store");
+            }
+            Some(token)
+        }
+        Err(keyring::Error::NoEntry) => None,
+        Err(err) => {
+            if verbose {
+                eprintln!("[registry] OS credential store unavailable: {}", err);
+            }
+            None
+        }
+    }
+}
+
+/// Store `token` for `registry_url` in the OS credential store.
+pub fn store(registry_url: &str, token: &str) -> Result<()> {
+    keyring::Entry::new(SERVICE, &account(registry_url))
+        .and_then(|entry| entry.set_password(token))
+        .with_context(|| {
+            format!(
+                "failed to store the token for {} in the OS credential store",
+                registry_url
+            )
+        })
+}
+
+/// Remove the token stored for `registry_url`. Returns whether there was one.
+pub fn delete(registry_url: &str) -> Result<bool> {
+    let entry = keyring::Entry::new(SERVICE, &account(registry_url))
+        .context("OS credential store unavailable")?;
+    match entry.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(err) => Err(err).with_context(|| {
+            format!(
+                "failed to remove the token for {} from the OS credential store",
+                registry_url
+            )
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_matches_npmrc_keys() {
+        assert_eq!(
+            account("https://registry.npmjs.org"),
+            "//registry.npmjs.org/"
+        );
+        assert_eq!(
+            account("https://gitlab.com/api/v4/projects/42/packages/npm/"),
+            "//gitlab.com/api/v4/projects/42/packages/npm/"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{Context, Result, bail};
+use serde_json::json;
+use std::path::Path;
+
+use crate::cli::LatestArgs;
+use crate::config::Config;
+use crate::format::{Component, VersionFormat};
+use crate::registry::PackageInfo;
+use crate::resolve::compare_versions;
+use crate::timing::Timings;
+use crate::version::{load_targets, query_registry};
+
+/// A date period given as `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+#[derive(Debug, PartialEq)]
+struct Period {
+    year: u64,
+    month: Option<u64>,
+    day: Option<u64>,
+}
+
+impl Period {
+    fn parse(spec: &str) -> Result<Self> {
+        let invalid = || {
+            format!(
+                "invalid period '{}': expected YYYY, YYYY-MM or YYYY-MM-DD",
+                spec
+            )
+        };
+        let parts: Vec<&str> = spec.split('-').collect();
+        if parts.len() > 3 || parts[0].len() != 4 {
+            bail!(invalid());
+        }
+        let numbers = parts
+            .iter()
+            .map(|p| p.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()
+            .with_context(invalid)?;
+        let period = Self {
+            year: numbers[0],
+            month: numbers.get(1).copied(),
+            day: numbers.get(2).copied(),
+        };
+        if period.month.is_some_and(|m| !(1..=12).contains(&m))
+            || period.day.is_some_and(|d| !(1..=31).contains(&d))
+        {
+            bail!(invalid());
+        }
+        Ok(period)
+    }
+
+    /// Whether a version's values (per `fmt`) fall in this period.
+    fn contains(&self, fmt: &VersionFormat, values: &[u64]) -> bool {
+        fmt.components
+            .iter()
+            .zip(values)
+            .all(|(component, value)| match component {
+                Component::Yyyy => *value == self.year,
+                Component::Yy => *value == self.year % 100,
+                Component::Mm => self.month.is_none_or(|m| *value == m),
+                Component::Dd => self.day.is_none_or(|d| *value == d),
+                Component::Micro => true,
+            })
+    }
+
+    /// Reject periods naming a month or day the format does not record.
+    fn check(&self, fmt: &VersionFormat, spec: &str) -> Result<()> {
+        let has = |c: Component| fmt.components.contains(&c);
+        if self.month.is_some() && !has(Component::Mm) {
+            bail!("period '{}' names a month, but the format has no MM", spec);
+        }
+        if self.day.is_some() && !has(Component::Dd) {
+            bail!("period '{}' names a day, but the format has no DD", spec);
+        }
+        Ok(())
+    }
+}
+
+pub fn run(args: LatestArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let fmt = VersionFormat::parse(&args.format)?;
+    let period = Period::parse(&args.period)?;
+    period.check(&fmt, &args.period)?;
+
+    let targets = load_targets(
+        &args.target,
+        config.primary_target(),
+        &config,
+        args.package_name.as_deref(),
+    )?;
+    let (primary_path, primary_target) = &targets[0];
+    let package = &primary_target.package_name;
+
+    let info = query_registry(
+        primary_path,
+        primary_target,
+        args.registry.as_deref(),
+        args.registry_type,
+        &config,
+        args.verbose,
+        &mut Timings::new(false),
+    )?;
+
+    let in_period = versions_in_period(&info, &fmt, &period);
+    if args.verbose {
+        eprintln!(
+            "[latest] {} version(s) of {} in {}",
+            in_period.len(),
+            package,
+            args.period
+        );
+    }
+    let latest = in_period.last();
+    let published_at = latest.and_then(|v| info.published_at(v));
+
+    if args.json {
+        let report = json!({
+            "package": package,
+            "period": args.period,
+            "latest": latest,
+            "published_at": published_at,
+            "count": in_period.len(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let Some(latest) = latest else {
+        bail!(
+            "no release of {} in {} (format {})",
+            package,
+            args.period,
+            args.format
+        );
+    };
+    if let Some(time) = published_at {
+        eprintln!("{} {} published {}", package, latest, time);
+    }
+    println!("{}", latest);
+    Ok(())
+}
+
+/// Stable versions published in `period`, lowest first.
+fn versions_in_period(info: &PackageInfo, fmt: &VersionFormat, period: &Period) -> Vec<String> {
+    let PackageInfo::Found { versions, .. } = info else {
+        return Vec::new();
+    };
+    let mut matching: Vec<String> = versions
+        .iter()
+        .filter(|v| {
+            fmt.extract_values(v)
+                .is_some_and(|values| period.contains(fmt, &values))
+        })
+        .cloned()
+        .collect();
+    matching.sort_by(|a, b| compare_versions(a, b));
+    matching
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_periods() {
+        assert_eq!(
+            Period::parse("2026-01").unwrap(),
+            Period {
+                year: 2026,
+                month: Some(1),
+                day: None
+            }
+        );
+        assert_eq!(Period::parse("2026").unwrap().month, None);
+        assert_eq!(Period::parse("2026-01-15").unwrap().day, Some(15));
+        assert!(Period::parse("26-01").is_err());
+        assert!(Period::parse("2026-13").is_err());
+        assert!(Period::parse("2026-01-15-1").is_err());
+        assert!(Period::parse("january").is_err());
+    }
+
+    #[test]
+    fn highest_version_in_period() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        let info = PackageInfo::Found {
+            versions: [
+                "25.12.4",
+                "26.1.0",
+                "26.1.10",
+                "26.1.9",
+                "26.1.11-beta.0",
+                "26.2.0",
+            ]
+            .iter()
+            .map(|v| v.to_string())
+            .collect(),
+            latest: "26.2.0".to_string(),
+            published: Default::default(),
+        };
+        let january = Period::parse("2026-01").unwrap();
+        assert_eq!(
+            versions_in_period(&info, &fmt, &january),
+            vec!["26.1.0", "26.1.9", "26.1.10"]
+        );
+        let year = Period::parse("2026").unwrap();
+        assert_eq!(
+            versions_in_period(&info, &fmt, &year).last().unwrap(),
+            "26.2.0"
+        );
+        assert!(versions_in_period(&PackageInfo::NotFound, &fmt, &year).is_empty());
+
+        assert!(
+            Period::parse("2026-01-15")
+                .unwrap()
+                .check(&fmt, "2026-01-15")
+                .is_err()
+        );
+        assert!(january.check(&fmt, "2026-01").is_ok());
+    }
+}
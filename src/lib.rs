@@ -0,0 +1,12 @@
+//! The pure part of oneup: version formats and the next-version decision.
+//! Nothing here touches the network, git or the filesystem, so the library
+//! builds without the `cli` feature and compiles to wasm32; the `wasm`
+//! feature adds JavaScript bindings.
+
+pub mod error;
+pub mod format;
+pub mod redact;
+pub mod resolve;
+pub mod suggest;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,107 @@
+use anyhow::{Context, Result, bail};
+use std::io::IsTerminal;
+use std::path::Path;
+
+use crate::cli::{LoginArgs, LogoutArgs};
+use crate::gitlab;
+use crate::keychain;
+use crate::npmrc::{self, NpmrcConfig};
+use crate::registry::RegistryClient;
+use crate::target::TargetFile;
+use crate::version::registry_route;
+
+pub fn run(args: LoginArgs) -> Result<()> {
+    let registry = resolve_registry(args.registry.as_deref())?;
+
+    let token = if args.web {
+        RegistryClient::new(&registry, None).web_login(|url| {
+            eprintln!("Open this URL in your browser to log in to {}:", registry);
+            eprintln!("  {}", url);
+        })?
+    } else {
+        read_token(&registry)?
+    };
+
+    if args.npmrc {
+        let path = npmrc::user_npmrc().context("HOME is not set; cannot locate ~/.npmrc")?;
+        npmrc::set_auth_token(&path, &registry, Some(&token))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        eprintln!("stored the token for {} in {}", registry, path.display());
+    } else {
+        keychain::store(&registry, &token)?;
+        eprintln!(
+            "stored the token for {} in the OS credential store",
+            registry
+        );
+    }
+    Ok(())
+}
+
+/// Remove the token for a registry from the OS credential store and
+/// ~/.npmrc. Tokens are not revoked on the registry.
+pub fn logout(args: LogoutArgs) -> Result<()> {
+    let registry = resolve_registry(args.registry.as_deref())?;
+
+    let mut removed = Vec::new();
+    match keychain::delete(&registry) {
+        Ok(true) => removed.push("the OS credential store".to_string()),
+        Ok(false) => {}
+        Err(err) => eprintln!("warning: {:#}", err),
+    }
+    if let Some(path) = npmrc::user_npmrc()
+        && npmrc::set_auth_token(&path, &registry, None)
+            .with_context(|| format!("failed to write {}", path.display()))?
+    {
+        removed.push(path.display().to_string());
+    }
+
+    if removed.is_empty() {
+        bail!("no stored token for {}", registry);
+    }
+    eprintln!(
+        "removed the token for {} from {}",
+        registry,
+        removed.join(" and ")
+    );
+    Ok(())
+}
+
+/// The registry given on the command line (GitLab shorthand expanded), or
+/// the one package.json's package is looked up in.
+fn resolve_registry(registry: Option<&str>) -> Result<String> {
+    match registry {
+        Some(url) => Ok(gitlab::expand_registry(url)?
+            .unwrap_or_else(|| url.to_string())
+            .trim_end_matches('/')
+            .to_string()),
+        None => default_registry(),
+    }
+}
+
+/// Read a pasted token from stdin. A terminal gets a prompt and does not
+/// echo the token, so it stays out of the scrollback and recordings.
+fn read_token(registry: &str) -> Result<String> {
+    let token = if std::io::stdin().is_terminal() {
+        eprintln!("Paste the token for {} and press Enter:", registry);
+        rpassword::read_password()
+    } else {
+        let mut token = String::new();
+        std::io::stdin().read_line(&mut token).map(|_| token)
+    }
+    .context("failed to read the token from stdin")?;
+    let token = token.trim();
+    if token.is_empty() {
+        bail!("no token given for {}", registry);
+    }
+    Ok(token.to_string())
+}
+
+/// The registry package.json's package is looked up in, or npm's default.
+fn default_registry() -> Result<String> {
+    let package_json = Path::new("package.json");
+    if package_json.exists() {
+        let target = TargetFile::read(package_json, None)?;
+        return Ok(registry_route(package_json, &target, None, None)?.0);
+    }
+    Ok(NpmrcConfig::load(Path::new("."))?.registry_url(None))
+}
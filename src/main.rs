@@ -1,19 +1,115 @@
+mod adopt;
+#[cfg(feature = "network")]
+mod announce;
+mod available;
+mod backup;
+mod bazel;
+mod ci;
+mod citation;
 mod cli;
+mod config;
+#[cfg(feature = "crates")]
 mod crates_io;
-mod format;
+#[cfg(feature = "network")]
+mod dispatch;
+#[cfg(feature = "network")]
+mod distribute;
+mod docker;
+mod docker_tag;
+mod docs;
+mod feed;
+mod generate;
+mod git;
+#[cfg(feature = "network")]
+mod github;
+mod gitlab;
+mod gradle;
+mod helm;
+#[cfg(feature = "network")]
+mod http;
+#[cfg(feature = "npm")]
+mod keychain;
+mod latest;
+#[cfg(feature = "npm")]
+mod login;
+mod man;
+mod metrics;
+mod nix;
 mod npmrc;
+mod output;
+mod policy;
+mod preflight;
 mod registry;
+mod release_manifest;
+mod report;
+mod scan;
+#[cfg(feature = "network")]
+mod self_update;
+#[cfg(feature = "selftest")]
+mod selftest;
+mod status;
+mod sync;
 mod target;
+mod timing;
 mod version;
+mod vscode;
+mod webext;
+mod window;
+mod workspace;
+mod xcode;
+mod yaml;
 
 use anyhow::Result;
 use clap::Parser;
 use cli::{Cli, Commands};
+use oneup::{error, format, redact, resolve, suggest};
 
-fn main() -> Result<()> {
+fn main() {
     let cli = Cli::parse();
+    // Commands with --json report errors as JSON too
+    let json = match &cli.command {
+        Commands::Available(args) => args.json,
+        Commands::Latest(args) => args.json,
+        Commands::Preflight(args) => args.json,
+        Commands::Status(args) => args.json,
+        _ => false,
+    };
 
+    if let Err(err) = run(cli) {
+        std::process::exit(error::report(&err, json));
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Version(args) => version::run(args),
+        Commands::Adopt(args) => adopt::run(args),
+        Commands::Available(args) => available::run(args),
+        Commands::Latest(args) => latest::run(args),
+        Commands::Sync(args) => sync::run(args),
+        Commands::Preflight(args) => preflight::run(args),
+        Commands::Status(args) => status::run(args),
+        Commands::Report(args) => report::run(args),
+        Commands::Config(args) => config::run(args),
+        #[cfg(feature = "npm")]
+        Commands::Login(args) => login::run(args),
+        #[cfg(feature = "npm")]
+        Commands::Logout(args) => login::logout(args),
+        Commands::Restore(args) => backup::run(args),
+        Commands::ReleaseManifest(args) => release_manifest::run(args),
+        Commands::Feed(args) => feed::run(args),
+        Commands::DockerTag(args) => docker_tag::run(args),
+        #[cfg(feature = "network")]
+        Commands::Announce(args) => announce::run(args),
+        #[cfg(feature = "network")]
+        Commands::Dispatch(args) => dispatch::run(args),
+        #[cfg(feature = "network")]
+        Commands::Distribute(args) => distribute::run(args),
+        #[cfg(feature = "network")]
+        Commands::SelfUpdate(args) => self_update::run(args),
+        Commands::Man(args) => man::run(args),
+        Commands::Generate(args) => generate::run(args),
+        #[cfg(feature = "selftest")]
+        Commands::Selftest(args) => selftest::run(args),
     }
 }
@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use clap::{ArgAction, CommandFactory};
+use serde_json::{Value, json};
+
+use crate::cli::{Cli, ManArgs};
+
+pub fn run(args: ManArgs) -> Result<()> {
+    std::fs::create_dir_all(&args.out_dir)
+        .with_context(|| format!("failed to create {}", args.out_dir.display()))?;
+
+    // 1. Man pages: oneup.1 plus one page per subcommand (oneup-version.1, ...)
+    clap_mangen::generate_to(Cli::command(), &args.out_dir)
+        .with_context(|| format!("failed to write man pages to {}", args.out_dir.display()))?;
+
+    // 2. Machine-readable CLI spec
+    let spec_path = args.out_dir.join("oneup.json");
+    let mut spec = serde_json::to_string_pretty(&cli_spec())?;
+    spec.push('\n');
+    std::fs::write(&spec_path, spec)
+        .with_context(|| format!("failed to write {}", spec_path.display()))?;
+
+    println!("{}", args.out_dir.display());
+
+    Ok(())
+}
+
+/// JSON description of every subcommand and flag.
+pub fn cli_spec() -> Value {
+    let mut cmd = Cli::command();
+    cmd.build();
+    command_spec(&cmd)
+}
+
+fn command_spec(cmd: &clap::Command) -> Value {
+    let args: Vec<Value> = cmd
+        .get_arguments()
+        .filter(|a| !a.is_hide_set())
+        .map(|a| {
+            json!({
+                "name": a.get_id().as_str(),
+                "long": a.get_long(),
+                "short": a.get_short().map(|c| c.to_string()),
+                "help": a.get_help().map(|h| h.to_string()),
+                "env": a.get_env().map(|e| e.to_string_lossy().into_owned()),
+                "required": a.is_required_set(),
+                "takes_value": a.get_action().takes_values(),
+                "repeatable": matches!(a.get_action(), ArgAction::Append | ArgAction::Count),
+                "default": a
+                    .get_default_values()
+                    .iter()
+                    .map(|v| v.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    let subcommands: Vec<Value> = cmd
+        .get_subcommands()
+        .filter(|s| !s.is_hide_set() && s.get_name() != "help")
+        .map(command_spec)
+        .collect();
+
+    json!({
+        "name": cmd.get_name(),
+        "about": cmd.get_about().map(|a| a.to_string()),
+        "args": args,
+        "subcommands": subcommands,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subcommand<'a>(spec: &'a Value, name: &str) -> &'a Value {
+        spec["subcommands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["name"] == name)
+            .unwrap()
+    }
+
+    fn arg<'a>(cmd: &'a Value, name: &str) -> &'a Value {
+        cmd["args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|a| a["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn spec_lists_version_flags() {
+        let spec = cli_spec();
+        assert_eq!(spec["name"], "oneup");
+
+        let version = subcommand(&spec, "version");
+        let format = arg(version, "format");
+        assert_eq!(format["long"], "format");
+        assert_eq!(format["default"], json!(["YY.MM.MICRO"]));
+        assert_eq!(format["takes_value"], true);
+        assert_eq!(format["env"], "ONEUP_FORMAT");
+
+        let target = arg(version, "target");
+        assert_eq!(target["repeatable"], true);
+
+        let dry_run = arg(version, "dry_run");
+        assert_eq!(dry_run["takes_value"], false);
+    }
+
+    #[test]
+    fn spec_omits_help_subcommand() {
+        let spec = cli_spec();
+        let names: Vec<_> = spec["subcommands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["name"].as_str().unwrap())
+            .collect();
+        assert!(!names.contains(&"help"));
+        assert!(names.contains(&"man"));
+    }
+}
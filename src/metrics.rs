@@ -0,0 +1,360 @@
+// Without the network feature --metrics only warns; the encoders go unused
+#![cfg_attr(not(feature = "network"), allow(dead_code))]
+
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::error::{self, ErrorKind};
+
+/// How long a metrics push may take before the run stops waiting for it
+#[cfg(feature = "network")]
+const PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a `oneup version` run ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// Target files were written with a new version
+    Bumped,
+    /// The computed version equals the current one
+    Unchanged,
+    /// Nothing was written (--dry-run, --target -)
+    DryRun,
+    /// The run failed; the label carries the error code
+    Failed,
+}
+
+impl Outcome {
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Bumped => "bumped",
+            Outcome::Unchanged => "unchanged",
+            Outcome::DryRun => "dry-run",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+/// What a run reports with --metrics, filled in as it goes
+pub struct Run {
+    start: Instant,
+    pub package: Option<String>,
+    pub version: Option<String>,
+    pub outcome: Outcome,
+    error: Option<&'static str>,
+    duration: Duration,
+    timestamp: Duration,
+}
+
+impl Run {
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            package: None,
+            version: None,
+            outcome: Outcome::Bumped,
+            error: None,
+            duration: Duration::ZERO,
+            timestamp: Duration::ZERO,
+        }
+    }
+
+    /// Stop the clock and record the result of the run.
+    pub fn finish(&mut self, result: &Result<()>) {
+        self.duration = self.start.elapsed();
+        self.timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        if let Err(err) = result {
+            self.outcome = Outcome::Failed;
+            self.error = Some(error::kind_of(err).map_or("error", ErrorKind::code));
+        }
+    }
+
+    fn succeeded(&self) -> bool {
+        self.outcome != Outcome::Failed
+    }
+}
+
+/// Send the run to `url`: an OTLP/HTTP endpoint when the path ends in
+/// `/v1/metrics`, otherwise a Prometheus Pushgateway. A failed push only
+/// warns; it never fails the release.
+pub fn push(url: &str, run: &Run, verbose: bool) {
+    if let Err(err) = send(url, run, verbose) {
+        eprintln!("warning: failed to push metrics to {}: {:#}", url, err);
+    }
+}
+
+#[cfg(not(feature = "network"))]
+fn send(_url: &str, _run: &Run, _verbose: bool) -> Result<()> {
+    Err(error::not_built("network"))
+}
+
+#[cfg(feature = "network")]
+fn send(url: &str, run: &Run, verbose: bool) -> Result<()> {
+    use anyhow::{Context, bail};
+
+    let url = url.trim_end_matches('/');
+    let http = crate::http::client();
+    let req = if is_otlp(url) {
+        let mut req = http.post(url).json(&otlp(run));
+        // The standard exporter variable: "key=value,key=value"
+        if let Ok(headers) = std::env::var("OTEL_EXPORTER_OTLP_HEADERS") {
+            for (key, value) in headers.split(',').filter_map(|h| h.split_once('=')) {
+                req = req.header(key.trim(), value.trim());
+            }
+        }
+        req
+    } else {
+        let url = format!("{}{}", url, grouping_path(run));
+        // POST replaces only the metrics pushed here, so the last success
+        // survives a failed run
+        http.post(&url)
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(exposition(run))
+    };
+    if verbose {
+        eprintln!("[metrics] POST {}", crate::redact::redact(url));
+    }
+    let resp = req.timeout(PUSH_TIMEOUT).send().context("request failed")?;
+    if !resp.status().is_success() {
+        bail!("HTTP {}", resp.status());
+    }
+    Ok(())
+}
+
+fn is_otlp(url: &str) -> bool {
+    url.ends_with("/v1/metrics")
+}
+
+/// Pushgateway grouping key: one group per package, so repositories and
+/// monorepo packages don't overwrite each other.
+fn grouping_path(run: &Run) -> String {
+    let package = run.package.as_deref().unwrap_or_default();
+    if package.is_empty() {
+        "/metrics/job/oneup".to_string()
+    } else if package.contains('/') {
+        // Scoped npm names: label values with '/' must be base64url-encoded
+        format!("/metrics/job/oneup/package@base64/{}", base64url(package))
+    } else {
+        format!("/metrics/job/oneup/package/{}", package)
+    }
+}
+
+/// Prometheus text exposition of the run.
+fn exposition(run: &Run) -> String {
+    let mut labels = vec![
+        ("version", run.version.clone().unwrap_or_default()),
+        ("outcome", run.outcome.label().to_string()),
+    ];
+    if let Some(code) = run.error {
+        labels.push(("error", code.to_string()));
+    }
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+
+    let mut out = String::new();
+    let mut gauge = |name: &str, help: &str, sample: String| {
+        out.push_str(&format!(
+            "# HELP {name} {help}\n# TYPE {name} gauge\n{sample}\n"
+        ));
+    };
+    gauge(
+        "oneup_run_info",
+        "Result of the last oneup version run",
+        format!("oneup_run_info{{{}}} 1", labels.join(",")),
+    );
+    gauge(
+        "oneup_run_duration_seconds",
+        "Wall-clock time of the last oneup version run",
+        format!("oneup_run_duration_seconds {}", run.duration.as_secs_f64()),
+    );
+    gauge(
+        "oneup_run_timestamp_seconds",
+        "When the last oneup version run finished",
+        format!("oneup_run_timestamp_seconds {}", run.timestamp.as_secs()),
+    );
+    if run.succeeded() {
+        gauge(
+            "oneup_last_success_timestamp_seconds",
+            "When oneup version last succeeded",
+            format!(
+                "oneup_last_success_timestamp_seconds {}",
+                run.timestamp.as_secs()
+            ),
+        );
+    }
+    out
+}
+
+/// OTLP/HTTP JSON export request: a run counter (delta sum, so backends can
+/// graph release frequency and failures) and the run duration.
+fn otlp(run: &Run) -> Value {
+    let mut attributes = vec![
+        attribute("package", run.package.as_deref().unwrap_or_default()),
+        attribute("version", run.version.as_deref().unwrap_or_default()),
+        attribute("outcome", run.outcome.label()),
+    ];
+    if let Some(code) = run.error {
+        attributes.push(attribute("error", code));
+    }
+    let end = run.timestamp.as_nanos().to_string();
+    let start = run
+        .timestamp
+        .saturating_sub(run.duration)
+        .as_nanos()
+        .to_string();
+
+    json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": [attribute("service.name", "oneup")] },
+            "scopeMetrics": [{
+                "scope": { "name": "oneup", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": [
+                    {
+                        "name": "oneup.runs",
+                        "description": "oneup version runs",
+                        "unit": "{run}",
+                        "sum": {
+                            "aggregationTemporality": 1,
+                            "isMonotonic": true,
+                            "dataPoints": [{
+                                "attributes": attributes,
+                                "startTimeUnixNano": start,
+                                "timeUnixNano": end,
+                                "asInt": "1",
+                            }],
+                        },
+                    },
+                    {
+                        "name": "oneup.run.duration",
+                        "description": "Wall-clock time of a oneup version run",
+                        "unit": "s",
+                        "gauge": {
+                            "dataPoints": [{
+                                "attributes": attributes,
+                                "timeUnixNano": end,
+                                "asDouble": run.duration.as_secs_f64(),
+                            }],
+                        },
+                    },
+                ],
+            }],
+        }],
+    })
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Unpadded base64url, as the Pushgateway reads `label@base64` values.
+fn base64url(value: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::new();
+    for chunk in value.as_bytes().chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ResultExt;
+    use anyhow::anyhow;
+
+    fn run(outcome: Outcome) -> Run {
+        Run {
+            start: Instant::now(),
+            package: Some("@circlesac/oneup".to_string()),
+            version: Some("26.10.4".to_string()),
+            outcome,
+            error: None,
+            duration: Duration::from_millis(1500),
+            timestamp: Duration::from_secs(1_790_000_000),
+        }
+    }
+
+    #[test]
+    fn finish_records_failure_code() {
+        let mut r = run(Outcome::Bumped);
+        r.finish(&Err(anyhow!("HTTP 503")).kind(ErrorKind::Network));
+        assert_eq!(r.outcome, Outcome::Failed);
+        assert_eq!(r.error, Some("network"));
+
+        let mut r = run(Outcome::Unchanged);
+        r.finish(&Ok(()));
+        assert_eq!(r.outcome, Outcome::Unchanged);
+        assert_eq!(r.error, None);
+    }
+
+    #[test]
+    fn exposition_format() {
+        let text = exposition(&run(Outcome::Bumped));
+        assert!(text.contains("oneup_run_info{version=\"26.10.4\",outcome=\"bumped\"} 1\n"));
+        assert!(text.contains("# TYPE oneup_run_duration_seconds gauge\n"));
+        assert!(text.contains("oneup_run_duration_seconds 1.5\n"));
+        assert!(text.contains("oneup_last_success_timestamp_seconds 1790000000\n"));
+
+        let mut failed = run(Outcome::Failed);
+        failed.error = Some("auth");
+        let text = exposition(&failed);
+        assert!(text.contains("outcome=\"failed\",error=\"auth\"}"));
+        assert!(!text.contains("oneup_last_success_timestamp_seconds"));
+    }
+
+    #[test]
+    fn grouping_key_per_package() {
+        assert_eq!(
+            grouping_path(&run(Outcome::Bumped)),
+            "/metrics/job/oneup/package@base64/QGNpcmNsZXNhYy9vbmV1cA"
+        );
+        let mut r = run(Outcome::Bumped);
+        r.package = Some("oneup".to_string());
+        assert_eq!(grouping_path(&r), "/metrics/job/oneup/package/oneup");
+        r.package = None;
+        assert_eq!(grouping_path(&r), "/metrics/job/oneup");
+    }
+
+    #[test]
+    fn base64url_unpadded() {
+        assert_eq!(base64url("f"), "Zg");
+        assert_eq!(base64url("fo"), "Zm8");
+        assert_eq!(base64url("foo"), "Zm9v");
+        assert_eq!(base64url("a/b?"), "YS9iPw");
+    }
+
+    #[test]
+    fn otlp_counts_the_run() {
+        assert!(is_otlp("http://collector:4318/v1/metrics"));
+        assert!(!is_otlp("http://pushgateway:9091"));
+
+        let body = otlp(&run(Outcome::DryRun));
+        let metrics = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+        let point = &metrics[0]["sum"]["dataPoints"][0];
+        assert_eq!(point["asInt"], "1");
+        assert_eq!(point["timeUnixNano"], "1790000000000000000");
+        assert_eq!(point["startTimeUnixNano"], "1789999998500000000");
+        assert_eq!(
+            point["attributes"][2],
+            json!({ "key": "outcome", "value": { "stringValue": "dry-run" } })
+        );
+        assert_eq!(metrics[1]["gauge"]["dataPoints"][0]["asDouble"], 1.5);
+    }
+}
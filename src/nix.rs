@@ -0,0 +1,155 @@
+use anyhow::{Result, bail};
+use std::ops::Range;
+
+use crate::scan;
+
+/// The package's `version = "...";` string attribute in a flake.nix /
+/// default.nix: the one in the attribute set whose `pname` is `package`,
+/// else the first in the file.
+pub fn version<'a>(content: &'a str, package: &str) -> Option<&'a str> {
+    package_version(content, package).map(|r| &content[r])
+}
+
+/// Set the package's `version = "...";` (see [`version`]) to `version`.
+/// Other derivations and fetchers in the file keep their pinned versions,
+/// and attributes bound to expressions (`version = lib.version;`) are
+/// left alone.
+pub fn update(content: &str, package: &str, version: &str) -> Result<String> {
+    let Some(range) = package_version(content, package) else {
+        bail!("no version = \"...\"; attribute found");
+    };
+    let mut output = content.to_string();
+    output.replace_range(range, version);
+    Ok(output)
+}
+
+fn package_version(content: &str, package: &str) -> Option<Range<usize>> {
+    let ranges = string_attributes(content, "version");
+    let set = string_attributes(content, "pname")
+        .into_iter()
+        .find(|r| !package.is_empty() && content[r.clone()] == *package)
+        .map(|r| enclosing_set(content, r.start));
+    match set {
+        Some(set) => ranges.into_iter().find(|r| set.contains(&r.start)),
+        None => ranges.into_iter().next(),
+    }
+}
+
+/// Byte range of the `{ ... }` attribute set around `pos`.
+fn enclosing_set(content: &str, pos: usize) -> Range<usize> {
+    let mut depth = 0;
+    let start = content[..pos]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| match c {
+            '}' => {
+                depth += 1;
+                false
+            }
+            '{' if depth == 0 => true,
+            '{' => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        })
+        .map_or(0, |(i, _)| i);
+    let mut depth = 0;
+    let end = content[pos..]
+        .char_indices()
+        .find(|(_, c)| match c {
+            '{' => {
+                depth += 1;
+                false
+            }
+            '}' if depth == 0 => true,
+            '}' => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        })
+        .map_or(content.len(), |(i, _)| pos + i);
+    start..end
+}
+
+/// Byte ranges of the string values of every `<key> = "...";`.
+fn string_attributes(content: &str, key: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    // Nix identifiers may contain '-' and '\''
+    for start in scan::word_starts(content, key, &['_', '-', '\'', '.']) {
+        let from = start + key.len();
+        let Some(value) = content[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let Some(value) = value.trim_start().strip_prefix('"') else {
+            continue;
+        };
+        let value_start = content.len() - value.len();
+        let Some(len) = value.find('"') else {
+            continue;
+        };
+        if !value[len + 1..].trim_start().starts_with(';') {
+            continue;
+        }
+        ranges.push(value_start..value_start + len);
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAKE: &str = r#"{
+  outputs = { self, nixpkgs }: {
+    packages.x86_64-linux.default = pkgs.rustPlatform.buildRustPackage {
+      pname = "my-app";
+      version = "26.2.1";
+      src = ./.;
+    };
+    packages.x86_64-linux.docs = pkgs.stdenv.mkDerivation {
+      pname = "my-app-docs";
+      version  =  "26.2.1" ;
+      meta.version = "ignored";
+    };
+    lib.version = self.packages.x86_64-linux.default.version;
+  };
+}
+"#;
+
+    #[test]
+    fn updates_only_the_first_version() {
+        assert_eq!(version(FLAKE, ""), Some("26.2.1"));
+        let updated = update(FLAKE, "", "26.2.5").unwrap();
+        assert!(updated.contains("      version = \"26.2.5\";\n"));
+        assert!(updated.contains("      version  =  \"26.2.1\" ;\n"));
+        assert!(updated.contains("meta.version = \"ignored\";"));
+        assert!(updated.contains("lib.version = self.packages"));
+    }
+
+    #[test]
+    fn updates_the_derivation_named_by_pname() {
+        let updated = update(FLAKE, "my-app-docs", "26.2.5").unwrap();
+        assert!(updated.contains("      version = \"26.2.1\";\n"));
+        assert!(updated.contains("      version  =  \"26.2.5\" ;\n"));
+
+        // A fetcher's pinned version before the package stays untouched
+        let content = "{\n  src = fetchurl { version = \"1.4.0\"; };\n  app = mkDerivation {\n    pname = \"my-app\";\n    version = \"26.2.1\";\n  };\n}\n";
+        assert_eq!(version(content, "my-app"), Some("26.2.1"));
+        let updated = update(content, "my-app", "26.2.5").unwrap();
+        assert!(updated.contains("fetchurl { version = \"1.4.0\"; }"));
+        assert!(updated.contains("    version = \"26.2.5\";\n"));
+        // An unknown pname falls back to the first version
+        assert_eq!(version(content, "other"), Some("1.4.0"));
+    }
+
+    #[test]
+    fn skips_expressions_and_longer_names() {
+        assert_eq!(
+            version("  myversion = \"1\";\n  version = \"2\";\n", ""),
+            Some("2")
+        );
+        assert!(update("{ version = lib.version; }", "", "26.2.5").is_err());
+    }
+}
@@ -1,7 +1,15 @@
+// Without the npm feature only `status` reads .npmrc, for the registry
+// route; tokens and ~/.npmrc writes go unused
+#![cfg_attr(not(feature = "npm"), allow(dead_code))]
+
 use anyhow::Result;
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use crate::redact;
+use crate::suggest;
+
 /// Parsed .npmrc configuration
 pub struct NpmrcConfig {
     entries: HashMap<String, String>,
@@ -11,6 +19,7 @@ impl NpmrcConfig {
     /// Load .npmrc files following npm's resolution order:
     /// 1. Project-level .npmrc (directory of target file)
     /// 2. User-level ~/.npmrc
+    ///
     /// Environment variables (NPM_CONFIG_*) override file values.
     pub fn load(project_dir: &Path) -> Result<Self> {
         let mut entries = HashMap::new();
@@ -45,20 +54,43 @@ impl NpmrcConfig {
 
     /// Get the registry URL for a given scope (e.g., "@myorg") or the default registry.
     pub fn registry_url(&self, scope: Option<&str>) -> String {
+        match self.registry_key(scope) {
+            Some(key) => normalize_registry_url(&self.entries[&key]),
+            None => "https://registry.npmjs.org".to_string(),
+        }
+    }
+
+    /// The setting `registry_url` takes the URL from: `<scope>:registry`,
+    /// else `registry`, or None for npm's default registry.
+    pub fn registry_key(&self, scope: Option<&str>) -> Option<String> {
         // Check scoped registry first
         if let Some(scope) = scope {
             let key = format!("{scope}:registry");
-            if let Some(url) = self.entries.get(&key) {
-                return normalize_registry_url(url);
+            if self.entries.contains_key(&key) {
+                return Some(key);
             }
         }
 
         // Fall back to default registry
-        if let Some(url) = self.entries.get("registry") {
-            return normalize_registry_url(url);
-        }
+        self.entries
+            .contains_key("registry")
+            .then(|| "registry".to_string())
+    }
 
-        "https://registry.npmjs.org".to_string()
+    /// A scope with its own `<scope>:registry` entry whose name is close to
+    /// `scope`, when `scope` has none (e.g. `@my-org` for `@myorg`).
+    pub fn similar_scope(&self, scope: &str) -> Option<&str> {
+        if self.entries.contains_key(&format!("{scope}:registry")) {
+            return None;
+        }
+        let mut scopes: Vec<&str> = self
+            .entries
+            .keys()
+            .filter_map(|k| k.strip_suffix(":registry"))
+            .filter(|s| s.starts_with('@'))
+            .collect();
+        scopes.sort();
+        suggest::closest(scope, scopes)
     }
 
     /// Get auth token for a registry URL.
@@ -68,18 +100,16 @@ impl NpmrcConfig {
             .trim_start_matches("http://")
             .trim_end_matches('/');
 
-        // Check //<host>/:_authToken
+        // Check //<host>/:_authToken, then _authToken (global)
         let key = format!("//{host}/:_authToken");
-        if let Some(token) = self.entries.get(&key) {
-            return Some(resolve_env_var(token));
-        }
-
-        // Check _authToken (global)
-        if let Some(token) = self.entries.get("_authToken") {
-            return Some(resolve_env_var(token));
-        }
-
-        None
+        let token = resolve_env_var(
+            self.entries
+                .get(&key)
+                .or_else(|| self.entries.get("_authToken"))?,
+        );
+        // Tokens must never reach verbose output or error messages
+        redact::register(&token);
+        Some(token)
     }
 }
 
@@ -87,6 +117,61 @@ fn dirs_path() -> Option<PathBuf> {
     std::env::var_os("HOME").map(PathBuf::from)
 }
 
+/// The user-level ~/.npmrc
+pub fn user_npmrc() -> Option<PathBuf> {
+    dirs_path().map(|home| home.join(".npmrc"))
+}
+
+/// A registry URL the way .npmrc keys name it: without scheme and with a
+/// trailing slash (`//host/path/`).
+pub fn nerf_dart(registry_url: &str) -> String {
+    let bare = registry_url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+    format!("//{}/", bare)
+}
+
+/// Set (Some) or remove (None) the `_authToken` line for `registry_url` in
+/// the .npmrc at `path`, keeping every other line. Returns whether the file
+/// changed. A new file is readable by its owner only, as it holds a token.
+pub fn set_auth_token(path: &Path, registry_url: &str, token: Option<&str>) -> Result<bool> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let updated = with_auth_token(&content, registry_url, token);
+    if updated == content {
+        return Ok(false);
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(updated.as_bytes())?;
+    Ok(true)
+}
+
+fn with_auth_token(content: &str, registry_url: &str, token: Option<&str>) -> String {
+    let key = format!("{}:_authToken", nerf_dart(registry_url));
+    let mut lines: Vec<String> = content
+        .lines()
+        .filter(|line| line.split_once('=').is_none_or(|(k, _)| k.trim() != key))
+        .map(String::from)
+        .collect();
+    if let Some(token) = token {
+        lines.push(format!("{}={}", key, token));
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+    lines.join("\n") + "\n"
+}
+
 fn parse_npmrc_file(path: &Path, entries: &mut HashMap<String, String>) -> Result<()> {
     let content = std::fs::read_to_string(path)?;
 
@@ -104,14 +189,119 @@ fn parse_npmrc_file(path: &Path, entries: &mut HashMap<String, String>) -> Resul
 }
 
 fn normalize_registry_url(url: &str) -> String {
-    url.trim_end_matches('/').to_string()
+    resolve_env_var(url).trim_end_matches('/').to_string()
 }
 
-/// Resolve ${ENV_VAR} references in token values.
+/// Resolve ${ENV_VAR} references in tokens and registry URLs
+/// (e.g. GitLab's .../projects/${CI_PROJECT_ID}/packages/npm/).
 fn resolve_env_var(value: &str) -> String {
-    if let Some(var_name) = value.strip_prefix("${").and_then(|v| v.strip_suffix('}')) {
-        std::env::var(var_name).unwrap_or_default()
-    } else {
-        value.to_string()
+    let mut output = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&std::env::var(&rest[start + 2..start + len]).unwrap_or_default());
+        rest = &rest[start + len + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similar_scope_suggests_near_misses_only() {
+        let config = NpmrcConfig {
+            entries: HashMap::from([
+                (
+                    "@my-org:registry".to_string(),
+                    "https://npm.example.com".to_string(),
+                ),
+                (
+                    "registry".to_string(),
+                    "https://registry.npmjs.org".to_string(),
+                ),
+            ]),
+        };
+        assert_eq!(config.similar_scope("@myorg"), Some("@my-org"));
+        assert_eq!(config.similar_scope("@my-org"), None);
+        assert_eq!(config.similar_scope("@acme"), None);
+    }
+
+    #[test]
+    fn auth_token_lines_are_replaced_and_removed() {
+        let npmrc = "registry=https://npm.example.com/\n//npm.example.com/:_authToken=old\n";
+        assert_eq!(
+            with_auth_token(npmrc, "https://npm.example.com", Some("new")),
+            "registry=https://npm.example.com/\n//npm.example.com/:_authToken=new\n"
+        );
+        assert_eq!(
+            with_auth_token(npmrc, "https://npm.example.com/", None),
+            "registry=https://npm.example.com/\n"
+        );
+        assert_eq!(
+            with_auth_token("", "https://registry.npmjs.org", Some("t")),
+            "//registry.npmjs.org/:_authToken=t\n"
+        );
+        assert_eq!(
+            with_auth_token(npmrc, "https://other.example.com", None),
+            npmrc
+        );
+        assert_eq!(
+            nerf_dart("https://gitlab.com/api/v4/projects/42/packages/npm/"),
+            "//gitlab.com/api/v4/projects/42/packages/npm/"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn new_npmrc_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".npmrc");
+        assert!(set_auth_token(&path, "https://registry.npmjs.org", Some("t")).unwrap());
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "//registry.npmjs.org/:_authToken=t\n"
+        );
+    }
+
+    #[test]
+    fn registry_routes_by_scope() {
+        let mut config = NpmrcConfig {
+            entries: HashMap::from([(
+                "@acme:registry".to_string(),
+                "https://npm.pkg.github.com/".to_string(),
+            )]),
+        };
+        assert_eq!(
+            config.registry_key(Some("@acme")).as_deref(),
+            Some("@acme:registry")
+        );
+        assert_eq!(
+            config.registry_url(Some("@acme")),
+            "https://npm.pkg.github.com"
+        );
+        assert_eq!(config.registry_key(Some("@other")), None);
+        assert_eq!(config.registry_url(None), "https://registry.npmjs.org");
+
+        config.entries.insert(
+            "registry".to_string(),
+            "https://npm.example.com".to_string(),
+        );
+        assert_eq!(
+            config.registry_key(Some("@other")).as_deref(),
+            Some("registry")
+        );
+        assert_eq!(
+            config.registry_url(Some("@other")),
+            "https://npm.example.com"
+        );
     }
 }
@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Build TeamCity service messages announcing the new version.
+/// Sets the build number and exposes the version as `env.ONEUP_VERSION`.
+pub fn teamcity_messages(version: &str) -> Vec<String> {
+    let value = teamcity_escape(version);
+    vec![
+        format!("##teamcity[buildNumber '{value}']"),
+        format!("##teamcity[setParameter name='env.ONEUP_VERSION' value='{value}']"),
+    ]
+}
+
+/// Write a Java properties file (`VERSION=<version>`) for Jenkins to load
+/// with `readProperties` or the EnvInject plugin.
+pub fn write_properties(path: &Path, version: &str) -> Result<()> {
+    std::fs::write(path, format!("VERSION={version}\n"))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Escape a value for use inside a TeamCity service message.
+fn teamcity_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '|' => out.push_str("||"),
+            '\'' => out.push_str("|'"),
+            '[' => out.push_str("|["),
+            ']' => out.push_str("|]"),
+            '\n' => out.push_str("|n"),
+            '\r' => out.push_str("|r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn teamcity_messages_for_version() {
+        let msgs = teamcity_messages("26.2.3");
+        assert_eq!(msgs[0], "##teamcity[buildNumber '26.2.3']");
+        assert_eq!(
+            msgs[1],
+            "##teamcity[setParameter name='env.ONEUP_VERSION' value='26.2.3']"
+        );
+    }
+
+    #[test]
+    fn teamcity_escape_special_chars() {
+        assert_eq!(teamcity_escape("a|b'c[d]e\n"), "a||b|'c|[d|]e|n");
+    }
+
+    #[test]
+    fn write_properties_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("oneup.properties");
+        write_properties(&path, "26.2.3").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "VERSION=26.2.3\n");
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::{Context, Result, bail};
+use chrono::Datelike;
+use toml_edit::Item;
+
+use crate::config::glob_match;
+use crate::format::VersionFormat;
+
+/// Keys of the [policy] table
+pub const POLICY_KEYS: &[&str] = &["max-micro", "blocked-days", "release-branches"];
+
+/// [policy]: rules every computed version must pass before it is written
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Policy {
+    /// Highest MICRO a release may carry
+    pub max_micro: Option<u64>,
+    /// Days of the month (1-31) on which nothing is released
+    pub blocked_days: Vec<u32>,
+    /// Branch patterns allowed to release stable versions; elsewhere a
+    /// prerelease is required (empty means every branch)
+    pub release_branches: Vec<String>,
+}
+
+impl Policy {
+    pub fn parse(item: &Item) -> Result<Self> {
+        let table = item.as_table_like().context("'policy' must be a table")?;
+        let mut policy = Policy::default();
+
+        if let Some(item) = table.get("max-micro") {
+            let max = item
+                .as_integer()
+                .filter(|n| *n >= 0)
+                .context("policy.max-micro must be a non-negative integer")?;
+            policy.max_micro = Some(max as u64);
+        }
+
+        if let Some(item) = table.get("blocked-days") {
+            let days = item
+                .as_array()
+                .context("policy.blocked-days must be an array of days of the month")?;
+            for day in days.iter() {
+                match day.as_integer() {
+                    Some(day @ 1..=31) => policy.blocked_days.push(day as u32),
+                    _ => bail!(
+                        "policy.blocked-days entries must be days of the month (1-31), got {}",
+                        day.to_string().trim()
+                    ),
+                }
+            }
+        }
+
+        if let Some(item) = table.get("release-branches") {
+            let branches = item
+                .as_array()
+                .context("policy.release-branches must be an array of branch patterns")?;
+            for branch in branches.iter() {
+                let branch = branch
+                    .as_str()
+                    .context("policy.release-branches entries must be strings")?;
+                policy.release_branches.push(branch.to_string());
+            }
+        }
+
+        Ok(policy)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Policy::default()
+    }
+
+    /// Every rule `version`, computed in `fmt` for `branch`, breaks. The
+    /// date is the one the version is built from. Without a branch (a
+    /// detached HEAD) the release-branches rule cannot apply.
+    pub fn violations(
+        &self,
+        version: &str,
+        fmt: &VersionFormat,
+        branch: Option<&str>,
+    ) -> Vec<String> {
+        let mut violations = Vec::new();
+        let (release, prerelease) = match version.split_once('+') {
+            Some((release, _)) => split_prerelease(release),
+            None => split_prerelease(version),
+        };
+
+        if let Some(max) = self.max_micro
+            && let Some(micro) = fmt
+                .extract_values(release)
+                .and_then(|values| fmt.micro_value(&values))
+            && micro > max
+        {
+            violations.push(format!("MICRO {} is above max-micro {}", micro, max));
+        }
+
+        let day = fmt.today.day();
+        if self.blocked_days.contains(&day) {
+            violations.push(format!(
+                "nothing is released on day {} of the month (blocked-days)",
+                day
+            ));
+        }
+
+        if let Some(branch) = branch
+            && prerelease.is_none()
+            && !self.release_branches.is_empty()
+            && !self.release_branches.iter().any(|p| glob_match(p, branch))
+        {
+            violations.push(format!(
+                "branch '{}' is not a release branch ({}); it may only publish prereleases (see [channels])",
+                branch,
+                self.release_branches.join(", ")
+            ));
+        }
+
+        violations
+    }
+}
+
+fn split_prerelease(version: &str) -> (&str, Option<&str>) {
+    match version.split_once('-') {
+        Some((release, pre)) => (release, Some(pre)),
+        None => (version, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn policy(toml: &str) -> Result<Policy> {
+        let doc: toml_edit::DocumentMut = toml.parse().unwrap();
+        Policy::parse(&doc["policy"])
+    }
+
+    fn format(day: u32) -> VersionFormat {
+        VersionFormat::parse("YY.MM.MICRO")
+            .unwrap()
+            .with_today(NaiveDate::from_ymd_opt(2026, 10, day).unwrap())
+    }
+
+    #[test]
+    fn parse_policy() {
+        let p = policy(
+            "[policy]\nmax-micro = 49\nblocked-days = [1, 31]\nrelease-branches = [\"main\", \"release/*\"]\n",
+        )
+        .unwrap();
+        assert_eq!(p.max_micro, Some(49));
+        assert_eq!(p.blocked_days, vec![1, 31]);
+        assert_eq!(p.release_branches, vec!["main", "release/*"]);
+        assert!(!p.is_empty());
+        assert!(policy("[policy]\n").unwrap().is_empty());
+
+        assert!(policy("[policy]\nmax-micro = -1\n").is_err());
+        assert!(policy("[policy]\nblocked-days = [32]\n").is_err());
+        assert!(policy("[policy]\nblocked-days = \"Mon\"\n").is_err());
+        assert!(policy("[policy]\nrelease-branches = [1]\n").is_err());
+        assert!(policy("policy = 1\n").is_err());
+    }
+
+    #[test]
+    fn max_micro_and_blocked_days() {
+        let p = policy("[policy]\nmax-micro = 49\nblocked-days = [1]\n").unwrap();
+        assert!(p.violations("26.10.49", &format(17), None).is_empty());
+        assert_eq!(
+            p.violations("26.10.50-beta.0+a1b2c3d", &format(17), None),
+            vec!["MICRO 50 is above max-micro 49"]
+        );
+        assert_eq!(
+            p.violations("26.10.0", &format(1), None),
+            vec!["nothing is released on day 1 of the month (blocked-days)"]
+        );
+    }
+
+    #[test]
+    fn prerelease_required_off_release_branches() {
+        let p = policy("[policy]\nrelease-branches = [\"main\", \"release/*\"]\n").unwrap();
+        let fmt = format(17);
+        assert!(p.violations("26.10.3", &fmt, Some("main")).is_empty());
+        assert!(
+            p.violations("26.10.3", &fmt, Some("release/26.10"))
+                .is_empty()
+        );
+        assert!(
+            p.violations("26.10.3-beta.0", &fmt, Some("develop"))
+                .is_empty()
+        );
+        assert!(p.violations("26.10.3", &fmt, None).is_empty());
+        let violations = p.violations("26.10.3+a1b2c3d", &fmt, Some("develop"));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("branch 'develop' is not a release branch"));
+    }
+}
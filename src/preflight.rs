@@ -0,0 +1,299 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+use crate::cli::PreflightArgs;
+#[cfg(feature = "npm")]
+use crate::cli::RegistryType;
+use crate::config::Config;
+use crate::error::{ErrorKind, ResultExt};
+use crate::format::VersionFormat;
+use crate::git;
+use crate::redact::redact;
+use crate::registry::PackageInfo;
+use crate::target::TargetFile;
+use crate::timing::Timings;
+#[cfg(feature = "npm")]
+use crate::version::npm_client;
+use crate::version::{
+    below_published, current_branch, determine_version, load_targets, query_registry,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: redact(&detail.into()),
+        }
+    }
+}
+
+pub fn run(args: PreflightArgs) -> Result<()> {
+    let checks = checks(&args)?;
+    let passed = checks.iter().all(|c| c.status != Status::Fail);
+
+    if args.json {
+        let report = json!({ "passed": passed, "checks": checks });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for check in &checks {
+            let status = match check.status {
+                Status::Pass => "pass",
+                Status::Warn => "warn",
+                Status::Fail => "FAIL",
+            };
+            eprintln!("[{}] {}: {}", status, check.name, check.detail);
+        }
+        println!("{}", if passed { "pass" } else { "fail" });
+    }
+
+    if !passed {
+        let failed: Vec<&str> = checks
+            .iter()
+            .filter(|c| c.status == Status::Fail)
+            .map(|c| c.name)
+            .collect();
+        return Err(anyhow!("preflight failed: {}", failed.join(", "))).kind(ErrorKind::Check);
+    }
+    Ok(())
+}
+
+/// Run every check. Only a broken setup (unreadable targets or oneup.toml)
+/// is an error; everything else becomes a failed check in the report.
+fn checks(args: &PreflightArgs) -> Result<Vec<Check>> {
+    let config = Config::load(Path::new("."))?;
+    let targets = load_targets(
+        &args.target,
+        config.primary_target(),
+        &config,
+        args.package_name.as_deref(),
+    )?;
+    let (primary_path, primary_target) = &targets[0];
+    let project_dir = primary_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let mut checks = Vec::new();
+
+    // Manifest metadata for every published target
+    for (path, target) in targets.iter().filter(|(_, t)| t.is_published()) {
+        let missing = target.missing_fields();
+        checks.push(if missing.is_empty() {
+            Check::new("manifest", Status::Pass, path.display().to_string())
+        } else {
+            Check::new(
+                "manifest",
+                Status::Fail,
+                format!("{} is missing {}", path.display(), missing.join(", ")),
+            )
+        });
+    }
+
+    let fmt = match VersionFormat::parse(&args.format) {
+        Ok(fmt) => {
+            checks.push(Check::new("format", Status::Pass, &args.format));
+            Some(fmt)
+        }
+        Err(err) => {
+            checks.push(Check::new("format", Status::Fail, format!("{:#}", err)));
+            None
+        }
+    };
+
+    checks.push(git_check(args, &targets, project_dir));
+
+    let info = match query_registry(
+        primary_path,
+        primary_target,
+        args.registry.as_deref(),
+        args.registry_type,
+        &config,
+        args.verbose,
+        &mut Timings::new(false),
+    ) {
+        Ok(info) => {
+            let detail = match &info {
+                PackageInfo::Found { versions, .. } => {
+                    format!("reachable, {} versions published", versions.len())
+                }
+                PackageInfo::NotFound => "reachable, package not published yet".to_string(),
+            };
+            checks.push(Check::new("registry", Status::Pass, detail));
+            Some(info)
+        }
+        Err(err) => {
+            checks.push(Check::new("registry", Status::Fail, format!("{:#}", err)));
+            None
+        }
+    };
+
+    // The version `oneup version` would write, and its tag
+    if let (Some(fmt), Some(info)) = (&fmt, &info) {
+        let package = &primary_target.package_name;
+        match determine_version(
+            info,
+            package,
+            fmt,
+            &config.skip,
+            config.clock_skew_hours,
+            args.verbose,
+        ) {
+            Ok(version) => {
+                // Rendering fails for versions the manifest cannot hold
+                let problem = below_published(info, &version).or_else(|| {
+                    primary_target
+                        .render(&version, Some(fmt.today))
+                        .err()
+                        .map(|err| format!("{:#}", err))
+                });
+                checks.push(match problem {
+                    Some(problem) => Check::new("version", Status::Fail, problem),
+                    None => Check::new("version", Status::Pass, format!("next: {}", version)),
+                });
+                let tags = [format!("v{}", version), version.clone()];
+                let taken: Vec<&String> = tags
+                    .iter()
+                    .filter(|t| git::tag_exists(project_dir, t).unwrap_or(false))
+                    .collect();
+                checks.push(if taken.is_empty() {
+                    Check::new("tag", Status::Pass, format!("v{} is free", version))
+                } else {
+                    Check::new(
+                        "tag",
+                        Status::Fail,
+                        format!("tag {} already exists", taken[0]),
+                    )
+                });
+                if !config.policy.is_empty() && version != primary_target.version {
+                    checks.push(policy_check(&config, fmt, &version, project_dir));
+                }
+            }
+            Err(err) => checks.push(Check::new("version", Status::Fail, format!("{:#}", err))),
+        }
+    }
+
+    checks.push(auth_check(args, primary_target, project_dir));
+
+    Ok(checks)
+}
+
+/// The [policy] rules in oneup.toml, applied to the next version.
+fn policy_check(config: &Config, fmt: &VersionFormat, version: &str, dir: &Path) -> Check {
+    let branch = if config.policy.release_branches.is_empty() {
+        None
+    } else {
+        match current_branch(dir) {
+            Ok(branch) => branch,
+            Err(err) => return Check::new("policy", Status::Warn, format!("{:#}", err)),
+        }
+    };
+    let violations = config.policy.violations(version, fmt, branch.as_deref());
+    if violations.is_empty() {
+        Check::new("policy", Status::Pass, format!("{} passes", version))
+    } else {
+        Check::new("policy", Status::Fail, violations.join("; "))
+    }
+}
+
+/// The working tree must be clean; with --allow-dirty-targets, changes to
+/// the target files themselves only warn.
+fn git_check(args: &PreflightArgs, targets: &[(PathBuf, TargetFile)], dir: &Path) -> Check {
+    let changes = match git::uncommitted_changes(dir) {
+        Ok(changes) if changes.is_empty() => {
+            return Check::new("git", Status::Pass, "working tree clean");
+        }
+        Ok(changes) => changes,
+        Err(err) => return Check::new("git", Status::Warn, format!("{:#}", err)),
+    };
+
+    let (dirty_targets, other) = match git::repo_root(dir) {
+        Ok(root) if args.allow_dirty_targets => {
+            let target_files: Vec<PathBuf> = targets
+                .iter()
+                .filter_map(|(path, _)| path.canonicalize().ok())
+                .collect();
+            changes.into_iter().partition(|change| {
+                root.join(change)
+                    .canonicalize()
+                    .is_ok_and(|path| target_files.contains(&path))
+            })
+        }
+        _ => (Vec::new(), changes),
+    };
+
+    if other.is_empty() {
+        Check::new(
+            "git",
+            Status::Warn,
+            format!("only target files changed: {}", dirty_targets.join(", ")),
+        )
+    } else {
+        Check::new(
+            "git",
+            Status::Fail,
+            format!("uncommitted changes: {}", other.join(", ")),
+        )
+    }
+}
+
+/// Built without the npm registry client: nothing to check credentials with.
+#[cfg(not(feature = "npm"))]
+fn auth_check(_: &PreflightArgs, _: &TargetFile, _: &Path) -> Check {
+    Check::new(
+        "auth",
+        Status::Warn,
+        "skipped (built without the npm feature)",
+    )
+}
+
+/// Verify npm credentials with a whoami request. Other registries are only
+/// read anonymously by oneup, so there is nothing to check.
+#[cfg(feature = "npm")]
+fn auth_check(args: &PreflightArgs, target: &TargetFile, project_dir: &Path) -> Check {
+    let is_npm = args
+        .registry_type
+        .map_or(!target.is_cargo() && !target.is_vscode(), |t| {
+            t == RegistryType::Npm
+        });
+    if !is_npm || target.package_name.is_empty() {
+        return Check::new("auth", Status::Warn, "skipped (not an npm registry)");
+    }
+
+    let client = match npm_client(
+        project_dir,
+        &target.package_name,
+        args.registry.as_deref(),
+        args.verbose,
+    ) {
+        Ok(client) => client,
+        Err(err) => return Check::new("auth", Status::Fail, format!("{:#}", err)),
+    };
+    if !client.has_auth() {
+        return Check::new(
+            "auth",
+            Status::Warn,
+            format!("no token configured for {}", client.registry_url()),
+        );
+    }
+    match client.whoami() {
+        Ok(user) => Check::new("auth", Status::Pass, format!("authenticated as {}", user)),
+        Err(err) => Check::new("auth", Status::Fail, format!("{:#}", err)),
+    }
+}
@@ -1,48 +1,344 @@
 use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
 
+use crate::resolve::compare_versions;
+
+// The npm registry client; packuments and fixtures are read without it
+#[cfg(feature = "npm")]
+use {
+    crate::error::{ErrorKind, ResultExt},
+    crate::http,
+    crate::redact::redact,
+    anyhow::anyhow,
+};
+
+#[cfg(feature = "npm")]
 pub struct RegistryClient {
     http: reqwest::blocking::Client,
     registry_url: String,
-    auth_token: Option<String>,
+    /// Authorization header name and value
+    auth: Option<(&'static str, String)>,
+    /// Largest packument read before giving up, in bytes
+    max_response_size: u64,
 }
 
+#[cfg(feature = "npm")]
+const MB: u64 = 1024 * 1024;
+
+/// Default `max-response-mb`: well above the largest public packuments
+pub const DEFAULT_MAX_RESPONSE_MB: u64 = 256;
+
 /// Result of querying the registry for a package
 pub enum PackageInfo {
     /// Package exists with all published version strings and dist-tags.latest
     Found {
         versions: Vec<String>,
         latest: String,
+        /// Publish time (RFC 3339) of each version the registry dates
+        published: BTreeMap<String, String>,
     },
     /// Package does not exist in the registry (new package)
     NotFound,
 }
 
+impl PackageInfo {
+    /// The answers of two registries serving the same package combined:
+    /// every version either has, and the higher latest.
+    #[cfg(feature = "npm")]
+    pub fn merge(self, other: PackageInfo) -> PackageInfo {
+        match (self, other) {
+            (PackageInfo::NotFound, info) | (info, PackageInfo::NotFound) => info,
+            (
+                PackageInfo::Found {
+                    mut versions,
+                    latest,
+                    mut published,
+                },
+                PackageInfo::Found {
+                    versions: other_versions,
+                    latest: other_latest,
+                    published: other_published,
+                },
+            ) => {
+                for version in other_versions {
+                    if !versions.contains(&version) {
+                        versions.push(version);
+                    }
+                }
+                for (version, time) in other_published {
+                    published.entry(version).or_insert(time);
+                }
+                let latest = if compare_versions(&other_latest, &latest).is_gt() {
+                    other_latest
+                } else {
+                    latest
+                };
+                PackageInfo::Found {
+                    versions,
+                    latest,
+                    published,
+                }
+            }
+        }
+    }
+
+    /// The registry as it stood at `cutoff`, like npm's `--before`: only the
+    /// versions published earlier, with latest the highest stable one left.
+    /// Versions the registry does not date are dropped.
+    pub fn before(self, cutoff: chrono::DateTime<chrono::Utc>) -> PackageInfo {
+        let PackageInfo::Found {
+            versions,
+            latest,
+            published,
+        } = self
+        else {
+            return PackageInfo::NotFound;
+        };
+        let published_before = |version: &String| {
+            published
+                .get(version)
+                .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+                .is_some_and(|time| time < cutoff)
+        };
+        let versions: Vec<String> = versions.into_iter().filter(published_before).collect();
+        let latest = if versions.contains(&latest) {
+            latest
+        } else {
+            let stable = versions.iter().filter(|v| !v.contains('-'));
+            match stable.max_by(|a, b| compare_versions(a, b)) {
+                Some(v) => v.clone(),
+                None => match versions.iter().max_by(|a, b| compare_versions(a, b)) {
+                    Some(v) => v.clone(),
+                    None => return PackageInfo::NotFound,
+                },
+            }
+        };
+        let published = published
+            .into_iter()
+            .filter(|(version, _)| versions.contains(version))
+            .collect();
+        PackageInfo::Found {
+            versions,
+            latest,
+            published,
+        }
+    }
+
+    /// Versions the registry dates but no longer serves: unpublished from
+    /// npm or yanked from crates.io.
+    pub fn retired(&self) -> Vec<&str> {
+        match self {
+            PackageInfo::Found {
+                versions,
+                published,
+                ..
+            } => published
+                .keys()
+                .filter(|version| !versions.contains(version))
+                .map(String::as_str)
+                .collect(),
+            PackageInfo::NotFound => Vec::new(),
+        }
+    }
+
+    /// Count retired versions as published, so their numbers are never
+    /// issued again.
+    pub fn with_retired(self) -> PackageInfo {
+        let retired: Vec<String> = self.retired().into_iter().map(String::from).collect();
+        match self {
+            PackageInfo::Found {
+                mut versions,
+                latest,
+                published,
+            } => {
+                versions.extend(retired);
+                PackageInfo::Found {
+                    versions,
+                    latest,
+                    published,
+                }
+            }
+            PackageInfo::NotFound => PackageInfo::NotFound,
+        }
+    }
+
+    /// When `version` was published, if the registry says.
+    pub fn published_at(&self, version: &str) -> Option<&str> {
+        match self {
+            PackageInfo::Found { published, .. } => published.get(version).map(String::as_str),
+            PackageInfo::NotFound => None,
+        }
+    }
+}
+
+#[cfg(feature = "npm")]
 impl RegistryClient {
     pub fn new(registry_url: &str, auth_token: Option<String>) -> Self {
         Self {
-            http: reqwest::blocking::Client::new(),
+            http: http::client(),
             registry_url: registry_url.to_string(),
-            auth_token,
+            auth: auth_token.map(|token| ("Authorization", format!("Bearer {token}"))),
+            max_response_size: DEFAULT_MAX_RESPONSE_MB * MB,
         }
     }
 
+    /// Refuse packuments larger than `mb` megabytes.
+    pub fn with_max_response_mb(mut self, mb: u64) -> Self {
+        self.max_response_size = mb.saturating_mul(MB);
+        self
+    }
+
+    pub fn registry_url(&self) -> &str {
+        &self.registry_url
+    }
+
+    /// Whether requests carry credentials.
+    pub fn has_auth(&self) -> bool {
+        self.auth.is_some()
+    }
+
+    /// GET /-/whoami → the user the credentials belong to
+    pub fn whoami(&self) -> Result<String> {
+        let url = format!("{}/-/whoami", self.registry_url);
+        let mut req = self.http.get(&url).header("Accept", "application/json");
+        if let Some((name, value)) = &self.auth {
+            req = req.header(*name, value);
+        }
+
+        let resp = req
+            .send()
+            .with_context(|| format!("failed to query registry {}", self.registry_url))
+            .kind(ErrorKind::Network)?;
+        if matches!(
+            resp.status(),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+        ) {
+            return Err(anyhow!(
+                "credentials rejected by {} (HTTP {})",
+                self.registry_url,
+                resp.status()
+            ))
+            .kind(ErrorKind::Auth);
+        }
+        if !resp.status().is_success() {
+            return Err(anyhow!("{} returned HTTP {}", url, resp.status()))
+                .kind(ErrorKind::Network);
+        }
+
+        let body: serde_json::Value = resp
+            .json()
+            .context("failed to parse whoami response")
+            .kind(ErrorKind::Network)?;
+        Ok(body
+            .get("username")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string())
+    }
+
+    /// npm web login: POST /-/v1/login for a browser URL, announced through
+    /// `open`, then poll the done URL until the user has logged in. Returns
+    /// the new token.
+    pub fn web_login(&self, open: impl Fn(&str)) -> Result<String> {
+        let url = format!("{}/-/v1/login", self.registry_url);
+        let resp = self
+            .http
+            .post(&url)
+            .json(&serde_json::json!({}))
+            .send()
+            .with_context(|| format!("failed to reach registry {}", self.registry_url))
+            .kind(ErrorKind::Network)?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "{} does not support web login (HTTP {}); paste a token instead",
+                self.registry_url,
+                resp.status()
+            ))
+            .kind(ErrorKind::Auth);
+        }
+        let body: serde_json::Value = resp
+            .json()
+            .context("failed to parse web login response")
+            .kind(ErrorKind::Network)?;
+        let field = |name: &str| {
+            body.get(name)
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .with_context(|| format!("web login response has no {}", name))
+                .kind(ErrorKind::Network)
+        };
+        let (login_url, done_url) = (field("loginUrl")?, field("doneUrl")?);
+        open(&login_url);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(300);
+        while std::time::Instant::now() < deadline {
+            let resp = self
+                .http
+                .get(&done_url)
+                .send()
+                .with_context(|| format!("failed to reach registry {}", self.registry_url))
+                .kind(ErrorKind::Network)?;
+            match resp.status() {
+                // Not logged in yet; the registry says how long to wait
+                reqwest::StatusCode::ACCEPTED => {
+                    let wait = resp
+                        .headers()
+                        .get("retry-after")
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(1u64);
+                    std::thread::sleep(std::time::Duration::from_secs(wait.clamp(1, 30)));
+                }
+                status if status.is_success() => {
+                    let body: serde_json::Value = resp
+                        .json()
+                        .context("failed to parse web login result")
+                        .kind(ErrorKind::Network)?;
+                    return body
+                        .get("token")
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                        .context("web login result has no token")
+                        .kind(ErrorKind::Auth);
+                }
+                status => {
+                    return Err(anyhow!("web login failed (HTTP {})", status))
+                        .kind(ErrorKind::Auth);
+                }
+            }
+        }
+        Err(anyhow!("web login timed out after 5 minutes")).kind(ErrorKind::Auth)
+    }
+
+    /// Authenticate with a custom header instead of a Bearer token
+    /// (GitLab's `Job-Token` / `Private-Token`).
+    pub fn with_auth_header(mut self, name: &'static str, value: String) -> Self {
+        self.auth = Some((name, value));
+        self
+    }
+
     /// GET /<package> → fetch all versions and dist-tags.latest
     pub fn get_package(&self, package_name: &str, verbose: bool) -> Result<PackageInfo> {
         let encoded = encode_package_name(package_name);
         let url = format!("{}/{}", self.registry_url, encoded);
 
         if verbose {
-            eprintln!("[registry] GET {}", url);
+            eprintln!("[registry] GET {}", redact(&url));
         }
 
         let mut req = self.http.get(&url).header("Accept", "application/json");
-        if let Some(token) = &self.auth_token {
-            req = req.header("Authorization", format!("Bearer {token}"));
+        if let Some((name, value)) = &self.auth {
+            req = req.header(*name, value);
         }
 
         let resp = req
             .send()
-            .with_context(|| format!("failed to query registry {}", self.registry_url))?;
+            .with_context(|| format!("failed to query registry {}", self.registry_url))
+            .kind(ErrorKind::Network)?;
 
         if resp.status() == reqwest::StatusCode::NOT_FOUND {
             if verbose {
@@ -52,44 +348,326 @@ impl RegistryClient {
         }
 
         if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
-            bail!(
+            return Err(anyhow!(
                 "registry authentication failed for {} (HTTP 401)",
                 self.registry_url
-            );
+            ))
+            .kind(ErrorKind::Auth);
         }
 
         if !resp.status().is_success() {
-            bail!(
+            return Err(anyhow!(
                 "failed to query registry {}: HTTP {}",
                 self.registry_url,
                 resp.status()
-            );
+            ))
+            .kind(ErrorKind::Network);
         }
 
-        let body: serde_json::Value = resp.json().context("failed to parse registry response")?;
-
-        let latest = body
-            .pointer("/dist-tags/latest")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0.0.0")
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
             .to_string();
-
-        let versions: Vec<String> = body
-            .get("versions")
-            .and_then(|v| v.as_object())
-            .map(|obj| obj.keys().cloned().collect())
-            .unwrap_or_default();
+        // Refuse what the registry announces as too big before reading it
+        if resp
+            .content_length()
+            .is_some_and(|len| len > self.max_response_size)
+        {
+            return Err(anyhow!(too_large(self.max_response_size))).kind(ErrorKind::Network);
+        }
+        let body = Limited::new(resp, self.max_response_size);
+        let (versions, latest, published) = read_packument(body, &content_type)
+            .with_context(|| format!("unexpected response from {}", url))
+            .kind(ErrorKind::Network)?;
 
         if verbose {
             eprintln!("[registry] latest: {}", latest);
             eprintln!("[registry] total versions: {}", versions.len());
         }
 
-        Ok(PackageInfo::Found { versions, latest })
+        Ok(PackageInfo::Found {
+            versions,
+            latest,
+            published,
+        })
+    }
+}
+
+/// Registry answers from a local fixture (`--registry file://fixture.json`):
+/// a packument, or `{"versions": [...], "dist-tags": {"latest": ...}}`.
+/// `{}` or `{"versions": []}` stands for a package that is not published yet.
+pub fn read_fixture(path: &Path, verbose: bool) -> Result<PackageInfo> {
+    if verbose {
+        eprintln!("[registry] fixture: {}", path.display());
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read registry fixture {}", path.display()))?;
+    let (versions, latest, published) = parse_packument(&text, "application/json")
+        .with_context(|| format!("invalid registry fixture {}", path.display()))?;
+
+    // Unpublished versions still mark a package that once existed
+    if versions.is_empty() && published.is_empty() {
+        if verbose {
+            eprintln!("[registry] package not found (empty fixture)");
+        }
+        return Ok(PackageInfo::NotFound);
+    }
+    if verbose {
+        eprintln!("[registry] latest: {}", latest);
+        eprintln!("[registry] total versions: {}", versions.len());
+    }
+    Ok(PackageInfo::Found {
+        versions,
+        latest,
+        published,
+    })
+}
+
+/// Save a registry answer as a cassette that `read_fixture` replays.
+pub fn write_cassette(
+    path: &Path,
+    info: &PackageInfo,
+    registry: &str,
+    package: &str,
+) -> Result<()> {
+    let (versions, latest, published) = match info {
+        PackageInfo::Found {
+            versions,
+            latest,
+            published,
+        } => (versions.clone(), Some(latest), Some(published)),
+        PackageInfo::NotFound => (Vec::new(), None, None),
+    };
+    let cassette = serde_json::json!({
+        "registry": registry,
+        "package": package,
+        "recorded_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "dist-tags": latest.map(|l| serde_json::json!({ "latest": l })),
+        "time": published,
+        "versions": versions,
+    });
+    let json = serde_json::to_string_pretty(&cassette)? + "\n";
+    std::fs::write(path, json).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Versions, latest release and publish times from a packument. Proxies
+/// that answer with an HTML page are reported with a snippet of the body;
+/// packuments missing `dist-tags` or `versions` fall back to what the other
+/// one provides.
+fn parse_packument(
+    text: &str,
+    content_type: &str,
+) -> Result<(Vec<String>, String, BTreeMap<String, String>)> {
+    read_packument(text.as_bytes(), content_type)
+}
+
+/// `parse_packument` over a stream. Version manifests are skipped as they
+/// are read, so packuments with 100k+ versions never sit in memory whole.
+fn read_packument(
+    reader: impl Read,
+    content_type: &str,
+) -> Result<(Vec<String>, String, BTreeMap<String, String>)> {
+    let mut reader = BufReader::new(reader);
+    // The first buffered chunk tells HTML and garbage apart and gives the
+    // snippet for error messages
+    let head = String::from_utf8_lossy(reader.fill_buf()?).into_owned();
+    let looks_like_html = content_type.contains("html") || head.trim_start().starts_with('<');
+    if looks_like_html {
+        bail!(
+            "registry returned HTML instead of JSON (content-type: {}); \
+             check the registry URL and proxy: {}",
+            if content_type.is_empty() {
+                "none"
+            } else {
+                content_type
+            },
+            snippet(&head)
+        );
+    }
+    let is_json = head.trim_start().starts_with('{');
+    let packument: Packument = match serde_json::from_reader(reader) {
+        // Oversized bodies are cut off by the reader, not malformed
+        Err(err) if err.is_io() => return Err(err.into()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to parse registry response: {}", snippet(&head)));
+        }
+        Ok(_) if !is_json => bail!(
+            "registry response is not a package document: {}",
+            snippet(&head)
+        ),
+        Ok(packument) => packument,
+    };
+
+    let mut versions = packument.versions.0;
+    let dist_tags = packument.dist_tags.unwrap_or_default();
+
+    // No versions object: the dist-tags still name published versions
+    if versions.is_empty() {
+        versions = dist_tags
+            .values()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect();
+        versions.sort();
+        versions.dedup();
+    }
+
+    // No dist-tags.latest: take the highest stable version
+    let latest = dist_tags
+        .get("latest")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| {
+            versions
+                .iter()
+                .filter(|v| !v.contains('-'))
+                .max_by(|a, b| compare_versions(a, b))
+                .cloned()
+        })
+        .unwrap_or_else(|| "0.0.0".to_string());
+    // `time` also holds the package's "created" and "modified" stamps
+    let time = packument.time.unwrap_or_default();
+    let mut published: BTreeMap<String, String> = time
+        .iter()
+        .filter(|(version, _)| *version != "created" && *version != "modified")
+        .filter_map(|(version, time)| Some((version.clone(), time.as_str()?.to_string())))
+        .collect();
+    // A fully unpublished package keeps only {"unpublished": {time, versions}};
+    // date those versions by the unpublish
+    if let Some(unpublished) = time.get("unpublished")
+        && let Some(when) = unpublished.get("time").and_then(|t| t.as_str())
+    {
+        for version in unpublished
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+        {
+            published
+                .entry(version.to_string())
+                .or_insert_with(|| when.to_string());
+        }
+    }
+
+    Ok((versions, latest, published))
+}
+
+/// The parts of a packument oneup reads; everything else is skipped while
+/// parsing
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Packument {
+    #[serde(rename = "dist-tags")]
+    dist_tags: Option<BTreeMap<String, serde_json::Value>>,
+    versions: VersionKeys,
+    time: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+/// A packument's `versions` object reduced to its keys, or a plain list of
+/// versions (fixtures)
+#[derive(Default)]
+struct VersionKeys(Vec<String>);
+
+impl<'de> Deserialize<'de> for VersionKeys {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = VersionKeys;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an object or list of versions")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<VersionKeys, A::Error> {
+                let mut keys = Vec::new();
+                while let Some((key, IgnoredAny)) = map.next_entry::<String, IgnoredAny>()? {
+                    keys.push(key);
+                }
+                Ok(VersionKeys(keys))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<VersionKeys, A::Error> {
+                let mut versions = Vec::new();
+                while let Some(value) = seq.next_element::<serde_json::Value>()? {
+                    if let Some(version) = value.as_str() {
+                        versions.push(version.to_string());
+                    }
+                }
+                Ok(VersionKeys(versions))
+            }
+
+            fn visit_unit<E>(self) -> Result<VersionKeys, E> {
+                Ok(VersionKeys::default())
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// A response body that fails once more than `limit` bytes are read
+#[cfg(feature = "npm")]
+struct Limited<R> {
+    inner: R,
+    left: u64,
+    limit: u64,
+}
+
+#[cfg(feature = "npm")]
+impl<R: Read> Limited<R> {
+    fn new(inner: R, limit: u64) -> Self {
+        Self {
+            inner,
+            left: limit,
+            limit,
+        }
+    }
+}
+
+#[cfg(feature = "npm")]
+impl<R: Read> Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Read one byte past the limit to tell "exactly full" from "too big"
+        let max = buf.len().min(self.left.saturating_add(1) as usize);
+        let n = self.inner.read(&mut buf[..max])?;
+        if n as u64 > self.left {
+            return Err(std::io::Error::other(too_large(self.limit)));
+        }
+        self.left -= n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "npm")]
+fn too_large(limit: u64) -> String {
+    format!(
+        "registry response is larger than {} MB; raise max-response-mb in oneup.toml if the package really is this big",
+        limit / MB
+    )
+}
+
+/// First 200 characters of a response body with whitespace collapsed, for error messages.
+fn snippet(text: &str) -> String {
+    let flat: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(200) {
+        Some((end, _)) => format!("{}…", &flat[..end]),
+        None => flat,
     }
 }
 
 /// Encode scoped package names: @scope/name → @scope%2fname
+#[cfg(feature = "npm")]
 fn encode_package_name(name: &str) -> String {
     if name.starts_with('@') {
         name.replacen('/', "%2f", 1)
@@ -97,3 +675,245 @@ fn encode_package_name(name: &str) -> String {
         name.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_standard_packument() {
+        let (versions, latest, published) = parse_packument(
+            r#"{"dist-tags": {"latest": "26.2.1"}, "versions": {"26.2.0": {}, "26.2.1": {}},
+                "time": {"created": "2026-02-16T08:00:00.000Z", "modified": "2026-02-17T09:31:00.000Z",
+                         "26.2.0": "2026-02-16T08:00:00.000Z", "26.2.1": "2026-02-17T09:30:00.000Z"}}"#,
+            "application/json",
+        )
+        .unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(latest, "26.2.1");
+        assert_eq!(
+            published.keys().collect::<Vec<_>>(),
+            vec!["26.2.0", "26.2.1"]
+        );
+        assert_eq!(published["26.2.1"], "2026-02-17T09:30:00.000Z");
+    }
+
+    #[test]
+    fn parse_tolerates_missing_fields() {
+        let (versions, latest, _) = parse_packument(
+            r#"{"versions": {"26.2.0": {}, "26.2.3": {}, "26.3.0-beta.0": {}}}"#,
+            "",
+        )
+        .unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(latest, "26.2.3");
+
+        let (versions, latest, _) = parse_packument(
+            r#"{"dist-tags": {"latest": "26.2.1", "next": "26.3.0-rc.0"}}"#,
+            "",
+        )
+        .unwrap();
+        assert_eq!(versions, vec!["26.2.1", "26.3.0-rc.0"]);
+        assert_eq!(latest, "26.2.1");
+    }
+
+    #[test]
+    #[cfg(feature = "npm")]
+    fn merge_takes_union_and_highest_latest() {
+        let found = |versions: &[&str], latest: &str, time: &str| PackageInfo::Found {
+            versions: versions.iter().map(|v| v.to_string()).collect(),
+            latest: latest.to_string(),
+            published: BTreeMap::from([(latest.to_string(), time.to_string())]),
+        };
+        let npmjs = found(&["26.2.0", "26.2.1"], "26.2.1", "2026-02-17T09:00:00Z");
+        let github = found(&["26.2.0", "26.2.2"], "26.2.2", "2026-02-17T10:00:00Z");
+        let merged = npmjs.merge(github);
+        assert_eq!(merged.published_at("26.2.2"), Some("2026-02-17T10:00:00Z"));
+        let PackageInfo::Found {
+            versions, latest, ..
+        } = merged
+        else {
+            panic!("expected versions");
+        };
+        assert_eq!(versions, vec!["26.2.0", "26.2.1", "26.2.2"]);
+        assert_eq!(latest, "26.2.2");
+
+        let only = found(&["26.2.0"], "26.2.0", "2026-02-16T09:00:00Z");
+        assert!(matches!(
+            PackageInfo::NotFound.merge(only),
+            PackageInfo::Found { .. }
+        ));
+        assert!(matches!(
+            PackageInfo::NotFound.merge(PackageInfo::NotFound),
+            PackageInfo::NotFound
+        ));
+    }
+
+    #[test]
+    fn before_keeps_versions_published_earlier() {
+        let info = PackageInfo::Found {
+            versions: ["26.2.0", "26.2.1", "26.2.2-beta.0", "26.2.2", "26.1.0"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            latest: "26.2.2".to_string(),
+            published: BTreeMap::from([
+                ("26.2.0".to_string(), "2026-02-16T08:00:00.000Z".to_string()),
+                ("26.2.1".to_string(), "2026-02-17T09:30:00.000Z".to_string()),
+                (
+                    "26.2.2-beta.0".to_string(),
+                    "2026-02-17T10:00:00.000Z".to_string(),
+                ),
+                ("26.2.2".to_string(), "2026-02-18T08:00:00.000Z".to_string()),
+            ]),
+        };
+        let cutoff = "2026-02-18T00:00:00Z".parse().unwrap();
+        let info = info.before(cutoff);
+        assert_eq!(info.published_at("26.2.2"), None);
+        let PackageInfo::Found {
+            versions, latest, ..
+        } = info
+        else {
+            panic!("expected versions");
+        };
+        // 26.1.0 has no publish time, so it cannot be placed before the cutoff
+        assert_eq!(versions, vec!["26.2.0", "26.2.1", "26.2.2-beta.0"]);
+        assert_eq!(latest, "26.2.1");
+
+        let info = PackageInfo::Found {
+            versions: vec!["26.2.0".to_string()],
+            latest: "26.2.0".to_string(),
+            published: BTreeMap::from([("26.2.0".to_string(), "2026-02-16T08:00:00Z".to_string())]),
+        };
+        assert!(matches!(
+            info.before("2026-01-01T00:00:00Z".parse().unwrap()),
+            PackageInfo::NotFound
+        ));
+    }
+
+    #[test]
+    fn unpublished_versions_are_retired() {
+        let (versions, latest, published) = parse_packument(
+            r#"{"dist-tags": {"latest": "26.2.0"}, "versions": {"26.2.0": {}},
+                "time": {"26.2.0": "2026-02-16T08:00:00.000Z", "26.2.1": "2026-02-16T09:00:00.000Z"}}"#,
+            "application/json",
+        )
+        .unwrap();
+        let info = PackageInfo::Found {
+            versions,
+            latest,
+            published,
+        };
+        assert_eq!(info.retired(), vec!["26.2.1"]);
+        let PackageInfo::Found { versions, .. } = info.with_retired() else {
+            panic!("expected versions");
+        };
+        assert_eq!(versions, vec!["26.2.0", "26.2.1"]);
+
+        let (versions, _, published) = parse_packument(
+            r#"{"time": {"created": "2026-01-01T00:00:00.000Z", "modified": "2026-02-01T00:00:00.000Z",
+                "unpublished": {"time": "2026-02-01T00:00:00.000Z", "versions": ["26.1.0", "26.1.1"]}}}"#,
+            "application/json",
+        )
+        .unwrap();
+        assert!(versions.is_empty());
+        assert_eq!(
+            published.keys().collect::<Vec<_>>(),
+            vec!["26.1.0", "26.1.1"]
+        );
+    }
+
+    #[test]
+    fn read_fixture_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixture.json");
+        std::fs::write(&path, r#"{"versions": ["26.2.0", "26.2.1"]}"#).unwrap();
+        let PackageInfo::Found {
+            versions, latest, ..
+        } = read_fixture(&path, false).unwrap()
+        else {
+            panic!("expected versions");
+        };
+        assert_eq!(versions, vec!["26.2.0", "26.2.1"]);
+        assert_eq!(latest, "26.2.1");
+
+        std::fs::write(&path, "{}").unwrap();
+        assert!(matches!(
+            read_fixture(&path, false).unwrap(),
+            PackageInfo::NotFound
+        ));
+        assert!(read_fixture(&dir.path().join("missing.json"), false).is_err());
+    }
+
+    #[test]
+    fn cassette_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.json");
+        let info = PackageInfo::Found {
+            versions: vec!["26.2.0".to_string(), "26.2.1-beta.0".to_string()],
+            latest: "26.2.0".to_string(),
+            published: BTreeMap::from([("26.2.0".to_string(), "2026-02-03T10:00:00Z".to_string())]),
+        };
+        write_cassette(&path, &info, "https://registry.npmjs.org", "pkg").unwrap();
+        let info = read_fixture(&path, false).unwrap();
+        assert_eq!(info.published_at("26.2.0"), Some("2026-02-03T10:00:00Z"));
+        assert_eq!(info.published_at("26.2.1-beta.0"), None);
+        let PackageInfo::Found {
+            versions, latest, ..
+        } = info
+        else {
+            panic!("expected versions");
+        };
+        assert_eq!(versions, vec!["26.2.0", "26.2.1-beta.0"]);
+        assert_eq!(latest, "26.2.0");
+
+        write_cassette(
+            &path,
+            &PackageInfo::NotFound,
+            "https://registry.npmjs.org",
+            "pkg",
+        )
+        .unwrap();
+        assert!(matches!(
+            read_fixture(&path, false).unwrap(),
+            PackageInfo::NotFound
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "npm")]
+    fn oversized_responses_are_refused() {
+        let body = r#"{"versions": {"26.2.0": {}}}"#;
+        let read = |limit| read_packument(Limited::new(body.as_bytes(), limit), "");
+        assert!(read(body.len() as u64).is_ok());
+        let err = read(10).unwrap_err().to_string();
+        assert!(err.contains("max-response-mb"), "{}", err);
+    }
+
+    #[test]
+    fn parse_skips_version_manifests() {
+        let (versions, latest, _) = parse_packument(
+            r#"{"dist-tags": null, "time": null,
+                "versions": {"26.2.0": {"dist": {"tarball": "x"}, "scripts": [1, {"a": null}]}, "26.2.1": {}}}"#,
+            "",
+        )
+        .unwrap();
+        assert_eq!(versions, vec!["26.2.0", "26.2.1"]);
+        assert_eq!(latest, "26.2.1");
+    }
+
+    #[test]
+    fn parse_reports_html_and_garbage() {
+        let err = parse_packument("<html><body>Sign in</body></html>", "text/html")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("HTML"));
+        assert!(err.contains("Sign in"));
+        let err = format!(
+            "{:#}",
+            parse_packument("not json", "application/json").unwrap_err()
+        );
+        assert!(err.contains("not json"));
+        assert!(parse_packument("[]", "application/json").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::cli::ReleaseManifestArgs;
+use crate::config::Config;
+use crate::git;
+use crate::target::write_atomic;
+use crate::version::load_targets;
+
+/// What was released, for downstream systems to verify against
+#[derive(Debug, Serialize)]
+struct ReleaseManifest {
+    package: String,
+    version: String,
+    git_sha: String,
+    /// Tag pointing at the released commit, if it has been tagged yet
+    tag: Option<String>,
+    built_at: String,
+    tool: String,
+    files: Vec<FileHash>,
+}
+
+#[derive(Debug, Serialize)]
+struct FileHash {
+    path: PathBuf,
+    sha256: String,
+}
+
+pub fn run(args: ReleaseManifestArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let primary = args.primary.as_deref().or(config.primary_target());
+    let targets = load_targets(&args.target, primary, &config, None)?;
+    let (primary_path, primary_target) = &targets[0];
+    let project_dir = primary_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    let mut files = Vec::new();
+    for (path, _) in &targets {
+        let content =
+            std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        files.push(FileHash {
+            path: path.clone(),
+            sha256: sha256_hex(&content),
+        });
+    }
+
+    let version = primary_target.version.clone();
+    let tags = git::tags_at_head(project_dir)?;
+    let manifest = ReleaseManifest {
+        package: primary_target.package_name.clone(),
+        tag: pick_tag(&tags, &version),
+        version,
+        git_sha: git::head_sha(project_dir)?,
+        built_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        tool: format!("oneup {}", env!("CARGO_PKG_VERSION")),
+        files,
+    };
+
+    let json = serde_json::to_string_pretty(&manifest)? + "\n";
+    write_atomic(&args.out, json.as_bytes())?;
+    if args.verbose {
+        eprintln!("[manifest] wrote {}", args.out.display());
+        for file in &manifest.files {
+            eprintln!("[manifest] {} {}", file.sha256, file.path.display());
+        }
+    }
+
+    println!("{}", manifest.version);
+    Ok(())
+}
+
+/// The tag at HEAD naming this version (`v26.2.5` or `26.2.5`).
+pub fn pick_tag(tags: &[String], version: &str) -> Option<String> {
+    tags.iter()
+        .find(|t| t.strip_prefix('v').unwrap_or(t) == version)
+        .cloned()
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_tag_for_version() {
+        let tags = vec!["nightly".to_string(), "v26.2.5".to_string()];
+        assert_eq!(pick_tag(&tags, "26.2.5").as_deref(), Some("v26.2.5"));
+        assert_eq!(pick_tag(&tags, "26.2.6"), None);
+        assert_eq!(pick_tag(&[], "26.2.6"), None);
+    }
+
+    #[test]
+    fn sha256_hex_known_value() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
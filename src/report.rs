@@ -0,0 +1,656 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Months, NaiveDate, TimeDelta, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::cli::{ReportArgs, ReportFormat};
+use crate::config::Config;
+use crate::git;
+use crate::redact::redact;
+use crate::registry::PackageInfo;
+use crate::target::TargetFile;
+use crate::timing::Timings;
+use crate::version::query_registry;
+use crate::workspace;
+
+/// One release in the period: a published version, a tag, or both
+#[derive(Debug, Clone, PartialEq)]
+struct Release {
+    package: String,
+    version: String,
+    /// Publish time, or the tag's creation time for unpublished releases
+    date: DateTime<Utc>,
+    published: bool,
+    tag: Option<String>,
+    /// From the first commit after the package's previous tag to the release
+    lead_time: Option<TimeDelta>,
+}
+
+/// A tag read as a release of `package`
+struct TaggedRelease {
+    package: String,
+    version: String,
+    tag: String,
+    date: DateTime<Utc>,
+    lead_time: Option<TimeDelta>,
+}
+
+/// A titled table of the report
+struct Table {
+    title: String,
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+pub fn run(args: ReportArgs) -> Result<()> {
+    let since = period_start(&args.since)?;
+    let until = match &args.until {
+        Some(until) => period_end(until)?,
+        None => Utc::now(),
+    };
+    if until <= since {
+        bail!("--until must be after --since");
+    }
+    let format = match args.output_format {
+        Some(format) => format,
+        None => match args.out.as_ref().and_then(|p| p.extension()) {
+            Some(ext) if ext == "csv" => ReportFormat::Csv,
+            Some(ext) if ext == "html" || ext == "htm" => ReportFormat::Html,
+            _ => ReportFormat::Markdown,
+        },
+    };
+
+    // 1. Packages and what their registries have published
+    let config = Config::load(Path::new("."))?;
+    let manifests = if args.target.is_empty() {
+        workspace::packages(Path::new("."))?
+    } else {
+        args.target.clone()
+    };
+    if manifests.is_empty() {
+        bail!(
+            "no packages found (package.json or Cargo.toml with a version, or workspace members)"
+        );
+    }
+    let mut packages = Vec::new();
+    for path in &manifests {
+        let name = config.target(path).and_then(|t| t.publish_name.as_deref());
+        let target = TargetFile::read(path, name)?;
+        let info = match query_registry(
+            path,
+            &target,
+            args.registry.as_deref(),
+            args.registry_type,
+            &config,
+            args.verbose,
+            &mut Timings::new(false),
+        ) {
+            Ok(info) => info,
+            Err(err) => {
+                eprintln!(
+                    "warning: {}: registry unavailable, using tags only: {}",
+                    target.package_name,
+                    redact(&format!("{:#}", err))
+                );
+                PackageInfo::NotFound
+            }
+        };
+        packages.push((target.package_name, info));
+    }
+
+    // 2. Tags, attributed to packages, with lead times from git history
+    let dir = Path::new(".");
+    let tagged = match git::tags(dir) {
+        Ok(tags) => tagged_releases(dir, &tags, &packages),
+        Err(err) => {
+            eprintln!(
+                "warning: git unavailable, using registry data only: {}",
+                redact(&format!("{:#}", err))
+            );
+            Vec::new()
+        }
+    };
+
+    let releases = releases(&packages, tagged, since, until);
+    if args.verbose {
+        eprintln!(
+            "[report] {} release(s) from {} to {}",
+            releases.len(),
+            since.format("%Y-%m-%d"),
+            until.format("%Y-%m-%d")
+        );
+    }
+
+    let title = format!(
+        "Releases {} to {}",
+        since.format("%Y-%m-%d"),
+        (until - TimeDelta::seconds(1)).format("%Y-%m-%d")
+    );
+    let document = match format {
+        ReportFormat::Markdown => markdown(&title, &tables(&releases)),
+        ReportFormat::Html => html(&title, &tables(&releases)),
+        ReportFormat::Csv => csv(&releases),
+    };
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, document)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!("[report] wrote {}", path.display());
+        }
+        None => print!("{}", document),
+    }
+    Ok(())
+}
+
+/// Start of `--since`: a month (2026-01) or a day (2026-01-15), in UTC.
+fn period_start(spec: &str) -> Result<DateTime<Utc>> {
+    let (date, _) = parse_period(spec)?;
+    Ok(date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+/// End of `--until`, inclusive: the end of that month or day.
+fn period_end(spec: &str) -> Result<DateTime<Utc>> {
+    let (date, month) = parse_period(spec)?;
+    let end = if month {
+        date.checked_add_months(Months::new(1))
+    } else {
+        date.succ_opt()
+    }
+    .context("date out of range")?;
+    Ok(end.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+/// The first day of the period and whether it is a whole month.
+fn parse_period(spec: &str) -> Result<(NaiveDate, bool)> {
+    if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        return Ok((date, false));
+    }
+    NaiveDate::parse_from_str(&format!("{spec}-01"), "%Y-%m-%d")
+        .map(|date| (date, true))
+        .with_context(|| format!("invalid period '{}': expected YYYY-MM or YYYY-MM-DD", spec))
+}
+
+/// Tags read as releases: `<package>@<version>`, or `v<version>` and
+/// `<version>` attributed to the package whose registry has that version
+/// (the first package when none does). Lead time runs from the first commit
+/// after the package's previous tag.
+fn tagged_releases(
+    dir: &Path,
+    tags: &[(String, i64)],
+    packages: &[(String, PackageInfo)],
+) -> Vec<TaggedRelease> {
+    let mut previous: BTreeMap<String, String> = BTreeMap::new();
+    let mut releases = Vec::new();
+    // Oldest first, so each tag's predecessor is known
+    for (tag, time) in tags {
+        let Some((package, version)) = attribute_tag(tag, packages) else {
+            continue;
+        };
+        let Some(date) = DateTime::from_timestamp(*time, 0) else {
+            continue;
+        };
+        let from = previous.insert(package.clone(), tag.clone());
+        let first_commit = git::oldest_commit_time(dir, from.as_deref(), tag)
+            .ok()
+            .flatten()
+            .and_then(|t| DateTime::from_timestamp(t, 0));
+        releases.push(TaggedRelease {
+            package,
+            version,
+            tag: tag.clone(),
+            date,
+            lead_time: first_commit.map(|first| date - first),
+        });
+    }
+    releases
+}
+
+/// The package and version a tag names, if it names one.
+fn attribute_tag(tag: &str, packages: &[(String, PackageInfo)]) -> Option<(String, String)> {
+    for (package, _) in packages {
+        if let Some(version) = tag.strip_prefix(&format!("{package}@")) {
+            return Some((package.clone(), version.to_string()));
+        }
+    }
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    if !version.starts_with(|c: char| c.is_ascii_digit()) || !version.contains('.') {
+        return None;
+    }
+    let package = packages
+        .iter()
+        .find(|(_, info)| match info {
+            PackageInfo::Found { versions, .. } => versions.iter().any(|v| v == version),
+            PackageInfo::NotFound => false,
+        })
+        .or(packages.first())?;
+    Some((package.0.clone(), version.to_string()))
+}
+
+/// Releases dated in [since, until), oldest first: registry publishes
+/// joined with their tags, and tags of versions the registry doesn't date.
+fn releases(
+    packages: &[(String, PackageInfo)],
+    tagged: Vec<TaggedRelease>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<Release> {
+    let mut releases: BTreeMap<(String, String), Release> = BTreeMap::new();
+    for tagged in tagged {
+        releases.insert(
+            (tagged.package.clone(), tagged.version.clone()),
+            Release {
+                package: tagged.package,
+                version: tagged.version,
+                date: tagged.date,
+                published: false,
+                tag: Some(tagged.tag),
+                lead_time: tagged.lead_time,
+            },
+        );
+    }
+    for (package, info) in packages {
+        let PackageInfo::Found { published, .. } = info else {
+            continue;
+        };
+        for (version, time) in published {
+            let Ok(date) = DateTime::parse_from_rfc3339(time) else {
+                continue;
+            };
+            let date = date.to_utc();
+            let release = releases
+                .entry((package.clone(), version.clone()))
+                .or_insert_with(|| Release {
+                    package: package.clone(),
+                    version: version.clone(),
+                    date,
+                    published: true,
+                    tag: None,
+                    lead_time: None,
+                });
+            // Tagged after publishing (or before): the release happened at publish
+            if let Some(lead_time) = release.lead_time {
+                release.lead_time = Some(lead_time + (date - release.date));
+            }
+            release.date = date;
+            release.published = true;
+        }
+    }
+
+    let mut releases: Vec<Release> = releases
+        .into_values()
+        .filter(|r| r.date >= since && r.date < until)
+        .map(|mut r| {
+            r.lead_time = r.lead_time.filter(|t| *t >= TimeDelta::zero());
+            r
+        })
+        .collect();
+    releases.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.package.cmp(&b.package)));
+    releases
+}
+
+/// Summary, per-package and per-month counts, and the release list.
+fn tables(releases: &[Release]) -> Vec<Table> {
+    let lead_times = |rs: &[&Release]| -> String {
+        median(rs.iter().filter_map(|r| r.lead_time).collect()).map_or("-".into(), duration)
+    };
+    let all: Vec<&Release> = releases.iter().collect();
+    let packages: BTreeSet<&str> = releases.iter().map(|r| r.package.as_str()).collect();
+
+    let summary = Table {
+        title: "Summary".to_string(),
+        header: vec!["Releases", "Packages", "Median lead time"],
+        rows: vec![vec![
+            releases.len().to_string(),
+            packages.len().to_string(),
+            lead_times(&all),
+        ]],
+    };
+
+    let mut by_package: BTreeMap<&str, Vec<&Release>> = BTreeMap::new();
+    let mut by_month: BTreeMap<String, Vec<&Release>> = BTreeMap::new();
+    for release in releases {
+        by_package
+            .entry(&release.package)
+            .or_default()
+            .push(release);
+        by_month
+            .entry(release.date.format("%Y-%m").to_string())
+            .or_default()
+            .push(release);
+    }
+    let per_package = Table {
+        title: "Per package".to_string(),
+        header: vec![
+            "Package",
+            "Releases",
+            "Median lead time",
+            "Latest",
+            "Last released",
+        ],
+        rows: by_package
+            .iter()
+            .map(|(package, rs)| {
+                let last = rs.last().expect("grouped releases are not empty");
+                vec![
+                    package.to_string(),
+                    rs.len().to_string(),
+                    lead_times(rs),
+                    last.version.clone(),
+                    last.date.format("%Y-%m-%d").to_string(),
+                ]
+            })
+            .collect(),
+    };
+    let per_month = Table {
+        title: "Per month".to_string(),
+        header: vec!["Month", "Releases", "Packages", "Median lead time"],
+        rows: by_month
+            .iter()
+            .map(|(month, rs)| {
+                let packages: BTreeSet<&str> = rs.iter().map(|r| r.package.as_str()).collect();
+                vec![
+                    month.clone(),
+                    rs.len().to_string(),
+                    packages.len().to_string(),
+                    lead_times(rs),
+                ]
+            })
+            .collect(),
+    };
+    let list = Table {
+        title: "Releases".to_string(),
+        header: vec!["Date", "Package", "Version", "Tag", "Lead time"],
+        rows: releases
+            .iter()
+            .map(|r| {
+                vec![
+                    r.date.format("%Y-%m-%d %H:%M").to_string(),
+                    r.package.clone(),
+                    r.version.clone(),
+                    r.tag.clone().unwrap_or_else(|| "-".into()),
+                    r.lead_time.map_or("-".into(), duration),
+                ]
+            })
+            .collect(),
+    };
+    vec![summary, per_package, per_month, list]
+}
+
+fn median(mut values: Vec<TimeDelta>) -> Option<TimeDelta> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+/// The two largest units: "3d 4h", "5h 12m", "12m".
+fn duration(d: TimeDelta) -> String {
+    let (days, hours, minutes) = (d.num_days(), d.num_hours() % 24, d.num_minutes() % 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+fn markdown(title: &str, tables: &[Table]) -> String {
+    let mut out = format!("# {title}\n");
+    for table in tables {
+        out.push_str(&format!("\n## {}\n\n", table.title));
+        out.push_str(&format!("| {} |\n", table.header.join(" | ")));
+        out.push_str(&format!("|{}\n", " --- |".repeat(table.header.len())));
+        for row in &table.rows {
+            let cells: Vec<String> = row.iter().map(|c| c.replace('|', "\\|")).collect();
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+    out
+}
+
+fn html(title: &str, tables: &[Table]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+         <style>body{{font-family:sans-serif}}table{{border-collapse:collapse;margin-bottom:2em}}\
+         th,td{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style>\n\
+         </head>\n<body>\n<h1>{0}</h1>\n",
+        escape_html(title)
+    );
+    for table in tables {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n<tr>",
+            escape_html(&table.title)
+        ));
+        for cell in &table.header {
+            out.push_str(&format!("<th>{}</th>", escape_html(cell)));
+        }
+        out.push_str("</tr>\n");
+        for row in &table.rows {
+            out.push_str("<tr>");
+            for cell in row {
+                out.push_str(&format!("<td>{}</td>", escape_html(cell)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// One line per release, for spreadsheets; lead time in hours.
+fn csv(releases: &[Release]) -> String {
+    let mut out = String::from("date,package,version,tag,published,lead_time_hours\n");
+    for r in releases {
+        let fields = [
+            r.date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            r.package.clone(),
+            r.version.clone(),
+            r.tag.clone().unwrap_or_default(),
+            r.published.to_string(),
+            r.lead_time
+                .map(|t| format!("{:.1}", t.num_minutes() as f64 / 60.0))
+                .unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| escape_csv(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    fn found(versions: &[(&str, &str)]) -> PackageInfo {
+        PackageInfo::Found {
+            versions: versions.iter().map(|(v, _)| v.to_string()).collect(),
+            latest: versions.last().unwrap().0.to_string(),
+            published: versions
+                .iter()
+                .map(|(v, t)| (v.to_string(), t.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn periods_are_months_or_days() {
+        assert_eq!(
+            period_start("2026-01").unwrap(),
+            utc("2026-01-01T00:00:00Z")
+        );
+        assert_eq!(period_end("2026-03").unwrap(), utc("2026-04-01T00:00:00Z"));
+        assert_eq!(period_end("2026-12").unwrap(), utc("2027-01-01T00:00:00Z"));
+        assert_eq!(
+            period_start("2026-01-15").unwrap(),
+            utc("2026-01-15T00:00:00Z")
+        );
+        assert_eq!(
+            period_end("2026-01-15").unwrap(),
+            utc("2026-01-16T00:00:00Z")
+        );
+        assert!(period_start("Q1").is_err());
+    }
+
+    #[test]
+    fn tags_are_attributed_to_packages() {
+        let packages = vec![
+            (
+                "@scope/core".to_string(),
+                found(&[("26.1.0", "2026-01-05T10:00:00Z")]),
+            ),
+            (
+                "@scope/cli".to_string(),
+                found(&[("26.1.1", "2026-01-06T10:00:00Z")]),
+            ),
+        ];
+        let attribute = |tag| attribute_tag(tag, &packages);
+        assert_eq!(
+            attribute("@scope/cli@26.1.3"),
+            Some(("@scope/cli".into(), "26.1.3".into()))
+        );
+        assert_eq!(
+            attribute("v26.1.1"),
+            Some(("@scope/cli".into(), "26.1.1".into()))
+        );
+        assert_eq!(
+            attribute("26.1.0"),
+            Some(("@scope/core".into(), "26.1.0".into()))
+        );
+        // Unknown versions go to the first (root) package
+        assert_eq!(
+            attribute("v26.2.0"),
+            Some(("@scope/core".into(), "26.2.0".into()))
+        );
+        assert_eq!(attribute("nightly"), None);
+        assert_eq!(attribute("v2"), None);
+    }
+
+    #[test]
+    fn registry_and_tags_are_joined() {
+        let packages = vec![(
+            "app".to_string(),
+            found(&[
+                ("25.12.0", "2025-12-30T10:00:00Z"),
+                ("26.1.0", "2026-01-05T12:00:00Z"),
+                ("26.2.0", "2026-02-10T09:00:00Z"),
+            ]),
+        )];
+        let tagged = vec![
+            TaggedRelease {
+                package: "app".into(),
+                version: "26.1.0".into(),
+                tag: "v26.1.0".into(),
+                // Tagged an hour after publishing, two days after the first commit
+                date: utc("2026-01-05T13:00:00Z"),
+                lead_time: Some(TimeDelta::hours(49)),
+            },
+            TaggedRelease {
+                package: "app".into(),
+                version: "26.1.1".into(),
+                tag: "v26.1.1".into(),
+                date: utc("2026-01-20T08:00:00Z"),
+                lead_time: Some(TimeDelta::hours(5)),
+            },
+        ];
+        let releases = releases(
+            &packages,
+            tagged,
+            utc("2026-01-01T00:00:00Z"),
+            utc("2026-02-01T00:00:00Z"),
+        );
+        assert_eq!(
+            releases,
+            vec![
+                Release {
+                    package: "app".into(),
+                    version: "26.1.0".into(),
+                    date: utc("2026-01-05T12:00:00Z"),
+                    published: true,
+                    tag: Some("v26.1.0".into()),
+                    lead_time: Some(TimeDelta::hours(48)),
+                },
+                Release {
+                    package: "app".into(),
+                    version: "26.1.1".into(),
+                    date: utc("2026-01-20T08:00:00Z"),
+                    published: false,
+                    tag: Some("v26.1.1".into()),
+                    lead_time: Some(TimeDelta::hours(5)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_count_per_package_and_month() {
+        let release = |package: &str, version: &str, date: &str, hours| Release {
+            package: package.into(),
+            version: version.into(),
+            date: utc(date),
+            published: true,
+            tag: None,
+            lead_time: Some(TimeDelta::hours(hours)),
+        };
+        let releases = vec![
+            release("app", "26.1.0", "2026-01-05T12:00:00Z", 2),
+            release("lib", "26.1.0", "2026-01-07T12:00:00Z", 30),
+            release("app", "26.2.0", "2026-02-03T12:00:00Z", 4),
+        ];
+        let tables = tables(&releases);
+        assert_eq!(tables[0].rows, vec![vec!["3", "2", "4h 0m"]]);
+        assert_eq!(
+            tables[1].rows,
+            vec![
+                vec!["app", "2", "3h 0m", "26.2.0", "2026-02-03"],
+                vec!["lib", "1", "1d 6h", "26.1.0", "2026-01-07"],
+            ]
+        );
+        assert_eq!(
+            tables[2].rows,
+            vec![
+                vec!["2026-01", "2", "2", "16h 0m"],
+                vec!["2026-02", "1", "1", "4h 0m"]
+            ]
+        );
+
+        let md = markdown("Releases", &tables[..1]);
+        assert_eq!(
+            md,
+            "# Releases\n\n## Summary\n\n| Releases | Packages | Median lead time |\n| --- | --- | --- |\n| 3 | 2 | 4h 0m |\n"
+        );
+        assert!(html("<Q1>", &tables).contains("<h1>&lt;Q1&gt;</h1>"));
+        assert_eq!(
+            csv(&releases[..1]),
+            "date,package,version,tag,published,lead_time_hours\n2026-01-05T12:00:00Z,app,26.1.0,,true,2.0\n"
+        );
+        assert_eq!(escape_csv("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+}
@@ -0,0 +1,343 @@
+use anyhow::{Result, bail};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+use crate::format::VersionFormat;
+
+/// An entry of the `skip` list
+#[derive(Debug, Clone, PartialEq)]
+pub enum Skip {
+    /// Exact version string, e.g. "26.2.3"
+    Version(String),
+    /// MICRO value skipped in every period, e.g. 13
+    Micro(u64),
+}
+
+impl Skip {
+    /// Whether a candidate version (with its MICRO value, if any) is skipped.
+    pub fn matches(&self, version: &str, micro: Option<u64>) -> bool {
+        match self {
+            Skip::Version(v) => v == version,
+            Skip::Micro(m) => micro == Some(*m),
+        }
+    }
+}
+
+/// The next version for today's period, decided from the published
+/// versions alone
+#[derive(Debug, PartialEq)]
+pub struct Decision {
+    pub version: String,
+    /// Highest MICRO published for today's prefix (formats with MICRO)
+    pub max_micro: Option<u64>,
+    /// Candidates passed over because they are in the skip list
+    pub skipped: Vec<String>,
+    /// Today's version is already published (formats without MICRO), so
+    /// the bump is a no-op
+    pub exists: bool,
+}
+
+/// With MICRO: highest MICRO published for today's prefix, plus one (0 for
+/// a new period). Without MICRO: today's version, whether or not it is
+/// already published. Skipped versions are hopped over by advancing MICRO.
+pub fn decide(fmt: &VersionFormat, versions: &[String], skip: &[Skip]) -> Result<Decision> {
+    let max_micro = if fmt.has_micro() {
+        max_micro_today(fmt, versions)
+    } else {
+        None
+    };
+    let (version, skipped) = next_allowed(fmt, max_micro.map_or(0, |m| m + 1), skip)?;
+    let exists = !fmt.has_micro()
+        && versions.iter().any(|v| {
+            fmt.extract_values(v)
+                .is_some_and(|values| fmt.matches_today(&values))
+        });
+    Ok(Decision {
+        version,
+        max_micro,
+        skipped,
+        exists,
+    })
+}
+
+/// Build today's version starting at `micro`, advancing MICRO past any
+/// skipped version; also returns the versions passed over. Formats without
+/// MICRO cannot hop and fail instead.
+pub fn next_allowed(
+    fmt: &VersionFormat,
+    mut micro: u64,
+    skip: &[Skip],
+) -> Result<(String, Vec<String>)> {
+    let mut skipped = Vec::new();
+    loop {
+        let version = fmt.build_version(micro);
+        let micro_value = fmt.has_micro().then_some(micro);
+
+        if !skip.iter().any(|s| s.matches(&version, micro_value)) {
+            return Ok((version, skipped));
+        }
+
+        if !fmt.has_micro() {
+            bail!(
+                "version {} is in the skip list and the format has no MICRO to advance past it",
+                version
+            );
+        }
+
+        skipped.push(version);
+        micro += 1;
+    }
+}
+
+/// Order two versions by their numeric dot-separated parts.
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |s: &str| -> Vec<u64> { s.split('.').filter_map(|p| p.parse().ok()).collect() };
+    parse(a).cmp(&parse(b))
+}
+
+/// Whether `version` is SemVer 2: exactly three integers without leading
+/// zeros, optionally followed by -prerelease or +build.
+pub fn is_semver(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|p| {
+            !p.is_empty()
+                && p.chars().all(|c| c.is_ascii_digit())
+                && (p.len() == 1 || !p.starts_with('0'))
+        })
+}
+
+/// SemVer precedence of two versions: numeric release components, then a
+/// prerelease below its release (identifiers compared numerically when
+/// both are numbers); build metadata is ignored. None when either release
+/// is not dot-separated numbers.
+pub fn semver_cmp(a: &str, b: &str) -> Option<Ordering> {
+    fn parse(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+        let version = version.split('+').next().unwrap_or(version);
+        let (release, pre) = match version.split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (version, None),
+        };
+        let numbers = release
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some((numbers, pre))
+    }
+    let (a_release, a_pre) = parse(a)?;
+    let (b_release, b_pre) = parse(b)?;
+    let ordering = a_release
+        .cmp(&b_release)
+        .then_with(|| match (a_pre, b_pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => {
+                let mut a = a.split('.');
+                let mut b = b.split('.');
+                loop {
+                    let ordering = match (a.next(), b.next()) {
+                        (None, None) => return Ordering::Equal,
+                        (None, Some(_)) => return Ordering::Less,
+                        (Some(_), None) => return Ordering::Greater,
+                        (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(a), Ok(b)) => a.cmp(&b),
+                            // Numeric identifiers sort below alphanumeric ones
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => a.cmp(b),
+                        },
+                    };
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+            }
+        });
+    Some(ordering)
+}
+
+/// Published releases that `version` does not sort above. npm resolves
+/// ranges and `latest` to the highest matching version, so a release below
+/// any of these would never be installed (typically after a format change,
+/// e.g. YYYY.MM.MICRO to YY.MM.MICRO). Prereleases are left out: ranges
+/// skip them. Versions that are not numeric are ignored.
+pub fn not_below<'a>(version: &str, versions: &'a [String]) -> Vec<&'a str> {
+    versions
+        .iter()
+        .map(String::as_str)
+        .filter(|v| !v.split('+').next().unwrap_or(v).contains('-'))
+        .filter(|v| semver_cmp(version, v).is_some_and(Ordering::is_le))
+        .collect()
+}
+
+/// Highest MICRO among published versions matching today's date prefix.
+pub fn max_micro_today(fmt: &VersionFormat, versions: &[String]) -> Option<u64> {
+    today_micros(fmt, versions).into_iter().max()
+}
+
+/// MICRO values of the published versions in today's period. A list sorted
+/// oldest first (MICRO is always the last component, so periods are
+/// contiguous) is read from the newest end and stops at the first version of
+/// an earlier period; any other list is scanned in full (in parallel with
+/// the `parallel` feature).
+fn today_micros(fmt: &VersionFormat, versions: &[String]) -> Vec<u64> {
+    let micro = |v: &String| {
+        fmt.extract_values(v)
+            .filter(|values| fmt.matches_today(values))
+            .and_then(|values| fmt.micro_value(&values))
+    };
+    let in_order = |pair: &[String]| compare_versions(&pair[0], &pair[1]).is_le();
+    #[cfg(feature = "parallel")]
+    let sorted = versions.par_windows(2).all(in_order);
+    #[cfg(not(feature = "parallel"))]
+    let sorted = versions.windows(2).all(in_order);
+    if sorted {
+        return versions
+            .iter()
+            .rev()
+            .take_while(|v| {
+                fmt.extract_values(v)
+                    .is_none_or(|values| !fmt.before_today(&values))
+            })
+            .filter_map(micro)
+            .collect();
+    }
+    #[cfg(feature = "parallel")]
+    return versions.par_iter().filter_map(micro).collect();
+    #[cfg(not(feature = "parallel"))]
+    return versions.iter().filter_map(micro).collect();
+}
+
+/// MICRO values below today's highest that were never published and are
+/// not skipped, e.g. left behind by a publish that failed halfway.
+pub fn micro_gaps(fmt: &VersionFormat, versions: &[String], skip: &[Skip]) -> Vec<u64> {
+    let published = today_micros(fmt, versions);
+    let Some(max) = published.iter().max() else {
+        return Vec::new();
+    };
+    (0..*max)
+        .filter(|m| !published.contains(m))
+        .filter(|m| {
+            !skip
+                .iter()
+                .any(|s| s.matches(&fmt.build_version(*m), Some(*m)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn micro_gaps_ignore_skipped_and_other_periods() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        let versions: Vec<String> = vec![
+            fmt.build_version(0),
+            fmt.build_version(2),
+            fmt.build_version(5),
+            "20.1.1".to_string(),
+        ];
+        assert_eq!(micro_gaps(&fmt, &versions, &[]), vec![1, 3, 4]);
+        let skip = [Skip::Micro(3), Skip::Version(fmt.build_version(4))];
+        assert_eq!(micro_gaps(&fmt, &versions, &skip), vec![1]);
+        assert!(micro_gaps(&fmt, &["20.1.3".to_string()], &[]).is_empty());
+    }
+
+    #[test]
+    fn today_micros_sorted_and_unsorted() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        let mut versions: Vec<String> = vec![
+            "20.1.1".to_string(),
+            "20.1.7".to_string(),
+            fmt.build_version(0),
+            format!("{}-beta.0", fmt.build_version(1)),
+            fmt.build_version(1),
+            fmt.build_version(2),
+            "99.1.0".to_string(),
+        ];
+        let mut micros = today_micros(&fmt, &versions);
+        micros.sort();
+        assert_eq!(micros, vec![0, 1, 2]);
+
+        versions.swap(0, 5);
+        let mut micros = today_micros(&fmt, &versions);
+        micros.sort();
+        assert_eq!(micros, vec![0, 1, 2]);
+        assert_eq!(max_micro_today(&fmt, &versions), Some(2));
+        assert!(today_micros(&fmt, &versions[..0]).is_empty());
+    }
+
+    #[test]
+    fn semver_validity() {
+        assert!(is_semver("26.10.4"));
+        assert!(is_semver("0.0.0-pr12.0"));
+        assert!(is_semver("2026.10.17-beta.1+a1b2c3d"));
+        assert!(!is_semver("2026.10.17.5"));
+        assert!(!is_semver("26.10"));
+        assert!(!is_semver("26.02.5"));
+        assert!(!is_semver("v26.10.4"));
+    }
+
+    #[test]
+    fn semver_precedence() {
+        assert_eq!(semver_cmp("26.10.4", "26.10.3"), Some(Ordering::Greater));
+        assert_eq!(semver_cmp("26.10.4", "2026.1.0"), Some(Ordering::Less));
+        assert_eq!(semver_cmp("26.10.4+abc", "26.10.4"), Some(Ordering::Equal));
+        assert_eq!(semver_cmp("26.10.4-rc.0", "26.10.4"), Some(Ordering::Less));
+        assert_eq!(
+            semver_cmp("26.10.4-beta.10", "26.10.4-beta.9"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            semver_cmp("26.10.4-beta", "26.10.4-beta.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            semver_cmp("26.10.4-rc.0", "26.10.4-beta.3"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(semver_cmp("1.0.0-1", "1.0.0-alpha"), Some(Ordering::Less));
+        assert_eq!(semver_cmp("v1.0.0", "1.0.0"), None);
+    }
+
+    #[test]
+    fn not_below_ignores_prereleases_and_junk() {
+        let versions: Vec<String> = ["26.10.3", "2026.9.1", "27.0.0-beta.0", "latest", "26.10.4"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(not_below("26.10.4", &versions), vec!["2026.9.1", "26.10.4"]);
+        assert_eq!(not_below("2026.10.0", &versions), Vec::<&str>::new());
+        assert_eq!(
+            not_below("26.10.4-beta.0", &versions[..1]),
+            Vec::<&str>::new()
+        );
+        assert_eq!(not_below("26.10.3-beta.0", &versions[..1]), vec!["26.10.3"]);
+    }
+
+    #[test]
+    fn decide_next_micro_and_skips() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        let versions = vec![fmt.build_version(0), fmt.build_version(1)];
+        let decision = decide(&fmt, &versions, &[Skip::Micro(2)]).unwrap();
+        assert_eq!(decision.version, fmt.build_version(3));
+        assert_eq!(decision.max_micro, Some(1));
+        assert_eq!(decision.skipped, vec![fmt.build_version(2)]);
+        assert!(!decision.exists);
+        assert_eq!(
+            decide(&fmt, &[], &[]).unwrap().version,
+            fmt.build_version(0)
+        );
+
+        let monthly = VersionFormat::parse("YY.MM").unwrap();
+        let today = monthly.build_version(0);
+        let decision = decide(&monthly, std::slice::from_ref(&today), &[]).unwrap();
+        assert_eq!(decision.version, today);
+        assert!(decision.exists);
+        assert!(decide(&monthly, &[], &[Skip::Version(today)]).is_err());
+    }
+}
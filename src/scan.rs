@@ -0,0 +1,33 @@
+//! Text scanning shared by the targets oneup edits in place.
+
+/// Byte offsets of every `word` in `content` that starts a name instead of
+/// ending a longer one: the character before it is neither alphanumeric nor
+/// one of `ident`, the other characters the file's names may contain.
+pub fn word_starts<'a>(
+    content: &'a str,
+    word: &'a str,
+    ident: &'a [char],
+) -> impl Iterator<Item = usize> + 'a {
+    content
+        .match_indices(word)
+        .map(|(start, _)| start)
+        .filter(move |&start| {
+            !content[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || ident.contains(&c))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_longer_names() {
+        let content = "version = 1\nmeta.version = 2\nmy_version = 3\n(version=4)";
+        let starts: Vec<usize> = word_starts(content, "version", &['_', '.']).collect();
+        assert_eq!(starts, vec![0, 45]);
+        assert_eq!(word_starts(content, "version", &[]).count(), 4);
+    }
+}
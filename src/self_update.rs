@@ -0,0 +1,298 @@
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::cli::SelfUpdateArgs;
+use crate::http;
+use crate::release_manifest::sha256_hex;
+use crate::resolve::compare_versions;
+
+const REPO: &str = "circlesac/oneup";
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+pub fn run(args: SelfUpdateArgs) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let exe = std::env::current_exe().context("failed to locate the running executable")?;
+
+    if let Some(manager) = package_manager(&exe)
+        && !args.force
+    {
+        bail!(
+            "oneup at {} is managed by {}; update it with {} (or pass --force)",
+            exe.display(),
+            manager.0,
+            manager.1
+        );
+    }
+
+    let (artifact, ext) = platform_artifact()?;
+    let http = http::client();
+
+    // 1. Find the latest release
+    let url = format!("https://api.github.com/repos/{REPO}/releases/latest");
+    if args.verbose {
+        eprintln!("[self-update] GET {}", url);
+    }
+    let mut req = http
+        .get(&url)
+        .header("Accept", "application/vnd.github+json");
+    // An empty token would get a 401 instead of the anonymous answer
+    if let Ok(token) = std::env::var("GITHUB_TOKEN")
+        && !token.is_empty()
+    {
+        req = req.header("Authorization", format!("Bearer {token}"));
+    }
+    let resp = req.send().context("failed to query GitHub releases")?;
+    if !resp.status().is_success() {
+        bail!("failed to query GitHub releases: HTTP {}", resp.status());
+    }
+    let release: serde_json::Value = resp.json().context("failed to parse GitHub release")?;
+
+    let tag = release
+        .get("tag_name")
+        .and_then(|v| v.as_str())
+        .context("GitHub release is missing tag_name")?;
+    let latest = tag.trim_start_matches('v');
+
+    if args.verbose {
+        eprintln!("[self-update] current: {}", current);
+        eprintln!("[self-update] latest: {}", latest);
+    }
+
+    if compare_versions(latest, current) != std::cmp::Ordering::Greater {
+        eprintln!("oneup {} is up to date", current);
+        return Ok(());
+    }
+
+    if args.check {
+        eprintln!("oneup {} is available (current: {})", latest, current);
+        println!("{}", latest);
+        return Ok(());
+    }
+
+    // 2. Download the archive and the checksum list
+    let archive_name = format!("{artifact}{ext}");
+    let archive = download_asset(&http, &release, &archive_name, args.verbose)?;
+    let checksums = download_asset(&http, &release, CHECKSUMS_ASSET, args.verbose)?;
+
+    // 3. Verify the archive checksum
+    let checksums = String::from_utf8_lossy(&checksums);
+    let expected = find_checksum(&checksums, &archive_name)
+        .with_context(|| format!("no checksum for {} in {}", archive_name, CHECKSUMS_ASSET))?;
+    let actual = sha256_hex(&archive);
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!(
+            "checksum mismatch for {}: expected {}, got {}",
+            archive_name,
+            expected,
+            actual
+        );
+    }
+    if args.verbose {
+        eprintln!("[self-update] sha256 ok: {}", actual);
+    }
+
+    // 4. Extract and replace the running executable
+    let work_dir = std::env::temp_dir().join(format!("oneup-self-update-{}", std::process::id()));
+    std::fs::create_dir_all(&work_dir)
+        .with_context(|| format!("failed to create {}", work_dir.display()))?;
+    let result = extract(&work_dir, &archive_name, &archive)
+        .and_then(|binary| replace_executable(&exe, &binary));
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result?;
+
+    eprintln!("updated oneup {} → {}", current, latest);
+    println!("{}", latest);
+
+    Ok(())
+}
+
+/// Detect installs owned by a package manager, which should update oneup themselves.
+fn package_manager(exe: &Path) -> Option<(&'static str, &'static str)> {
+    let path = exe.to_string_lossy();
+    if path.contains("node_modules") {
+        Some(("npm", "`npm install -g @circlesac/oneup`"))
+    } else if path.contains("/Cellar/") || path.contains("/homebrew/") {
+        Some(("Homebrew", "`brew upgrade oneup`"))
+    } else if path.contains(".cargo/bin") {
+        Some(("cargo", "`cargo install oneup`"))
+    } else {
+        None
+    }
+}
+
+/// Release artifact name and archive extension for the current platform.
+/// Mirrors the PLATFORMS table in bin/install.js.
+fn platform_artifact() -> Result<(&'static str, &'static str)> {
+    artifact_for(
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        cfg!(target_env = "musl"),
+    )
+}
+
+/// A static musl build updates to the musl artifact, never to the glibc one.
+fn artifact_for(os: &str, arch: &str, musl: bool) -> Result<(&'static str, &'static str)> {
+    let artifact = match (os, arch) {
+        ("macos", "x86_64") => ("oneup-x86_64-apple-darwin", ".tar.gz"),
+        ("macos", "aarch64") => ("oneup-aarch64-apple-darwin", ".tar.gz"),
+        ("linux", "x86_64") if musl => ("oneup-x86_64-unknown-linux-musl", ".tar.gz"),
+        ("linux", "x86_64") => ("oneup-x86_64-unknown-linux-gnu", ".tar.gz"),
+        ("linux", "aarch64") => ("oneup-aarch64-unknown-linux-gnu", ".tar.gz"),
+        ("windows", "x86_64") => ("oneup-x86_64-pc-windows-msvc", ".zip"),
+        (os, arch) => bail!("no prebuilt oneup binary for {}-{}", os, arch),
+    };
+    Ok(artifact)
+}
+
+fn download_asset(
+    http: &reqwest::blocking::Client,
+    release: &serde_json::Value,
+    name: &str,
+    verbose: bool,
+) -> Result<Vec<u8>> {
+    let url = release
+        .get("assets")
+        .and_then(|v| v.as_array())
+        .and_then(|assets| {
+            assets
+                .iter()
+                .find(|a| a.get("name").and_then(|n| n.as_str()) == Some(name))
+        })
+        .and_then(|a| a.get("browser_download_url"))
+        .and_then(|u| u.as_str())
+        .with_context(|| format!("release has no asset named {}", name))?;
+
+    if verbose {
+        eprintln!("[self-update] GET {}", url);
+    }
+
+    let resp = http
+        .get(url)
+        .send()
+        .with_context(|| format!("failed to download {}", name))?;
+    if !resp.status().is_success() {
+        bail!("failed to download {}: HTTP {}", name, resp.status());
+    }
+    Ok(resp
+        .bytes()
+        .with_context(|| format!("failed to download {}", name))?
+        .to_vec())
+}
+
+/// Find the checksum for `file` in `sha256sum`-style output ("<hex>  <name>").
+fn find_checksum<'a>(checksums: &'a str, file: &str) -> Option<&'a str> {
+    checksums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start().trim_start_matches('*');
+        (name == file).then_some(hash)
+    })
+}
+
+/// Unpack the archive with the same tools bin/install.js uses; returns the binary path.
+fn extract(dir: &Path, archive_name: &str, archive: &[u8]) -> Result<PathBuf> {
+    let archive_path = dir.join(archive_name);
+    std::fs::write(&archive_path, archive)
+        .with_context(|| format!("failed to write {}", archive_path.display()))?;
+
+    let status = if archive_name.ends_with(".zip") {
+        Command::new("powershell")
+            .arg("-Command")
+            .arg(format!(
+                "Expand-Archive -Force '{}' '{}'",
+                archive_path.display(),
+                dir.display()
+            ))
+            .status()
+    } else {
+        Command::new("tar")
+            .arg("xzf")
+            .arg(&archive_path)
+            .current_dir(dir)
+            .status()
+    }
+    .context("failed to run archive extraction")?;
+
+    if !status.success() {
+        bail!("failed to extract {}", archive_name);
+    }
+
+    let binary = dir.join(format!("oneup{}", std::env::consts::EXE_SUFFIX));
+    if !binary.exists() {
+        bail!("{} does not contain an oneup binary", archive_name);
+    }
+    Ok(binary)
+}
+
+/// Swap the running executable for `binary`, staging next to it so the rename is atomic.
+fn replace_executable(exe: &Path, binary: &Path) -> Result<()> {
+    let staged = exe.with_extension("new");
+    std::fs::copy(binary, &staged)
+        .with_context(|| format!("failed to write {}", staged.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Windows cannot overwrite a running executable, but it can rename it
+    if cfg!(windows) {
+        let old = exe.with_extension("old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(exe, &old)
+            .with_context(|| format!("failed to move {} aside", exe.display()))?;
+    }
+
+    std::fs::rename(&staged, exe).with_context(|| format!("failed to replace {}", exe.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_checksum_in_sha256sum_output() {
+        let sums = "abc123  oneup-x86_64-unknown-linux-gnu.tar.gz\n\
+                    def456 *oneup-x86_64-pc-windows-msvc.zip\n";
+        assert_eq!(
+            find_checksum(sums, "oneup-x86_64-unknown-linux-gnu.tar.gz"),
+            Some("abc123")
+        );
+        assert_eq!(
+            find_checksum(sums, "oneup-x86_64-pc-windows-msvc.zip"),
+            Some("def456")
+        );
+        assert_eq!(
+            find_checksum(sums, "oneup-aarch64-apple-darwin.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    fn musl_build_updates_to_musl_artifact() {
+        assert_eq!(
+            artifact_for("linux", "x86_64", true).unwrap(),
+            ("oneup-x86_64-unknown-linux-musl", ".tar.gz")
+        );
+        assert_eq!(
+            artifact_for("linux", "x86_64", false).unwrap(),
+            ("oneup-x86_64-unknown-linux-gnu", ".tar.gz")
+        );
+        assert!(artifact_for("freebsd", "x86_64", false).is_err());
+    }
+
+    #[test]
+    fn package_manager_detection() {
+        assert!(
+            package_manager(Path::new(
+                "/usr/lib/node_modules/@circlesac/oneup/bin/native/oneup"
+            ))
+            .is_some()
+        );
+        assert!(
+            package_manager(Path::new("/opt/homebrew/Cellar/oneup/26.2.0/bin/oneup")).is_some()
+        );
+        assert!(package_manager(Path::new("/usr/local/bin/oneup")).is_none());
+    }
+}
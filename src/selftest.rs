@@ -0,0 +1,227 @@
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::cli::SelftestArgs;
+use crate::format::VersionFormat;
+
+/// Package the registry stub serves; every other name is a 404
+const PACKAGE: &str = "oneup-selftest";
+
+/// MICRO values of today's period the stub reports as published
+const PUBLISHED_TODAY: u64 = 3;
+
+/// What every check needs: this binary, the stub's URL and the format the
+/// checks bump with
+struct Harness {
+    exe: PathBuf,
+    registry: String,
+    fmt: VersionFormat,
+    verbose: bool,
+}
+
+type Check = fn(&Harness, &Path) -> Result<()>;
+
+const CHECKS: &[(&str, Check)] = &[
+    ("next MICRO from the registry", next_micro),
+    ("first release of a new package", new_package),
+    ("dry run leaves files untouched", dry_run),
+    ("MICRO from the git commit count", git_count),
+];
+
+/// Run this oneup binary end to end against a local registry stub, in
+/// temporary git repositories, so an installation can be validated on
+/// runners without network access.
+pub fn run(args: SelftestArgs) -> Result<()> {
+    let fmt = VersionFormat::parse("YY.MM.MICRO")?;
+    let harness = Harness {
+        exe: std::env::current_exe().context("failed to locate the oneup binary")?,
+        registry: serve(packument(&fmt))?,
+        fmt,
+        verbose: args.verbose,
+    };
+    if args.verbose {
+        eprintln!("[selftest] binary: {}", harness.exe.display());
+        eprintln!("[selftest] registry stub: {}", harness.registry);
+    }
+
+    let mut failed = 0;
+    for (name, check) in CHECKS {
+        let dir = tempfile::tempdir().context("failed to create a temporary directory")?;
+        match check(&harness, dir.path()) {
+            Ok(()) => eprintln!("ok   {}", name),
+            Err(err) => {
+                failed += 1;
+                eprintln!("FAIL {}: {:#}", name, err);
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} self-test checks failed", failed, CHECKS.len());
+    }
+    println!("selftest passed ({} checks)", CHECKS.len());
+    Ok(())
+}
+
+fn next_micro(h: &Harness, dir: &Path) -> Result<()> {
+    write_package(dir, PACKAGE)?;
+    let version = h.oneup(dir, &["version", "--registry", &h.registry])?;
+    expect_version(&version, &h.fmt.build_version(PUBLISHED_TODAY))?;
+    expect_written(dir, &version)
+}
+
+fn new_package(h: &Harness, dir: &Path) -> Result<()> {
+    write_package(dir, "oneup-selftest-unpublished")?;
+    let version = h.oneup(dir, &["version", "--registry", &h.registry])?;
+    expect_version(&version, &h.fmt.build_version(0))?;
+    expect_written(dir, &version)
+}
+
+fn dry_run(h: &Harness, dir: &Path) -> Result<()> {
+    write_package(dir, PACKAGE)?;
+    let version = h.oneup(dir, &["version", "--dry-run", "--registry", &h.registry])?;
+    expect_version(&version, &h.fmt.build_version(PUBLISHED_TODAY))?;
+    expect_written(dir, "0.0.0")
+}
+
+fn git_count(h: &Harness, dir: &Path) -> Result<()> {
+    write_package(dir, PACKAGE)?;
+    git(dir, &["init", "-q"])?;
+    git(dir, &["add", "."])?;
+    git(dir, &["commit", "-q", "-m", "first"])?;
+    git(dir, &["commit", "-q", "--allow-empty", "-m", "second"])?;
+    let version = h.oneup(dir, &["version", "--micro-source", "git-count"])?;
+    expect_version(&version, &h.fmt.build_version(2))?;
+    expect_written(dir, &version)
+}
+
+impl Harness {
+    /// Run oneup in `dir` isolated from the user's .npmrc and oneup
+    /// settings; returns the last line of stdout (the version).
+    fn oneup(&self, dir: &Path, args: &[&str]) -> Result<String> {
+        if self.verbose {
+            eprintln!("[selftest] oneup {}", args.join(" "));
+        }
+        let output = isolated(Command::new(&self.exe), dir)
+            .args(args)
+            .output()
+            .with_context(|| format!("failed to run {}", self.exe.display()))?;
+        if !output.status.success() {
+            bail!(
+                "oneup {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().last().unwrap_or_default().to_string())
+    }
+}
+
+/// `command` running in `dir` with only PATH from the environment, HOME
+/// pointing at `dir`, and a git identity for commits.
+fn isolated(mut command: Command, dir: &Path) -> Command {
+    command
+        .current_dir(dir)
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .env("HOME", dir)
+        .env("GIT_AUTHOR_NAME", "oneup selftest")
+        .env("GIT_AUTHOR_EMAIL", "selftest@example.com")
+        .env("GIT_COMMITTER_NAME", "oneup selftest")
+        .env("GIT_COMMITTER_EMAIL", "selftest@example.com")
+        .env("GIT_CONFIG_NOSYSTEM", "1");
+    command
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = isolated(Command::new("git"), dir)
+        .args(["-c", "commit.gpgsign=false"])
+        .args(args)
+        .status()
+        .context("failed to run git (is it installed?)")?;
+    ensure!(status.success(), "git {} failed", args.join(" "));
+    Ok(())
+}
+
+fn write_package(dir: &Path, name: &str) -> Result<()> {
+    let json = format!(
+        "{{\n  \"name\": \"{}\",\n  \"version\": \"0.0.0\"\n}}\n",
+        name
+    );
+    std::fs::write(dir.join("package.json"), json).context("failed to write package.json")
+}
+
+fn expect_version(got: &str, want: &str) -> Result<()> {
+    ensure!(got == want, "expected version {}, got '{}'", want, got);
+    Ok(())
+}
+
+fn expect_written(dir: &Path, version: &str) -> Result<()> {
+    let json = std::fs::read_to_string(dir.join("package.json"))?;
+    ensure!(
+        json.contains(&format!("\"version\": \"{}\"", version)),
+        "package.json does not hold version {}",
+        version
+    );
+    Ok(())
+}
+
+/// A packument for PACKAGE: an old release plus MICRO 0.. of today's period.
+fn packument(fmt: &VersionFormat) -> String {
+    let mut versions = vec!["20.1.0".to_string()];
+    versions.extend((0..PUBLISHED_TODAY).map(|micro| fmt.build_version(micro)));
+    let latest = versions.last().cloned().unwrap_or_default();
+    serde_json::json!({
+        "name": PACKAGE,
+        "dist-tags": { "latest": latest },
+        "versions": versions
+            .iter()
+            .map(|v| (v.clone(), serde_json::json!({ "version": v })))
+            .collect::<serde_json::Map<_, _>>(),
+    })
+    .to_string()
+}
+
+/// Serve `packument` for PACKAGE (404 for anything else) on a local port
+/// until the process exits; returns the registry URL.
+fn serve(packument: String) -> Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").context("failed to start the registry stub")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = respond(stream, &packument);
+        }
+    });
+    Ok(url)
+}
+
+fn respond(mut stream: TcpStream, packument: &str) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Drain the headers
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" {
+        line.clear();
+    }
+    let path = request
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| anyhow!("malformed request"))?;
+    let (status, body) = if path.trim_start_matches('/') == PACKAGE {
+        ("200 OK", packument)
+    } else {
+        ("404 Not Found", r#"{"error":"Not found"}"#)
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use anyhow::{Result, bail};
+use serde::Serialize;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+use crate::cli::StatusArgs;
+use crate::config::Config;
+use crate::format::VersionFormat;
+use crate::git;
+use crate::redact::redact;
+use crate::registry::PackageInfo;
+use crate::target::TargetFile;
+use crate::timing::Timings;
+use crate::version::{query_registry, registry_route};
+use crate::workspace;
+
+/// One row of the dashboard
+#[derive(Debug, Serialize)]
+struct PackageStatus {
+    path: String,
+    package: String,
+    version: String,
+    /// Registry latest; None when unpublished or the lookup failed
+    latest: Option<String>,
+    /// Whether a version for today's date period is published
+    published_today: Option<bool>,
+    /// Commits touching the package since the last tag
+    pending_commits: Option<u64>,
+    /// Registry the package is looked up in, and the setting that chose it
+    registry: Option<String>,
+    registry_source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub fn run(args: StatusArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let fmt = VersionFormat::parse(&args.format)?;
+
+    let manifests = if args.target.is_empty() {
+        workspace::packages(Path::new("."))?
+    } else {
+        args.target.clone()
+    };
+    if manifests.is_empty() {
+        bail!(
+            "no packages found (package.json or Cargo.toml with a version, or workspace members)"
+        );
+    }
+
+    let dir = Path::new(".");
+    let last_tag = git::last_tag(dir);
+    match &last_tag {
+        Ok(Some(tag)) => eprintln!("[status] last tag: {}", tag),
+        Ok(None) => eprintln!("[status] no tags yet, pending counts all commits"),
+        Err(err) => eprintln!(
+            "[status] git unavailable: {}",
+            redact(&format!("{:#}", err))
+        ),
+    }
+
+    let mut rows = Vec::new();
+    for path in &manifests {
+        let name = config.target(path).and_then(|t| t.publish_name.as_deref());
+        let target = match TargetFile::read(path, name) {
+            Ok(target) => target,
+            Err(err) => {
+                let error = redact(&format!("{:#}", err));
+                eprintln!("[status] {}: {}", path.display(), error);
+                rows.push(PackageStatus {
+                    path: path.display().to_string(),
+                    package: String::new(),
+                    version: String::new(),
+                    latest: None,
+                    published_today: None,
+                    pending_commits: None,
+                    registry: None,
+                    registry_source: None,
+                    error: Some(error),
+                });
+                continue;
+            }
+        };
+
+        let package_dir = package_dir(path);
+        let pending_commits = match &last_tag {
+            Ok(tag) => git::commits_touching(dir, tag.as_deref(), &package_dir).ok(),
+            Err(_) => None,
+        };
+
+        // Each package is routed by its own scope, not the first package's
+        let route =
+            registry_route(path, &target, args.registry.as_deref(), args.registry_type).ok();
+        if args.verbose
+            && let Some((registry, source)) = &route
+        {
+            eprintln!(
+                "[status] {} → {} ({})",
+                target.package_name,
+                redact(registry),
+                source
+            );
+        }
+        let (registry, registry_source) = route.unzip();
+
+        let mut row = PackageStatus {
+            path: path.display().to_string(),
+            package: target.package_name.clone(),
+            version: target.version.clone(),
+            latest: None,
+            published_today: None,
+            pending_commits,
+            registry,
+            registry_source,
+            error: None,
+        };
+        match query_registry(
+            path,
+            &target,
+            args.registry.as_deref(),
+            args.registry_type,
+            &config,
+            args.verbose,
+            &mut Timings::new(false),
+        ) {
+            Ok(info) => {
+                row.published_today = Some(published_today(&info, &fmt));
+                if let PackageInfo::Found { latest, .. } = info {
+                    row.latest = Some(latest);
+                }
+            }
+            Err(err) => {
+                let error = redact(&format!("{:#}", err));
+                eprintln!("[status] {}: {}", path.display(), error);
+                row.error = Some(error);
+            }
+        }
+        rows.push(row);
+    }
+
+    if args.json {
+        let report = json!({
+            "last_tag": last_tag.ok().flatten(),
+            "packages": rows,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for line in table(&rows) {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Directory whose commits count as changes to the package at `manifest`.
+fn package_dir(manifest: &Path) -> PathBuf {
+    match manifest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Whether the registry has a version in today's date period.
+fn published_today(info: &PackageInfo, fmt: &VersionFormat) -> bool {
+    match info {
+        PackageInfo::NotFound => false,
+        PackageInfo::Found { versions, .. } => versions.iter().any(|v| {
+            fmt.extract_values(v)
+                .is_some_and(|values| fmt.matches_today(&values))
+        }),
+    }
+}
+
+/// Rows as aligned columns under a header; unknown values show as "-".
+fn table(rows: &[PackageStatus]) -> Vec<String> {
+    let header = ["PACKAGE", "VERSION", "LATEST", "TODAY", "PENDING", "PATH"];
+    let mut cells: Vec<[String; 6]> = vec![header.map(String::from)];
+    for row in rows {
+        let dash = || "-".to_string();
+        cells.push([
+            if row.package.is_empty() {
+                dash()
+            } else {
+                row.package.clone()
+            },
+            if row.version.is_empty() {
+                dash()
+            } else {
+                row.version.clone()
+            },
+            row.latest.clone().unwrap_or_else(dash),
+            row.published_today
+                .map_or_else(dash, |p| if p { "yes" } else { "no" }.to_string()),
+            row.pending_commits.map_or_else(dash, |n| n.to_string()),
+            row.path.clone(),
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|c| c[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    cells
+        .iter()
+        .map(|c| {
+            let line: Vec<String> = c
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            line.join("  ").trim_end().to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn today_is_detected_per_format() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        let today = fmt.build_version(3);
+        let info = PackageInfo::Found {
+            versions: vec!["25.1.0".to_string(), today.clone()],
+            latest: today,
+            published: Default::default(),
+        };
+        assert!(published_today(&info, &fmt));
+
+        let info = PackageInfo::Found {
+            versions: vec!["25.1.0".to_string()],
+            latest: "25.1.0".to_string(),
+            published: Default::default(),
+        };
+        assert!(!published_today(&info, &fmt));
+        assert!(!published_today(&PackageInfo::NotFound, &fmt));
+    }
+
+    #[test]
+    fn table_aligns_columns() {
+        let rows = vec![
+            PackageStatus {
+                path: "packages/core/package.json".to_string(),
+                package: "@scope/core".to_string(),
+                version: "26.2.3".to_string(),
+                latest: Some("26.2.3".to_string()),
+                published_today: Some(true),
+                pending_commits: Some(4),
+                registry: Some("https://npm.pkg.github.com".to_string()),
+                registry_source: Some("npm config @scope:registry".to_string()),
+                error: None,
+            },
+            PackageStatus {
+                path: "Cargo.toml".to_string(),
+                package: "app".to_string(),
+                version: "26.1.0".to_string(),
+                latest: None,
+                published_today: None,
+                pending_commits: None,
+                registry: None,
+                registry_source: None,
+                error: Some("offline".to_string()),
+            },
+        ];
+        assert_eq!(
+            table(&rows),
+            vec![
+                "PACKAGE      VERSION  LATEST  TODAY  PENDING  PATH",
+                "@scope/core  26.2.3   26.2.3  yes    4        packages/core/package.json",
+                "app          26.1.0   -       -      -        Cargo.toml",
+            ]
+        );
+        assert_eq!(package_dir(Path::new("Cargo.toml")), PathBuf::from("."));
+        assert_eq!(
+            package_dir(Path::new("packages/core/package.json")),
+            PathBuf::from("packages/core")
+        );
+    }
+}
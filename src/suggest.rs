@@ -0,0 +1,64 @@
+/// The candidate closest to `input` by edit distance, ignoring case, if it
+/// is close enough to be a plausible typo.
+pub fn closest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let input = input.to_lowercase();
+    let max = (input.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|c| (distance(&input, &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= max)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// `" (did you mean 'X'?)"` for the closest candidate, or an empty string.
+pub fn did_you_mean<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    closest(input, candidates)
+        .map(|c| format!(" (did you mean '{}'?)", c))
+        .unwrap_or_default()
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(current)
+            };
+            prev = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_counts_edits() {
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("yyy", "yyyy"), 1);
+    }
+
+    #[test]
+    fn closest_ignores_case_and_far_candidates() {
+        let keys = ["format", "timezone", "registry"];
+        assert_eq!(closest("timzone", keys), Some("timezone"));
+        assert_eq!(closest("FORMAT", keys), Some("format"));
+        assert_eq!(closest("something", keys), None);
+        assert_eq!(
+            did_you_mean("regsitry", keys),
+            " (did you mean 'registry'?)"
+        );
+        assert_eq!(did_you_mean("x", keys), "");
+    }
+}
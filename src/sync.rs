@@ -0,0 +1,151 @@
+use anyhow::{Result, anyhow};
+use std::path::{Path, PathBuf};
+
+use crate::backup;
+use crate::cli::SyncArgs;
+use crate::config::Config;
+use crate::error::{ErrorKind, ResultExt};
+use crate::target::{self, TargetFile};
+use crate::version::load_targets;
+
+pub fn run(args: SyncArgs) -> Result<()> {
+    let config = Config::load(Path::new("."))?;
+    let primary = args.primary.as_deref().or(config.primary_target());
+    let targets = load_targets(&args.target, primary, &config, None)?;
+
+    let canonical = match &args.version {
+        Some(v) => v.clone(),
+        None => targets[0].1.version.clone(),
+    };
+    if args.verbose {
+        eprintln!("[sync] canonical version: {}", canonical);
+    }
+
+    let drifted = drifted(&targets, &config, &canonical);
+    if drifted.is_empty() {
+        eprintln!("all targets at {}", canonical);
+    }
+    for (path, target) in &drifted {
+        eprintln!(
+            "{}: {} (expected {})",
+            path.display(),
+            target.version,
+            canonical
+        );
+    }
+
+    if args.fix && !drifted.is_empty() {
+        let rendered = repair(&drifted, &canonical)?;
+        let paths: Vec<&Path> = rendered.iter().map(|file| file.path).collect();
+        let backups = backup::project_dir(&targets[0].0);
+        if let Some(snapshot) = backup::snapshot(&backups, &paths, config.keep_backups)?
+            && args.verbose
+        {
+            eprintln!("[backup] {}", snapshot.display());
+        }
+        target::write_all(&rendered)?;
+        for (path, _) in &drifted {
+            eprintln!("updated {}", path.display());
+        }
+    }
+
+    println!("{}", canonical);
+
+    if !drifted.is_empty() && !args.fix {
+        return Err(anyhow!(
+            "{} of {} targets not at {}; run `oneup sync --fix` to update them",
+            drifted.len(),
+            targets.len(),
+            canonical
+        ))
+        .kind(ErrorKind::Check);
+    }
+
+    Ok(())
+}
+
+/// `drifted` rendered at `canonical`. A repair is not a release, so the
+/// release dates of CITATION.cff and codemeta.json stay as they are.
+fn repair<'a>(
+    drifted: &[&'a (PathBuf, TargetFile)],
+    canonical: &str,
+) -> Result<Vec<target::Rendered<'a>>> {
+    let writes: Vec<_> = drifted
+        .iter()
+        .map(|(path, target)| (path.as_path(), target, canonical))
+        .collect();
+    target::render_all(&writes, None)
+}
+
+/// Targets whose version differs from `canonical`. Targets with their own
+/// format in oneup.toml carry a different shape by design and are left out.
+fn drifted<'a>(
+    targets: &'a [(PathBuf, TargetFile)],
+    config: &Config,
+    canonical: &str,
+) -> Vec<&'a (PathBuf, TargetFile)> {
+    targets
+        .iter()
+        .filter(|(path, _)| config.target(path).is_none_or(|t| t.format.is_none()))
+        .filter(|(_, target)| target.version != canonical)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TargetConfig;
+
+    fn write_package(dir: &Path, name: &str, version: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(
+            &path,
+            format!(r#"{{"name": "pkg", "version": "{}"}}"#, version),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn drifted_lists_disagreeing_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write_package(dir.path(), "a.json", "26.2.3");
+        let b = write_package(dir.path(), "b.json", "26.2.1");
+        let c = write_package(dir.path(), "c.json", "2026.2.9");
+        let mut config = Config::default();
+        config.targets.push((
+            c.clone(),
+            TargetConfig {
+                format: Some("YYYY.MM.DD".to_string()),
+                ..Default::default()
+            },
+        ));
+
+        let targets = load_targets(&[a, b.clone(), c], None, &config, None).unwrap();
+        let result = drifted(&targets, &config, "26.2.3");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0, b);
+        assert!(drifted(&targets[..1], &config, "26.2.3").is_empty());
+    }
+
+    #[test]
+    fn repair_keeps_release_dates() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write_package(dir.path(), "a.json", "26.2.3");
+        let cff = dir.path().join("CITATION.cff");
+        std::fs::write(
+            &cff,
+            "title: a\nversion: 26.2.1\ndate-released: 2026-02-01\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let targets = load_targets(&[a, cff.clone()], None, &config, None).unwrap();
+        let drifted = drifted(&targets, &config, "26.2.3");
+        target::write_all(&repair(&drifted, "26.2.3").unwrap()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&cff).unwrap(),
+            "title: a\nversion: 26.2.3\ndate-released: 2026-02-01\n"
+        );
+    }
+}
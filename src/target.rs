@@ -1,10 +1,64 @@
 use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
 use serde_json::Value;
 use std::path::Path;
 
+use crate::bazel;
+use crate::citation;
+use crate::docker;
+use crate::docs;
+use crate::gradle;
+use crate::helm;
+use crate::nix;
+use crate::resolve::is_semver;
+use crate::suggest;
+use crate::vscode;
+use crate::webext;
+use crate::xcode;
+
+/// Placeholder replaced by the version in generated source templates
+pub const VERSION_PLACEHOLDER: &str = "{version}";
+
 enum TargetFormat {
     Json(Value),
+    /// VS Code extension package.json (has engines.vscode)
+    VsCodeExtension(Value),
+    /// Browser extension manifest.json (has manifest_version)
+    WebExtension(Value),
     Toml(toml_edit::DocumentMut),
+    /// Source file regenerated from a template on every bump
+    Source(String),
+    /// Android build.gradle / build.gradle.kts (versionName + versionCode)
+    Gradle(String),
+    /// iOS/macOS Info.plist (CFBundleShortVersionString + CFBundleVersion)
+    Plist(String),
+    /// Xcode project.pbxproj (MARKETING_VERSION build settings)
+    Pbxproj(String),
+    /// Helm Chart.yaml (chart version, plus appVersion when coupled)
+    HelmChart {
+        content: String,
+        app_version: Option<String>,
+    },
+    /// flake.nix / default.nix (version = "..."; attributes)
+    Nix(String),
+    /// Bazel MODULE.bazel (module(version = "..."))
+    Bazel(String),
+    /// CITATION.cff (version + date-released)
+    Cff(String),
+    /// codemeta.json (version + dateModified)
+    CodeMeta(Value),
+    /// Dockerfile (org.opencontainers.image.version labels)
+    Dockerfile(String),
+    /// docker-compose / Kubernetes / kustomization file pinning an image tag
+    Image {
+        content: String,
+        image: String,
+    },
+    /// Documentation with versions at configured patterns (README, install docs)
+    Docs {
+        content: String,
+        patterns: Vec<String>,
+    },
 }
 
 pub struct TargetFile {
@@ -14,32 +68,298 @@ pub struct TargetFile {
 }
 
 impl TargetFile {
-    pub fn read(path: &Path) -> Result<Self> {
+    /// Read a target. `name` replaces the manifest's package name
+    /// (which then may be missing).
+    pub fn read(path: &Path, name: Option<&str>) -> Result<Self> {
+        let generated = generated_template(path).is_some();
+        if !generated && !path.exists() {
+            bail!(
+                "target file not found: {}{}",
+                path.display(),
+                missing_hint(path)
+            );
+        }
+        match path.file_name().and_then(|f| f.to_str()) {
+            Some("Chart.yaml") => return Self::read_helm(path, name),
+            Some("MODULE.bazel") => return Self::read_bazel(path, name),
+            Some("CITATION.cff" | "codemeta.json") => return Self::read_citation(path, name),
+            Some(f) if f == "Dockerfile" || f.starts_with("Dockerfile.") => {
+                return Self::read_dockerfile(path, name);
+            }
+            _ => {}
+        }
         match path.extension().and_then(|e| e.to_str()) {
-            Some("toml") => Self::read_toml(path),
-            _ => Self::read_json(path),
+            Some("toml") => Self::read_toml(path, name),
+            Some("gradle" | "kts") => Self::read_gradle(path, name),
+            Some("plist" | "pbxproj") => Self::read_xcode(path, name),
+            Some("nix") => Self::read_nix(path, name),
+            Some("dockerfile") => Self::read_dockerfile(path, name),
+            _ if generated => Self::read_source(path, name, None),
+            Some(ext) if default_template(ext).is_some() => bail!(
+                "{} is not a generated version file; name it version.{} (or set template for it in oneup.toml) to have oneup rewrite it",
+                path.display(),
+                ext
+            ),
+            _ => Self::read_json(path, name),
+        }
+    }
+
+    /// Read a generated source target (version.rs, version.ts, __version__.py, ...).
+    /// The file may not exist yet; `template` defaults to one for the file's
+    /// language when the file has a conventional name. Such targets carry no
+    /// package name of their own.
+    pub fn read_source(path: &Path, name: Option<&str>, template: Option<&str>) -> Result<Self> {
+        let template = match template {
+            Some(t) => t.to_string(),
+            None => generated_template(path)
+                .with_context(|| {
+                    format!(
+                        "no default template for {}; set template in oneup.toml",
+                        path.display()
+                    )
+                })?
+                .to_string(),
+        };
+        if !template.contains(VERSION_PLACEHOLDER) {
+            bail!(
+                "template for {} has no {} placeholder",
+                path.display(),
+                VERSION_PLACEHOLDER
+            );
+        }
+
+        let version = match std::fs::read_to_string(path) {
+            Ok(content) => version_in(&template, &content)
+                .unwrap_or("0.0.0")
+                .to_string(),
+            Err(_) => "0.0.0".to_string(),
+        };
+
+        Ok(Self {
+            package_name: name.unwrap_or_default().to_string(),
+            version,
+            format: TargetFormat::Source(template),
+        })
+    }
+
+    /// Read a docs target whose versions sit at `patterns` (`{version}` marks
+    /// the spot). Like generated sources, it carries no package name.
+    pub fn read_docs(path: &Path, name: Option<&str>, patterns: &[String]) -> Result<Self> {
+        Self::read_with(
+            path,
+            name,
+            "version",
+            |content| Some(docs::version(content, patterns).unwrap_or("0.0.0")),
+            |content| TargetFormat::Docs {
+                content,
+                patterns: patterns.to_vec(),
+            },
+        )
+    }
+
+    /// Read a compose, Kubernetes or kustomization file whose tag of `image`
+    /// follows the version.
+    pub fn read_image(path: &Path, name: Option<&str>, image: &str) -> Result<Self> {
+        Self::read_with(
+            path,
+            name,
+            &format!("tag of image {}", image),
+            |content| docker::image_tag(content, image),
+            |content| TargetFormat::Image {
+                content,
+                image: image.to_string(),
+            },
+        )
+    }
+
+    fn read_dockerfile(path: &Path, name: Option<&str>) -> Result<Self> {
+        Self::read_with(
+            path,
+            name,
+            "LABEL org.opencontainers.image.version",
+            docker::label_version,
+            TargetFormat::Dockerfile,
+        )
+    }
+
+    fn read_gradle(path: &Path, name: Option<&str>) -> Result<Self> {
+        Self::read_with(
+            path,
+            name,
+            "versionName",
+            gradle::version_name,
+            TargetFormat::Gradle,
+        )
+    }
+
+    fn read_helm(path: &Path, name: Option<&str>) -> Result<Self> {
+        Self::read_with(
+            path,
+            name,
+            "chart version",
+            helm::chart_version,
+            |content| TargetFormat::HelmChart {
+                content,
+                app_version: None,
+            },
+        )
+    }
+
+    fn read_nix(path: &Path, name: Option<&str>) -> Result<Self> {
+        Self::read_with(
+            path,
+            name,
+            "version = \"...\";",
+            |content| nix::version(content, name.unwrap_or_default()),
+            TargetFormat::Nix,
+        )
+    }
+
+    fn read_bazel(path: &Path, name: Option<&str>) -> Result<Self> {
+        Self::read_with(
+            path,
+            name,
+            "module version",
+            bazel::module_version,
+            TargetFormat::Bazel,
+        )
+    }
+
+    fn read_citation(path: &Path, name: Option<&str>) -> Result<Self> {
+        if path.extension().is_some_and(|e| e == "cff") {
+            return Self::read_with(
+                path,
+                name,
+                "version",
+                |content| Some(citation::cff_version(content).unwrap_or("0.0.0")),
+                TargetFormat::Cff,
+            );
         }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("target file not found: {}", path.display()))?;
+        let raw: Value = serde_json::from_str(&content)
+            .with_context(|| format!("failed to parse {}: invalid JSON", path.display()))?;
+        let version = raw
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("0.0.0")
+            .to_string();
+
+        Ok(Self {
+            package_name: name.unwrap_or_default().to_string(),
+            version,
+            format: TargetFormat::CodeMeta(raw),
+        })
+    }
+
+    fn read_xcode(path: &Path, name: Option<&str>) -> Result<Self> {
+        let is_plist = path.extension().is_some_and(|e| e == "plist");
+        Self::read_with(
+            path,
+            name,
+            "version",
+            |content| {
+                let version = if is_plist {
+                    xcode::plist_version(content)
+                } else {
+                    xcode::pbxproj_version(content)
+                }?;
+                // A $(MARKETING_VERSION) reference has no version of its own
+                Some(if version.starts_with("$(") {
+                    "0.0.0"
+                } else {
+                    version
+                })
+            },
+            if is_plist {
+                TargetFormat::Plist
+            } else {
+                TargetFormat::Pbxproj
+            },
+        )
     }
 
-    fn read_json(path: &Path) -> Result<Self> {
+    /// Read a text target whose version `extract` finds in its contents, and
+    /// wrap the contents with `format`. `missing` names what `extract` looks
+    /// for, in the error when it finds nothing.
+    fn read_with(
+        path: &Path,
+        name: Option<&str>,
+        missing: &str,
+        extract: impl Fn(&str) -> Option<&str>,
+        format: impl FnOnce(String) -> TargetFormat,
+    ) -> Result<Self> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("target file not found: {}", path.display()))?;
 
-        let raw: Value = serde_json::from_str(&content)
+        let version = extract(&content)
+            .with_context(|| format!("no {} found in {}", missing, path.display()))?
+            .to_string();
+
+        Ok(Self {
+            package_name: name.unwrap_or_default().to_string(),
+            version,
+            format: format(content),
+        })
+    }
+
+    /// Read a package.json or Cargo.toml document from stdin (`--target -`),
+    /// telling them apart by content.
+    pub fn read_stdin(name: Option<&str>) -> Result<Self> {
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)
+            .context("failed to read target from stdin")?;
+        let path = Path::new("<stdin>");
+        if content.trim_start().starts_with('{') {
+            Self::parse_json(path, &content, name)
+        } else {
+            Self::parse_toml(path, &content, name)
+        }
+    }
+
+    fn read_json(path: &Path, name: Option<&str>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("target file not found: {}", path.display()))?;
+        Self::parse_json(path, &content, name)
+    }
+
+    fn parse_json(path: &Path, content: &str, name: Option<&str>) -> Result<Self> {
+        let raw: Value = serde_json::from_str(content)
             .with_context(|| format!("failed to parse {}: invalid JSON", path.display()))?;
 
         let obj = raw
             .as_object()
             .with_context(|| format!("failed to parse {}: expected JSON object", path.display()))?;
 
-        // Auto-detect format: "package" key (MCP server) or "name" key (package.json)
-        let package_name = if let Some(pkg) = obj.get("package").and_then(|v| v.as_str()) {
+        let is_vscode = raw.pointer("/engines/vscode").is_some();
+        let is_webext = obj.contains_key("manifest_version");
+        let publisher = obj.get("publisher").and_then(|v| v.as_str());
+
+        // Auto-detect format: "package" key (MCP server) or "name" key (package.json);
+        // VS Code extensions are published as <publisher>.<name>
+        let package_name = if let Some(name) = name {
+            name.to_string()
+        } else if is_webext {
+            // Distributed through browser stores, not a registry oneup queries
+            String::new()
+        } else if is_vscode {
+            let ext = obj.get("name").and_then(|v| v.as_str());
+            match (publisher, ext) {
+                (Some(publisher), Some(ext)) => format!("{}.{}", publisher, ext),
+                _ => bail!(
+                    "cannot determine extension id from {}: missing 'publisher' or 'name' field",
+                    path.display()
+                ),
+            }
+        } else if let Some(pkg) = obj.get("package").and_then(|v| v.as_str()) {
             pkg.to_string()
         } else if let Some(name) = obj.get("name").and_then(|v| v.as_str()) {
             name.to_string()
         } else {
             bail!(
-                "cannot determine package name from {}: missing 'package' or 'name' field",
+                "cannot determine package name from {}: missing 'package' or 'name' field \
+                 (set publish-name in oneup.toml or pass --package-name)",
                 path.display()
             );
         };
@@ -53,24 +373,42 @@ impl TargetFile {
         Ok(Self {
             package_name,
             version,
-            format: TargetFormat::Json(raw),
+            format: if is_webext {
+                TargetFormat::WebExtension(raw)
+            } else if is_vscode {
+                TargetFormat::VsCodeExtension(raw)
+            } else {
+                TargetFormat::Json(raw)
+            },
         })
     }
 
-    fn read_toml(path: &Path) -> Result<Self> {
+    fn read_toml(path: &Path, name: Option<&str>) -> Result<Self> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("target file not found: {}", path.display()))?;
+        Self::parse_toml(path, &content, name)
+    }
 
+    fn parse_toml(path: &Path, content: &str, name: Option<&str>) -> Result<Self> {
         let doc: toml_edit::DocumentMut = content
             .parse()
             .with_context(|| format!("failed to parse {}: invalid TOML", path.display()))?;
 
-        let package_name = doc
-            .get("package")
-            .and_then(|p| p.get("name"))
-            .and_then(|n| n.as_str())
-            .with_context(|| format!("missing package.name in {}", path.display()))?
-            .to_string();
+        let package_name = match name {
+            Some(name) => name.to_string(),
+            None => doc
+                .get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str())
+                .with_context(|| {
+                    format!(
+                        "missing package.name in {} \
+                         (set publish-name in oneup.toml or pass --package-name)",
+                        path.display()
+                    )
+                })?
+                .to_string(),
+        };
 
         let version = doc
             .get("package")
@@ -86,9 +424,19 @@ impl TargetFile {
         })
     }
 
-    pub fn write(&self, path: &Path, new_version: &str) -> Result<()> {
+    /// File contents with the version replaced by `new_version`. `released`
+    /// is the release date written to CITATION.cff and codemeta.json; None
+    /// keeps the dates they have, for writes that are not a release.
+    pub fn render(&self, new_version: &str, released: Option<NaiveDate>) -> Result<String> {
         match &self.format {
-            TargetFormat::Json(raw) => {
+            TargetFormat::Json(raw)
+            | TargetFormat::VsCodeExtension(raw)
+            | TargetFormat::WebExtension(raw) => {
+                match self.format {
+                    TargetFormat::VsCodeExtension(_) => vscode::check_version(new_version)?,
+                    TargetFormat::WebExtension(_) => webext::check_version(new_version)?,
+                    _ => check_semver(new_version)?,
+                }
                 let mut raw = raw.clone();
                 raw.as_object_mut().unwrap().insert(
                     "version".to_string(),
@@ -98,24 +446,262 @@ impl TargetFile {
                 // Preserve 2-space indent + trailing newline
                 let mut output = serde_json::to_string_pretty(&raw)?;
                 output.push('\n');
-
-                std::fs::write(path, &output)
-                    .with_context(|| format!("failed to write {}", path.display()))?;
+                Ok(output)
             }
             TargetFormat::Toml(doc) => {
+                check_semver(new_version)?;
                 let mut doc = doc.clone();
                 doc["package"]["version"] = toml_edit::value(new_version);
+                Ok(doc.to_string())
+            }
+            TargetFormat::Source(template) => {
+                Ok(template.replace(VERSION_PLACEHOLDER, new_version))
+            }
+            TargetFormat::Gradle(content) => gradle::update(content, new_version),
+            TargetFormat::Plist(content) => xcode::update_plist(content, new_version),
+            TargetFormat::Pbxproj(content) => xcode::update_pbxproj(content, new_version),
+            TargetFormat::HelmChart {
+                content,
+                app_version,
+            } => helm::update(content, new_version, app_version.as_deref()),
+            TargetFormat::Nix(content) => nix::update(content, &self.package_name, new_version),
+            TargetFormat::Bazel(content) => bazel::update(content, new_version),
+            TargetFormat::Cff(content) => {
+                let date = released.map(|d| d.format("%Y-%m-%d").to_string());
+                citation::update_cff(content, new_version, date.as_deref())
+            }
+            TargetFormat::Dockerfile(content) => docker::update_dockerfile(content, new_version),
+            TargetFormat::Image { content, image } => {
+                docker::update_image_tag(content, image, new_version)
+            }
+            TargetFormat::Docs { content, patterns } => {
+                docs::update(content, patterns, new_version)
+            }
+            TargetFormat::CodeMeta(raw) => {
+                let date = released.map(|d| d.format("%Y-%m-%d").to_string());
+                citation::update_codemeta(raw, new_version, date.as_deref())
+            }
+        }
+    }
+
+    /// Set a Helm chart's `appVersion` on the next write. Returns false
+    /// for any other kind of target.
+    pub fn set_app_version(&mut self, version: &str) -> bool {
+        match &mut self.format {
+            TargetFormat::HelmChart { app_version, .. } => {
+                *app_version = Some(version.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
 
-                std::fs::write(path, doc.to_string())
-                    .with_context(|| format!("failed to write {}", path.display()))?;
+    /// Metadata a registry listing needs that the manifest lacks
+    /// (description, license, repository). Only published manifests are checked.
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        let present = |key: &str| match &self.format {
+            TargetFormat::Json(raw) | TargetFormat::VsCodeExtension(raw) => raw
+                .get(key)
+                .is_some_and(|v| !v.is_null() && v.as_str() != Some("")),
+            TargetFormat::Toml(doc) => doc
+                .get("package")
+                .and_then(|p| p.get(key))
+                .is_some_and(|v| v.as_str() != Some("")),
+            _ => true,
+        };
+        let mut missing = Vec::new();
+        for key in ["description", "license", "repository"] {
+            let found =
+                present(key) || (key == "license" && self.is_cargo() && present("license-file"));
+            if !found {
+                missing.push(key);
             }
         }
-        Ok(())
+        missing
     }
 
     pub fn is_cargo(&self) -> bool {
         matches!(self.format, TargetFormat::Toml(_))
     }
+
+    /// Whether this is a package manifest published to a registry
+    /// (package.json, Cargo.toml) rather than an app or source file.
+    pub fn is_published(&self) -> bool {
+        matches!(
+            self.format,
+            TargetFormat::Json(_) | TargetFormat::VsCodeExtension(_) | TargetFormat::Toml(_)
+        )
+    }
+
+    /// Whether this is a VS Code extension (published to the Marketplace, not npm).
+    pub fn is_vscode(&self) -> bool {
+        matches!(self.format, TargetFormat::VsCodeExtension(_))
+    }
+}
+
+/// `" (did you mean '...'?)"` naming the file next to a missing target
+/// whose name is closest, e.g. `cargo.toml` for `Cargo.toml`.
+fn missing_hint(path: &Path) -> String {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let (Some(name), Ok(entries)) = (
+        path.file_name().and_then(|f| f.to_str()),
+        std::fs::read_dir(dir),
+    ) else {
+        return String::new();
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .collect();
+    files.sort();
+    match suggest::closest(name, files.iter().map(String::as_str)) {
+        Some(file) => format!(" (did you mean '{}'?)", path.with_file_name(file).display()),
+        None => String::new(),
+    }
+}
+
+/// Built-in template for a generated version file. Only conventional names
+/// (version.rs, version.ts, __version__.py, ...) qualify, so a source file
+/// such as main.rs is never overwritten by mistake.
+fn generated_template(path: &Path) -> Option<&'static str> {
+    let stem = path.file_stem().and_then(|s| s.to_str())?;
+    if !matches!(stem, "version" | "_version" | "__version__") {
+        return None;
+    }
+    default_template(path.extension().and_then(|e| e.to_str())?)
+}
+
+/// Built-in version-constant template for a source file extension.
+fn default_template(ext: &str) -> Option<&'static str> {
+    match ext {
+        "rs" => Some("pub const VERSION: &str = \"{version}\";\n"),
+        "ts" | "js" | "mjs" => Some("export const VERSION = \"{version}\";\n"),
+        "py" => Some("__version__ = \"{version}\"\n"),
+        "go" => Some("package version\n\nconst Version = \"{version}\"\n"),
+        _ => None,
+    }
+}
+
+/// Version inside `content` previously generated from `template`.
+fn version_in<'a>(template: &str, content: &'a str) -> Option<&'a str> {
+    let (prefix, suffix) = template.split_once(VERSION_PLACEHOLDER)?;
+    let rest = content.strip_prefix(prefix)?;
+    let end = match suffix.lines().next().filter(|s| !s.is_empty()) {
+        Some(stop) => rest.find(stop)?,
+        None => rest.find('\n').unwrap_or(rest.len()),
+    };
+    Some(&rest[..end]).filter(|v| !v.is_empty())
+}
+
+/// npm and Cargo reject versions that are not SemVer 2, such as the four
+/// components of YYYY.MM.DD.MICRO. Catching that while rendering stops the
+/// run before any file is written, instead of at publish time.
+fn check_semver(version: &str) -> Result<()> {
+    if is_semver(version) {
+        return Ok(());
+    }
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let hint = if core.split('.').count() > 3 {
+        "; use a format with three components, such as YYYY.MM.MICRO, \
+         or YYYY.MM.DD for at most one release a day"
+    } else {
+        ""
+    };
+    bail!(
+        "{} is not valid SemVer (exactly three integers without leading zeros), \
+         which npm and Cargo require{}",
+        version,
+        hint
+    )
+}
+
+/// A target rendered for its new version, with the content it replaces
+#[derive(Debug)]
+pub struct Rendered<'a> {
+    pub path: &'a Path,
+    content: String,
+    original: Option<Vec<u8>>,
+}
+
+/// Render every target before any is written, so a version one of them
+/// cannot hold stops the run with nothing touched.
+pub fn render_all<'a>(
+    writes: &[(&'a Path, &TargetFile, &str)],
+    released: Option<NaiveDate>,
+) -> Result<Vec<Rendered<'a>>> {
+    let mut rendered = Vec::new();
+    for (path, target, version) in writes {
+        let content = target.render(version, released)?;
+        let original = if path.exists() {
+            Some(
+                std::fs::read(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?,
+            )
+        } else {
+            None
+        };
+        rendered.push(Rendered {
+            path,
+            content,
+            original,
+        });
+    }
+    Ok(rendered)
+}
+
+/// Write rendered targets as one unit: if any write fails, the targets
+/// already written are restored to their original contents (or removed,
+/// if they did not exist before).
+pub fn write_all(rendered: &[Rendered]) -> Result<()> {
+    for (i, file) in rendered.iter().enumerate() {
+        if let Err(err) = write_atomic(file.path, file.content.as_bytes()) {
+            let mut failed = Vec::new();
+            for written in &rendered[..i] {
+                let restored = match &written.original {
+                    Some(original) => write_atomic(written.path, original),
+                    None => std::fs::remove_file(written.path).map_err(Into::into),
+                };
+                if restored.is_err() {
+                    failed.push(written.path.display().to_string());
+                }
+            }
+            if failed.is_empty() {
+                return Err(
+                    err.context(format!("restored {} file(s) written before the failure", i))
+                );
+            }
+            return Err(err.context(format!("could not restore: {}", failed.join(", "))));
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace `path` through a temporary file in the same directory and a
+/// rename, so readers never see a partially written file. A symlinked
+/// `path` is resolved first, so the file it points to is replaced and the
+/// link stays.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let real = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let name = real
+        .file_name()
+        .with_context(|| format!("invalid target path {}", path.display()))?;
+    let tmp = real.with_file_name(format!(".{}.oneup-tmp", name.to_string_lossy()));
+
+    let result = std::fs::write(&tmp, content)
+        .and_then(|_| match std::fs::metadata(&real) {
+            Ok(meta) => std::fs::set_permissions(&tmp, meta.permissions()),
+            Err(_) => Ok(()),
+        })
+        .and_then(|_| std::fs::rename(&tmp, &real));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result.with_context(|| format!("failed to write {}", path.display()))
 }
 
 #[cfg(test)]
@@ -123,6 +709,14 @@ mod tests {
     use super::*;
     use std::io::Write;
 
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+    }
+
+    fn write(target: &TargetFile, path: &Path, version: &str) -> Result<()> {
+        write_all(&render_all(&[(path, target, version)], Some(today()))?)
+    }
+
     fn temp_json(content: &str) -> tempfile::NamedTempFile {
         let mut f = tempfile::Builder::new()
             .suffix(".json")
@@ -146,7 +740,7 @@ mod tests {
     #[test]
     fn read_package_json_format() {
         let f = temp_json(r#"{"name": "my-pkg", "version": "1.0.0"}"#);
-        let target = TargetFile::read(f.path()).unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
         assert_eq!(target.package_name, "my-pkg");
         assert_eq!(target.version, "1.0.0");
         assert!(!target.is_cargo());
@@ -155,7 +749,7 @@ mod tests {
     #[test]
     fn read_mcp_server_format() {
         let f = temp_json(r#"{"package": "@scope/mcp-server", "version": "2.3.4"}"#);
-        let target = TargetFile::read(f.path()).unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
         assert_eq!(target.package_name, "@scope/mcp-server");
         assert_eq!(target.version, "2.3.4");
     }
@@ -163,20 +757,75 @@ mod tests {
     #[test]
     fn read_package_key_takes_precedence() {
         let f = temp_json(r#"{"package": "pkg-name", "name": "other-name", "version": "1.0.0"}"#);
-        let target = TargetFile::read(f.path()).unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
         assert_eq!(target.package_name, "pkg-name");
     }
 
     #[test]
     fn read_missing_name_and_package() {
         let f = temp_json(r#"{"version": "1.0.0"}"#);
-        assert!(TargetFile::read(f.path()).is_err());
+        assert!(TargetFile::read(f.path(), None).is_err());
+    }
+
+    #[test]
+    fn read_vscode_extension() {
+        let f = temp_json(
+            r#"{"name": "my-ext", "publisher": "circlesac", "version": "26.2.1", "engines": {"vscode": "^1.90.0"}}"#,
+        );
+        let target = TargetFile::read(f.path(), None).unwrap();
+        assert_eq!(target.package_name, "circlesac.my-ext");
+        assert!(target.is_vscode());
+        assert!(target.is_published());
+        assert!(write(&target, f.path(), "26.2.5-beta.1").is_err());
+        write(&target, f.path(), "26.2.5").unwrap();
+        assert_eq!(TargetFile::read(f.path(), None).unwrap().version, "26.2.5");
+    }
+
+    #[test]
+    fn read_browser_extension_manifest() {
+        let f =
+            temp_json(r#"{"manifest_version": 3, "name": "My Extension", "version": "26.2.1"}"#);
+        let target = TargetFile::read(f.path(), None).unwrap();
+        assert_eq!(target.package_name, "");
+        assert_eq!(target.version, "26.2.1");
+        assert!(!target.is_published());
+        assert!(write(&target, f.path(), "26.2.5-beta.1").is_err());
+        assert!(write(&target, f.path(), "20260217.5").is_err());
+        write(&target, f.path(), "2026.2.17.5").unwrap();
+        assert_eq!(
+            TargetFile::read(f.path(), None).unwrap().version,
+            "2026.2.17.5"
+        );
+    }
+
+    #[test]
+    fn missing_manifest_fields() {
+        let f = temp_json(r#"{"name": "a", "version": "1.0.0", "license": "MIT"}"#);
+        let target = TargetFile::read(f.path(), None).unwrap();
+        assert_eq!(target.missing_fields(), vec!["description", "repository"]);
+
+        let f = temp_toml(
+            "[package]\nname = \"a\"\nversion = \"1.0.0\"\ndescription = \"x\"\nlicense-file = \"LICENSE\"\nrepository = \"https://example.com\"\n",
+        );
+        let target = TargetFile::read(f.path(), None).unwrap();
+        assert!(target.missing_fields().is_empty());
+    }
+
+    #[test]
+    fn read_with_name_overrides_manifest() {
+        let f = temp_json(r#"{"version": "1.0.0"}"#);
+        let target = TargetFile::read(f.path(), Some("@scope/published")).unwrap();
+        assert_eq!(target.package_name, "@scope/published");
+
+        let f = temp_json(r#"{"name": "local-name", "version": "1.0.0"}"#);
+        let target = TargetFile::read(f.path(), Some("published")).unwrap();
+        assert_eq!(target.package_name, "published");
     }
 
     #[test]
     fn read_missing_version_defaults_to_zero() {
         let f = temp_json(r#"{"name": "my-pkg"}"#);
-        let target = TargetFile::read(f.path()).unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
         assert_eq!(target.package_name, "my-pkg");
         assert_eq!(target.version, "0.0.0");
     }
@@ -184,21 +833,35 @@ mod tests {
     #[test]
     fn read_invalid_json() {
         let f = temp_json("not json");
-        assert!(TargetFile::read(f.path()).is_err());
+        assert!(TargetFile::read(f.path(), None).is_err());
     }
 
     #[test]
     fn read_file_not_found() {
-        assert!(TargetFile::read(Path::new("/nonexistent/file.json")).is_err());
+        assert!(TargetFile::read(Path::new("/nonexistent/file.json"), None).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        let err = TargetFile::read(&dir.path().join("cargo.toml"), None)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "target file not found: {} (did you mean '{}'?)",
+                dir.path().join("cargo.toml").display(),
+                dir.path().join("Cargo.toml").display()
+            )
+        );
     }
 
     #[test]
     fn write_updates_version() {
         let f = temp_json(r#"{"name": "my-pkg", "version": "1.0.0"}"#);
-        let target = TargetFile::read(f.path()).unwrap();
-        target.write(f.path(), "2.0.0").unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
+        write(&target, f.path(), "2.0.0").unwrap();
 
-        let updated = TargetFile::read(f.path()).unwrap();
+        let updated = TargetFile::read(f.path(), None).unwrap();
         assert_eq!(updated.version, "2.0.0");
         assert_eq!(updated.package_name, "my-pkg");
     }
@@ -206,13 +869,172 @@ mod tests {
     #[test]
     fn write_preserves_trailing_newline() {
         let f = temp_json(r#"{"name": "my-pkg", "version": "1.0.0"}"#);
-        let target = TargetFile::read(f.path()).unwrap();
-        target.write(f.path(), "2.0.0").unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
+        write(&target, f.path(), "2.0.0").unwrap();
 
         let content = std::fs::read_to_string(f.path()).unwrap();
         assert!(content.ends_with('\n'));
     }
 
+    #[test]
+    fn write_all_rolls_back_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.json");
+        let b = dir.path().join("b.json");
+        std::fs::write(&a, r#"{"name": "a", "version": "1.0.0"}"#).unwrap();
+        std::fs::write(&b, r#"{"name": "b", "version": "1.0.0"}"#).unwrap();
+        let ta = TargetFile::read(&a, None).unwrap();
+        let tb = TargetFile::read(&b, None).unwrap();
+
+        // A directory in the way of b's temp file makes its write fail
+        std::fs::create_dir(dir.path().join(".b.json.oneup-tmp")).unwrap();
+
+        let rendered = render_all(
+            &[(a.as_path(), &ta, "2.0.0"), (b.as_path(), &tb, "2.0.0")],
+            Some(today()),
+        )
+        .unwrap();
+        let result = write_all(&rendered);
+        assert!(result.is_err());
+        assert_eq!(TargetFile::read(&a, None).unwrap().version, "1.0.0");
+        assert_eq!(TargetFile::read(&b, None).unwrap().version, "1.0.0");
+    }
+
+    #[test]
+    fn write_all_writes_every_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.json");
+        std::fs::write(&a, r#"{"name": "a", "version": "1.0.0"}"#).unwrap();
+        let ta = TargetFile::read(&a, None).unwrap();
+
+        write(&ta, &a, "2.0.0").unwrap();
+        assert_eq!(TargetFile::read(&a, None).unwrap().version, "2.0.0");
+        assert!(!dir.path().join(".a.json.oneup-tmp").exists());
+    }
+
+    #[test]
+    fn render_all_rejects_non_semver_before_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.json");
+        let b = dir.path().join("Cargo.toml");
+        std::fs::write(&a, r#"{"name": "a", "version": "1.0.0"}"#).unwrap();
+        std::fs::write(&b, "[package]\nname = \"b\"\nversion = \"1.0.0\"\n").unwrap();
+        let ta = TargetFile::read(&a, None).unwrap();
+        let tb = TargetFile::read(&b, None).unwrap();
+
+        let err = render_all(
+            &[
+                (a.as_path(), &ta, "26.10.17"),
+                (b.as_path(), &tb, "2026.10.17.5"),
+            ],
+            Some(today()),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.starts_with("2026.10.17.5 is not valid SemVer"),
+            "{}",
+            err
+        );
+        assert!(err.contains("YYYY.MM.MICRO"));
+        assert_eq!(TargetFile::read(&a, None).unwrap().version, "1.0.0");
+        assert!(ta.render("2026.10.17.5", Some(today())).is_err());
+        assert!(ta.render("26.10.17-beta.0+a1b2c3d", Some(today())).is_ok());
+    }
+
+    #[test]
+    fn citation_is_dated_with_the_release_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CITATION.cff");
+        std::fs::write(
+            &path,
+            "title: a\nversion: 26.9.0\ndate-released: 2026-09-01\n",
+        )
+        .unwrap();
+        let target = TargetFile::read(&path, None).unwrap();
+        assert_eq!(
+            target.render("26.10.0", Some(today())).unwrap(),
+            "title: a\nversion: 26.10.0\ndate-released: 2026-10-17\n"
+        );
+        assert_eq!(
+            target.render("26.10.0", None).unwrap(),
+            "title: a\nversion: 26.10.0\ndate-released: 2026-09-01\n"
+        );
+    }
+
+    // --- Generated source tests ---
+
+    #[test]
+    fn source_target_uses_language_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("version.rs");
+
+        let target = TargetFile::read(&path, None).unwrap();
+        assert!(!target.is_published());
+        assert_eq!(target.version, "0.0.0");
+
+        write(&target, &path, "26.2.5").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "pub const VERSION: &str = \"26.2.5\";\n"
+        );
+        assert_eq!(TargetFile::read(&path, None).unwrap().version, "26.2.5");
+    }
+
+    #[test]
+    fn source_target_custom_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Version.kt");
+        let template = "object Version {\n    const val NAME = \"{version}\"\n}\n";
+
+        let target = TargetFile::read_source(&path, None, Some(template)).unwrap();
+        write(&target, &path, "2026.2.17").unwrap();
+        let target = TargetFile::read_source(&path, None, Some(template)).unwrap();
+        assert_eq!(target.version, "2026.2.17");
+
+        let env = dir.path().join("release.env");
+        let target = TargetFile::read_source(&env, None, Some("VERSION={version}\n")).unwrap();
+        write(&target, &env, "26.2.5").unwrap();
+        let target = TargetFile::read_source(&env, None, Some("VERSION={version}\n")).unwrap();
+        assert_eq!(target.version, "26.2.5");
+
+        assert!(TargetFile::read_source(&path, None, Some("no placeholder")).is_err());
+        assert!(TargetFile::read_source(&path, None, None).is_err());
+    }
+
+    #[test]
+    fn source_code_is_not_a_generated_target() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["__version__.py", "_version.py", "version.go", "version.mjs"] {
+            assert!(TargetFile::read(&dir.path().join(name), None).is_ok());
+        }
+
+        let main = dir.path().join("main.rs");
+        std::fs::write(&main, "fn main() {}\n").unwrap();
+        let err = TargetFile::read(&main, None).err().unwrap().to_string();
+        assert!(err.contains("not a generated version file"), "{}", err);
+        assert!(TargetFile::read_source(&main, None, None).is_err());
+        assert!(TargetFile::read(&dir.path().join("index.ts"), None).is_err());
+
+        let template = "pub const VERSION: &str = \"{version}\";\n";
+        assert!(TargetFile::read_source(&main, None, Some(template)).is_ok());
+    }
+
+    #[test]
+    fn gradle_target_updates_name_and_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("build.gradle.kts");
+        std::fs::write(&path, "versionCode = 1\nversionName = \"0.0.0\"\n").unwrap();
+
+        let target = TargetFile::read(&path, None).unwrap();
+        assert!(!target.is_published());
+        write(&target, &path, "26.2.5").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "versionCode = 260205\nversionName = \"26.2.5\"\n"
+        );
+    }
+
     // --- TOML tests ---
 
     #[test]
@@ -223,7 +1045,7 @@ name = "my-crate"
 version = "1.0.0"
 "#,
         );
-        let target = TargetFile::read(f.path()).unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
         assert_eq!(target.package_name, "my-crate");
         assert_eq!(target.version, "1.0.0");
         assert!(target.is_cargo());
@@ -236,7 +1058,18 @@ version = "1.0.0"
 version = "1.0.0"
 "#,
         );
-        assert!(TargetFile::read(f.path()).is_err());
+        assert!(TargetFile::read(f.path(), None).is_err());
+    }
+
+    #[test]
+    fn read_cargo_toml_renamed_crate() {
+        let f = temp_toml(
+            r#"[package]
+version = "1.0.0"
+"#,
+        );
+        let target = TargetFile::read(f.path(), Some("published-crate")).unwrap();
+        assert_eq!(target.package_name, "published-crate");
     }
 
     #[test]
@@ -246,13 +1079,13 @@ version = "1.0.0"
 name = "my-crate"
 "#,
         );
-        assert!(TargetFile::read(f.path()).is_err());
+        assert!(TargetFile::read(f.path(), None).is_err());
     }
 
     #[test]
     fn read_invalid_toml() {
         let f = temp_toml("not [valid toml");
-        assert!(TargetFile::read(f.path()).is_err());
+        assert!(TargetFile::read(f.path(), None).is_err());
     }
 
     #[test]
@@ -263,10 +1096,10 @@ name = "my-crate"
 version = "1.0.0"
 "#,
         );
-        let target = TargetFile::read(f.path()).unwrap();
-        target.write(f.path(), "2.0.0").unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
+        write(&target, f.path(), "2.0.0").unwrap();
 
-        let updated = TargetFile::read(f.path()).unwrap();
+        let updated = TargetFile::read(f.path(), None).unwrap();
         assert_eq!(updated.version, "2.0.0");
         assert_eq!(updated.package_name, "my-crate");
     }
@@ -280,12 +1113,54 @@ version = "1.0.0"
 edition = "2024"
 "#;
         let f = temp_toml(original);
-        let target = TargetFile::read(f.path()).unwrap();
-        target.write(f.path(), "2.0.0").unwrap();
+        let target = TargetFile::read(f.path(), None).unwrap();
+        write(&target, f.path(), "2.0.0").unwrap();
 
         let content = std::fs::read_to_string(f.path()).unwrap();
         assert!(content.contains("# This is a comment"));
         assert!(content.contains("edition = \"2024\""));
         assert!(content.contains("version = \"2.0.0\""));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_through_a_symlinked_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("shared.toml");
+        std::fs::write(&real, "[package]\nname = \"c\"\nversion = \"1.0.0\"\n").unwrap();
+        let link = dir.path().join("Cargo.toml");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let target = TargetFile::read(&link, None).unwrap();
+        write(&target, &link, "2.0.0").unwrap();
+
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(TargetFile::read(&real, None).unwrap().version, "2.0.0");
+    }
+
+    #[test]
+    fn parse_and_render_without_a_file() {
+        let path = Path::new("<stdin>");
+        let target = TargetFile::parse_json(
+            path,
+            "{\n  \"name\": \"pkg\",\n  \"version\": \"1.0.0\"\n}\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(target.package_name, "pkg");
+        assert!(
+            target
+                .render("2.0.0", Some(today()))
+                .unwrap()
+                .contains("\"version\": \"2.0.0\"")
+        );
+
+        let target =
+            TargetFile::parse_toml(path, "[package]\nname = \"c\"\nversion = \"1.0.0\"\n", None)
+                .unwrap();
+        assert_eq!(
+            target.render("2.0.0", Some(today())).unwrap(),
+            "[package]\nname = \"c\"\nversion = \"2.0.0\"\n"
+        );
+    }
 }
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+/// Per-phase wall-clock timings for a run, reported with --timing
+pub struct Timings {
+    enabled: bool,
+    start: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    pub fn new(enabled: bool) -> Self {
+        let now = Instant::now();
+        Self {
+            enabled,
+            start: now,
+            last: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Record the time spent since the previous lap under `phase`.
+    pub fn lap(&mut self, phase: &'static str) {
+        let now = Instant::now();
+        if self.enabled {
+            self.phases.push((phase, now - self.last));
+        }
+        self.last = now;
+    }
+
+    /// Print the per-phase report to stderr (no-op unless enabled).
+    pub fn report(&self) {
+        if !self.enabled {
+            return;
+        }
+        for (phase, elapsed) in &self.phases {
+            eprintln!("[timing] {:<10} {}", phase, format_duration(*elapsed));
+        }
+        eprintln!(
+            "[timing] {:<10} {}",
+            "total",
+            format_duration(self.start.elapsed())
+        );
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lap_records_phases_when_enabled() {
+        let mut t = Timings::new(true);
+        t.lap("targets");
+        t.lap("registry");
+        let names: Vec<_> = t.phases.iter().map(|(p, _)| *p).collect();
+        assert_eq!(names, vec!["targets", "registry"]);
+    }
+
+    #[test]
+    fn lap_is_noop_when_disabled() {
+        let mut t = Timings::new(false);
+        t.lap("targets");
+        assert!(t.phases.is_empty());
+    }
+
+    #[test]
+    fn format_duration_millis() {
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.5ms");
+    }
+}
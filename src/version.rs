@@ -1,37 +1,88 @@
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 
-use crate::cli::VersionArgs;
+use crate::backup;
+use crate::ci::CiInfo;
+use crate::cli::{MicroSource, RegistryType, VersionArgs};
+use crate::config::{Config, TargetConfig, same_path};
+#[cfg(feature = "crates")]
 use crate::crates_io::CratesIoClient;
+use crate::error::{ErrorKind, ResultExt};
 use crate::format::VersionFormat;
+use crate::git;
+#[cfg(feature = "network")]
+use crate::github::GitHubClient;
+use crate::gitlab;
+#[cfg(feature = "npm")]
+use crate::keychain;
+use crate::metrics::{self, Outcome};
 use crate::npmrc::NpmrcConfig;
-use crate::registry::{PackageInfo, RegistryClient};
-use crate::target::TargetFile;
+use crate::output;
+#[cfg(feature = "npm")]
+use crate::redact::redact;
+#[cfg(feature = "npm")]
+use crate::registry::RegistryClient;
+use crate::registry::{self, PackageInfo};
+use crate::resolve::{self, Skip, max_micro_today, micro_gaps};
+use crate::target::{self, TargetFile};
+use crate::timing::Timings;
+use crate::vscode::OPEN_VSX_URL;
+#[cfg(feature = "network")]
+use crate::vscode::{MarketplaceClient, OpenVsxClient};
 
 pub fn run(args: VersionArgs) -> Result<()> {
-    // 1. Parse version format
-    let fmt = VersionFormat::parse(&args.format)?;
+    let mut run = metrics::Run::start();
+    let result = bump(&args, &mut run);
+    if let Some(url) = &args.metrics {
+        run.finish(&result);
+        metrics::push(url, &run, args.verbose);
+    }
+    result
+}
 
-    // 2. Resolve target paths
-    let target_paths = if args.target.is_empty() {
-        detect_targets()?
-    } else {
-        args.target.clone()
-    };
+fn bump(args: &VersionArgs, run: &mut metrics::Run) -> Result<()> {
+    let mut timings = Timings::new(args.timing);
 
-    // 3. Read all targets, pick the primary (highest version) for registry query
-    let mut targets: Vec<(PathBuf, TargetFile)> = Vec::new();
-    for path in &target_paths {
-        targets.push((path.clone(), TargetFile::read(path)?));
+    // 1. Parse version format and load oneup.toml; --before/--date replay
+    //    the run as of a past time. On CI the date is taken in UTC, so
+    //    runners in any timezone agree on the period.
+    let before = args.before.as_deref().map(parse_before).transpose()?;
+    let date = args.date.or(before.map(|b| b.date_naive()));
+    let ci = CiInfo::detect();
+    let fmt = match date {
+        Some(date) => VersionFormat::parse(&args.format)?.with_today(date),
+        None if args.utc.unwrap_or(ci.is_some()) => VersionFormat::parse(&args.format)?.in_utc(),
+        None => VersionFormat::parse(&args.format)?,
+    };
+    let config = Config::load(Path::new("."))?;
+    if let Some(reason) = &config.freeze {
+        return Err(anyhow!("releases are frozen: {}", reason)).kind(ErrorKind::Config);
     }
+    check_release_window(&config, args.override_window, args.verbose)?;
 
-    // Sort by version descending — first entry is primary
-    targets.sort_by(|a, b| compare_versions(&b.1.version, &a.1.version));
-
+    // 2. Resolve target paths and read them, primary first
+    let primary = args.primary.as_deref().or(config.primary_target());
+    let mut targets = load_targets(&args.target, primary, &config, args.package_name.as_deref())?;
     let (primary_path, primary_target) = &targets[0];
+    run.package = Some(primary_target.package_name.clone());
+    timings.lap("targets");
+    warn_disagreeing_targets(&targets, &config, primary.is_some());
 
     if args.verbose {
+        if let Some(ci) = &ci {
+            eprintln!("[ci] provider: {}", ci.provider);
+            if let Some(url) = &ci.run_url {
+                eprintln!("[ci] run: {}", url);
+            }
+            if let Some(actor) = &ci.actor {
+                eprintln!("[ci] actor: {}", actor);
+            }
+            if let Some(id) = &ci.pipeline_id {
+                eprintln!("[ci] pipeline: {}", id);
+            }
+        }
         for (path, t) in &targets {
             eprintln!("[target] file: {} ({})", path.display(), t.version);
         }
@@ -42,97 +93,808 @@ pub fn run(args: VersionArgs) -> Result<()> {
             args.format,
             if fmt.has_micro() { "yes" } else { "no" }
         );
+        let clock = match (date, fmt.utc) {
+            (Some(_), _) => "--date",
+            (None, true) => "UTC",
+            (None, false) => "local time",
+        };
+        eprintln!("[date] {} ({})", fmt.today, clock);
     }
 
-    // 4. Query registry for published versions (using primary target)
-    let info = if primary_target.is_cargo() {
-        let client = CratesIoClient::new(args.registry.as_deref());
+    let project_dir = primary_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
 
+    // 3. Query registry for published versions (using primary target),
+    //    unless MICRO is derived from git history
+    let info = if args.micro.is_none() && args.micro_source == MicroSource::GitCount {
         if args.verbose {
-            eprintln!("[registry] type: crates.io");
+            eprintln!("[registry] skipped (MICRO from git commit count)");
         }
-
-        client.get_package(&primary_target.package_name, args.verbose)?
+        None
+    } else if let Some(cassette) = &args.replay {
+        Some(registry::read_fixture(cassette, args.verbose)?)
     } else {
-        let project_dir = primary_path
-            .parent()
-            .unwrap_or_else(|| std::path::Path::new("."));
-
-        let scope = if primary_target.package_name.starts_with('@') {
-            primary_target.package_name.split('/').next()
-        } else {
-            None
-        };
-
-        let (registry_url, auth_token) = if let Some(ref url) = args.registry {
-            (url.trim_end_matches('/').to_string(), None)
-        } else {
-            let npmrc = NpmrcConfig::load(project_dir)?;
-            let url = npmrc.registry_url(scope);
-            let token = npmrc.auth_token(&url);
-            (url, token)
-        };
+        let info = query_registry(
+            primary_path,
+            primary_target,
+            args.registry.as_deref(),
+            args.registry_type,
+            &config,
+            args.verbose,
+            &mut timings,
+        )?;
+        if let Some(cassette) = &args.record {
+            let registry = args.registry.as_deref().unwrap_or("auto");
+            registry::write_cassette(cassette, &info, registry, &primary_target.package_name)?;
+            if args.verbose {
+                eprintln!("[registry] recorded {}", cassette.display());
+            }
+        }
+        Some(info)
+    };
+    let info = match (info, before) {
+        (Some(info), Some(cutoff)) => Some(registry_before(info, cutoff, args.verbose)?),
+        (info, _) => info,
+    };
+    let info = match info {
+        Some(info) if args.never_reuse => Some(with_retired(info, args.verbose)),
+        info => info,
+    };
+    timings.lap("registry");
 
+    // 4. Determine next version
+    let new_version = compute_version(
+        args,
+        &fmt,
+        &config,
+        info.as_ref(),
+        &primary_target.package_name,
+        project_dir,
+    )?;
+    run.version = Some(new_version.clone());
+    // PR previews are not releases, and an unchanged version publishes nothing
+    if args.pr.is_none() && new_version != primary_target.version {
+        check_policy(&config, &fmt, &new_version, project_dir, args.verbose)?;
+    }
+    // Hotfixes deliberately sort below the release they patch, and a
+    // version built for a past date may sort below later releases
+    if args.pr.is_none()
+        && !args.hotfix
+        && date.is_none()
+        && let Some(info) = &info
+    {
+        if let Some(problem) = below_published(info, &new_version) {
+            return Err(anyhow!("{}", problem)).kind(ErrorKind::Conflict);
+        }
         if args.verbose {
-            eprintln!("[registry] type: npm");
-            eprintln!("[registry] url: {}", registry_url);
-            eprintln!(
-                "[registry] auth: {}",
-                if auth_token.is_some() {
-                    "token"
-                } else {
-                    "none"
-                }
-            );
+            eprintln!("[bump] {} sorts above every published release", new_version);
         }
+    }
+    timings.lap("compute");
 
-        let client = RegistryClient::new(&registry_url, auth_token);
-        client.get_package(&primary_target.package_name, args.verbose)?
-    };
+    // Per-target formats ([targets] in oneup.toml) render the same date and MICRO
+    let mut target_versions = Vec::new();
+    for (i, (path, _)) in targets.iter().enumerate() {
+        let format = config.target(path).and_then(|t| t.format.as_deref());
+        let version = match format {
+            None => new_version.clone(),
+            Some(_) if i == 0 => bail!(
+                "{} is the primary target; its format comes from --format, not oneup.toml",
+                path.display()
+            ),
+            Some(format) => reformat_version(
+                &fmt,
+                &VersionFormat::parse(format)?.with_today(fmt.today),
+                &new_version,
+            )?,
+        };
+        target_versions.push(version);
+    }
+
+    // A run that spans midnight (slow registry, queued runner) must not
+    // write a version dated for a period that has already ended
+    if args.pr.is_none() && !args.hotfix && date.is_none() && fmt.rolled_over() {
+        return Err(anyhow!(
+            "the date changed during the run: {} was computed for {}, it is now {}; rerun oneup version",
+            new_version,
+            fmt.today,
+            fmt.current_date()
+        ))
+        .kind(ErrorKind::Conflict);
+    }
+    couple_app_versions(&mut targets, &target_versions, &config, args.verbose)?;
+    let primary_target = &targets[0].1;
 
-    // 5. Determine next version
-    let new_version =
-        determine_version(info, &primary_target.package_name, &fmt, args.verbose)?;
+    // --target -: a pure transformer, the updated document goes to stdout
+    // and nothing is written (the version goes to stderr)
+    if is_stdin(&targets[0].0) {
+        print!("{}", primary_target.render(&new_version, Some(fmt.today))?);
+        eprintln!("{}", new_version);
+        run.outcome = Outcome::DryRun;
+        return Ok(());
+    }
 
-    // 6. Check if version actually changed
+    // 5. Check if version actually changed
     if new_version == primary_target.version {
         if args.verbose {
             eprintln!("[bump] version unchanged: {}", new_version);
         }
-        println!("{}", new_version);
-        return Ok(());
+        run.outcome = Outcome::Unchanged;
+        return emit(args, &new_version, &timings);
     }
 
     if args.verbose {
         eprintln!("[bump] {} → {}", primary_target.version, new_version);
     }
 
-    // 7. Dry run — just print and exit
+    // 6. Dry run — just print and exit
     if args.dry_run {
         eprintln!(
             "[dry-run] would update {} → {}",
             primary_target.version, new_version
         );
-        for (path, _) in &targets {
+        for ((path, target), version) in targets.iter().zip(&target_versions) {
+            // Rendering catches versions the target cannot hold (SemVer, ...)
+            target.render(version, Some(fmt.today))?;
+            eprintln!("[dry-run] would write {} ({})", path.display(), version);
+        }
+        run.outcome = Outcome::DryRun;
+        return emit(args, &new_version, &timings);
+    }
+
+    // 7. Update all target files (all or nothing)
+    let writes: Vec<_> = targets
+        .iter()
+        .zip(&target_versions)
+        .map(|((path, target), version)| (path.as_path(), target, version.as_str()))
+        .collect();
+    let rendered = target::render_all(&writes, Some(fmt.today))?;
+    let paths: Vec<&Path> = rendered.iter().map(|file| file.path).collect();
+    let backups = backup::project_dir(paths[0]);
+    if let Some(snapshot) = backup::snapshot(&backups, &paths, config.keep_backups)?
+        && args.verbose
+    {
+        eprintln!("[backup] {}", snapshot.display());
+    }
+    target::write_all(&rendered)?;
+    if args.verbose {
+        for (path, _, version) in &writes {
+            eprintln!("[file] updated {} ({})", path.display(), version);
+        }
+    }
+    timings.lap("write");
+
+    // 8. Print version to stdout
+    emit(args, &new_version, &timings)
+}
+
+/// Parse --before: an RFC 3339 time, or a date meaning its start in UTC
+/// (as npm reads `--before 2026-01-15`).
+fn parse_before(spec: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(spec) {
+        return Ok(time.to_utc());
+    }
+    chrono::NaiveDate::parse_from_str(spec, "%Y-%m-%d")
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+        .ok()
+        .with_context(|| {
+            format!(
+                "invalid --before '{}': expected YYYY-MM-DD or an RFC 3339 time",
+                spec
+            )
+        })
+}
+
+/// The registry's answer as it stood at `cutoff`. Registries that do not
+/// report publish times cannot be replayed.
+fn registry_before(
+    info: PackageInfo,
+    cutoff: chrono::DateTime<chrono::Utc>,
+    verbose: bool,
+) -> Result<PackageInfo> {
+    if let PackageInfo::Found {
+        versions,
+        published,
+        ..
+    } = &info
+        && !versions.is_empty()
+        && published.is_empty()
+    {
+        bail!(
+            "the registry reports no publish times, so --before cannot tell which versions existed"
+        );
+    }
+    let total = match &info {
+        PackageInfo::Found { versions, .. } => versions.len(),
+        PackageInfo::NotFound => 0,
+    };
+    let info = info.before(cutoff);
+    if verbose {
+        let kept = match &info {
+            PackageInfo::Found { versions, .. } => versions.len(),
+            PackageInfo::NotFound => 0,
+        };
+        eprintln!(
+            "[registry] before {}: {} of {} version(s)",
+            cutoff.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            kept,
+            total
+        );
+    }
+    Ok(info)
+}
+
+/// The registry's answer with unpublished and yanked versions counted as
+/// published (--never-reuse).
+fn with_retired(info: PackageInfo, verbose: bool) -> PackageInfo {
+    if verbose {
+        for version in info.retired() {
+            eprintln!(
+                "[registry] {} was unpublished or yanked; counted as taken",
+                version
+            );
+        }
+    }
+    info.with_retired()
+}
+
+/// Report the resulting version: CI service messages / properties file,
+/// then the bare version as the last line of stdout.
+fn emit(args: &VersionArgs, version: &str, timings: &Timings) -> Result<()> {
+    if args.teamcity {
+        for msg in output::teamcity_messages(version) {
+            println!("{}", msg);
+        }
+    }
+
+    if let Some(path) = &args.properties_file {
+        if args.dry_run {
             eprintln!("[dry-run] would write {}", path.display());
+        } else {
+            output::write_properties(path, version)?;
+            if args.verbose {
+                eprintln!("[file] wrote {}", path.display());
+            }
+        }
+    }
+
+    timings.report();
+
+    println!("{}", version);
+    Ok(())
+}
+
+/// Give Helm charts with `app-version-from` the version computed for that
+/// target in this run, so chart and application releases move together.
+fn couple_app_versions(
+    targets: &mut [(PathBuf, TargetFile)],
+    versions: &[String],
+    config: &Config,
+    verbose: bool,
+) -> Result<()> {
+    for i in 0..targets.len() {
+        let Some(from) = config
+            .target(&targets[i].0)
+            .and_then(|t| t.app_version_from.as_deref())
+        else {
+            continue;
+        };
+        let source = targets
+            .iter()
+            .position(|(path, _)| same_path(path, from))
+            .with_context(|| {
+                format!(
+                    "app-version-from {} for {} is not one of the targets",
+                    from.display(),
+                    targets[i].0.display()
+                )
+            })?;
+        let (path, target) = &mut targets[i];
+        if !target.set_app_version(&versions[source]) {
+            bail!(
+                "app-version-from is only supported for Helm Chart.yaml targets, not {}",
+                path.display()
+            );
+        }
+        if verbose {
+            eprintln!(
+                "[helm] {} appVersion: {} (from {})",
+                path.display(),
+                versions[source],
+                from.display()
+            );
         }
-        println!("{}", new_version);
+    }
+    Ok(())
+}
+
+/// Refuse to bump outside the allowed-windows configured in oneup.toml.
+fn check_release_window(config: &Config, override_window: bool, verbose: bool) -> Result<()> {
+    let windows = &config.allowed_windows;
+    if windows.is_empty() {
         return Ok(());
     }
 
-    // 8. Update all target files
-    for (path, target) in &targets {
-        target.write(path, &new_version)?;
+    let now = chrono::Utc::now();
+    if let Some(window) = windows.iter().find(|w| w.contains(now)) {
+        if verbose {
+            eprintln!("[window] inside release window: {}", window);
+        }
+        return Ok(());
+    }
 
-        if args.verbose {
-            eprintln!("[file] updated {}", path.display());
+    let list = windows
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if override_window {
+        eprintln!("warning: outside release windows ({}), overridden", list);
+        return Ok(());
+    }
+    Err(anyhow!(
+        "outside the allowed release windows ({}); pass --override-window to bump anyway",
+        list
+    ))
+    .kind(ErrorKind::Config)
+}
+
+/// Refuse a version that breaks the [policy] rules in oneup.toml, naming
+/// every rule it breaks.
+fn check_policy(
+    config: &Config,
+    fmt: &VersionFormat,
+    version: &str,
+    dir: &Path,
+    verbose: bool,
+) -> Result<()> {
+    let policy = &config.policy;
+    if policy.is_empty() {
+        return Ok(());
+    }
+    let branch = if policy.release_branches.is_empty() {
+        None
+    } else {
+        current_branch(dir)?
+    };
+    let violations = policy.violations(version, fmt, branch.as_deref());
+    if violations.is_empty() {
+        if verbose {
+            eprintln!("[policy] {} passes the release policy", version);
         }
+        return Ok(());
     }
+    Err(anyhow!(
+        "{} breaks the release policy in oneup.toml:\n  - {}",
+        version,
+        violations.join("\n  - ")
+    ))
+    .kind(ErrorKind::Config)
+}
 
-    // 9. Print version to stdout
-    println!("{}", new_version);
+/// Why `version` must not be published when it does not sort above every
+/// release in the registry (checked against the full history, not only
+/// today's prefix). None when it does, or when it is already published.
+pub fn below_published(info: &PackageInfo, version: &str) -> Option<String> {
+    let PackageInfo::Found { versions, .. } = info else {
+        return None;
+    };
+    // Today's version already published: the run changes nothing
+    if versions.iter().any(|v| v == version) {
+        return None;
+    }
+    let higher = resolve::not_below(version, versions);
+    let highest = higher
+        .iter()
+        .max_by(|a, b| resolve::semver_cmp(a, b).unwrap_or(Ordering::Equal))?;
+    Some(format!(
+        "{} does not sort above {} published release(s), up to {}; npm ranges and `latest` would never pick it (did the version format change?)",
+        version,
+        higher.len(),
+        highest
+    ))
+}
 
-    Ok(())
+/// Read the given target files (auto-detected if empty), primary target first.
+/// Without an explicit `primary`, the first published manifest without a
+/// `format` of its own wins, in the order given; versions are never
+/// compared, so a target with a different format cannot take over.
+/// Package names come from `package_name`, then each target's publish-name
+/// in oneup.toml, then the manifest itself.
+pub fn load_targets(
+    paths: &[PathBuf],
+    primary: Option<&Path>,
+    config: &Config,
+    package_name: Option<&str>,
+) -> Result<Vec<(PathBuf, TargetFile)>> {
+    let target_paths = if paths.is_empty() {
+        detect_targets()?
+    } else {
+        paths.to_vec()
+    };
+
+    if target_paths.len() > 1 && target_paths.iter().any(|p| is_stdin(p)) {
+        bail!("--target - (stdin) cannot be combined with other targets");
+    }
+
+    let mut targets: Vec<(PathBuf, TargetFile)> = Vec::new();
+    for path in &target_paths {
+        if is_stdin(path) {
+            targets.push((path.clone(), TargetFile::read_stdin(package_name)?));
+            continue;
+        }
+        let name =
+            package_name.or_else(|| config.target(path).and_then(|t| t.publish_name.as_deref()));
+        let settings = config.target(path);
+        let target = match settings {
+            Some(t) if !t.patterns.is_empty() => TargetFile::read_docs(path, name, &t.patterns)?,
+            Some(TargetConfig {
+                image: Some(image), ..
+            }) => TargetFile::read_image(path, name, image)?,
+            Some(t) if t.template.is_some() => {
+                TargetFile::read_source(path, name, t.template.as_deref())?
+            }
+            _ => TargetFile::read(path, name)?,
+        };
+        targets.push((path.clone(), target));
+    }
+
+    // Published manifests before app and generated files, then targets
+    // rendered in --format before those with their own format; the sort is
+    // stable, so the given order breaks ties — first entry is primary
+    let own_format = |path: &Path| config.target(path).is_some_and(|t| t.format.is_some());
+    targets.sort_by(|a, b| {
+        b.1.is_published()
+            .cmp(&a.1.is_published())
+            .then_with(|| own_format(&a.0).cmp(&own_format(&b.0)))
+    });
+
+    if let Some(primary) = primary {
+        let index = targets
+            .iter()
+            .position(|(path, _)| same_path(path, primary))
+            .with_context(|| {
+                format!(
+                    "primary target {} is not one of the targets",
+                    primary.display()
+                )
+            })?;
+        let target = targets.remove(index);
+        targets.insert(0, target);
+    }
+
+    Ok(targets)
+}
+
+/// Whether `path` is `-`, the document on stdin.
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Warn when published manifests sharing the primary's format carry different
+/// versions, since the registry lookup only follows the primary.
+fn warn_disagreeing_targets(targets: &[(PathBuf, TargetFile)], config: &Config, explicit: bool) {
+    let shared: Vec<_> = targets
+        .iter()
+        .filter(|(path, t)| {
+            t.is_published() && config.target(path).is_none_or(|t| t.format.is_none())
+        })
+        .collect();
+    let (primary_path, primary_target) = &targets[0];
+    if shared
+        .iter()
+        .all(|(_, t)| t.version == primary_target.version)
+    {
+        return;
+    }
+
+    eprintln!("warning: targets disagree on the current version:");
+    for (path, target) in &shared {
+        eprintln!(
+            "warning:   {} {} ({})",
+            path.display(),
+            target.version,
+            target.package_name
+        );
+    }
+    if explicit {
+        eprintln!("warning: using primary target {}", primary_path.display());
+    } else {
+        eprintln!(
+            "warning: using {} (first target); pass --primary or set primary = true under [targets] to choose",
+            primary_path.display()
+        );
+    }
+}
+
+/// npm registry client for `package_name`: --registry (or a GitLab
+/// shorthand), else the .npmrc registry and token for the package's scope.
+#[cfg(feature = "npm")]
+pub fn npm_client(
+    project_dir: &Path,
+    package_name: &str,
+    registry: Option<&str>,
+    verbose: bool,
+) -> Result<RegistryClient> {
+    let scope = if package_name.starts_with('@') {
+        package_name.split('/').next()
+    } else {
+        None
+    };
+
+    let (registry_url, source, auth_token) = if let Some(url) = registry {
+        let url = gitlab::expand_registry(url)?.unwrap_or_else(|| url.to_string());
+        (
+            url.trim_end_matches('/').to_string(),
+            "--registry".to_string(),
+            None,
+        )
+    } else {
+        let npmrc = NpmrcConfig::load(project_dir)?;
+        let url = npmrc.registry_url(scope);
+        let source = npmrc_source(npmrc.registry_key(scope));
+        if let Some(scope) = scope
+            && let Some(similar) = npmrc.similar_scope(scope)
+        {
+            eprintln!(
+                "warning: .npmrc has no {scope}:registry, using {url} (did you mean {similar}:registry?)"
+            );
+        }
+        let token = npmrc
+            .auth_token(&url)
+            .or_else(|| keychain::token(&url, verbose));
+        (url, source, token)
+    };
+
+    // GitLab endpoints also take the CI job token or a private token
+    let gitlab_auth = if auth_token.is_none() && gitlab::is_registry_url(&registry_url) {
+        gitlab::auth_header()
+    } else {
+        None
+    };
+
+    if verbose {
+        eprintln!("[registry] type: npm");
+        eprintln!("[registry] url: {} ({})", redact(&registry_url), source);
+        eprintln!(
+            "[registry] auth: {}",
+            match (&auth_token, &gitlab_auth) {
+                (Some(_), _) => "token",
+                (None, Some((header, _))) => header,
+                (None, None) => "none",
+            }
+        );
+    }
+
+    let mut client = RegistryClient::new(&registry_url, auth_token);
+    if let Some((header, value)) = gitlab_auth {
+        client = client.with_auth_header(header, value);
+    }
+    Ok(client)
+}
+
+/// Where an npm registry URL came from, for verbose and JSON output.
+fn npmrc_source(key: Option<String>) -> String {
+    match key {
+        Some(key) => format!("npm config {}", key),
+        None => "npm default".to_string(),
+    }
+}
+
+/// The registry a lookup for `target` goes to and why, following the same
+/// dispatch as `query_registry` without querying anything.
+pub fn registry_route(
+    path: &Path,
+    target: &TargetFile,
+    registry: Option<&str>,
+    registry_type: Option<RegistryType>,
+) -> Result<(String, String)> {
+    let from_flag = |url: &str| (url.to_string(), "--registry".to_string());
+    if let Some(fixture) = registry.filter(|r| r.starts_with("file://")) {
+        return Ok(from_flag(fixture));
+    }
+    if registry_type == Some(RegistryType::GithubTags) {
+        let repo = match registry {
+            Some(repo) => repo.to_string(),
+            None => std::env::var("GITHUB_REPOSITORY").unwrap_or_default(),
+        };
+        return Ok((format!("github:{}", repo), "GitHub tags".to_string()));
+    }
+    if target.is_vscode() && registry_type.is_none() {
+        return Ok(match registry {
+            None => ("VS Code Marketplace".to_string(), "default".to_string()),
+            Some("open-vsx") => from_flag(OPEN_VSX_URL),
+            Some(url) => from_flag(url),
+        });
+    }
+    if registry_type.map_or(target.is_cargo(), |t| t == RegistryType::Crates) {
+        return Ok(match registry {
+            Some(url) => from_flag(url),
+            None => ("https://crates.io".to_string(), "default".to_string()),
+        });
+    }
+    if let Some(url) = registry {
+        let url = gitlab::expand_registry(url)?.unwrap_or_else(|| url.to_string());
+        return Ok(from_flag(url.trim_end_matches('/')));
+    }
+
+    let project_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let npmrc = NpmrcConfig::load(project_dir)?;
+    let scope = target
+        .package_name
+        .starts_with('@')
+        .then(|| target.package_name.split('/').next())
+        .flatten();
+    Ok((
+        npmrc.registry_url(scope),
+        npmrc_source(npmrc.registry_key(scope)),
+    ))
+}
+
+/// Query the registry that publishes `target`: crates.io for Cargo.toml,
+/// otherwise the npm registry resolved from .npmrc (scoped registries and
+/// auth tokens included). `registry` overrides the URL.
+pub fn query_registry(
+    path: &Path,
+    target: &TargetFile,
+    registry: Option<&str>,
+    registry_type: Option<RegistryType>,
+    config: &Config,
+    verbose: bool,
+    timings: &mut Timings,
+) -> Result<PackageInfo> {
+    // Local fixture: no network, works for every target type
+    if let Some(fixture) = registry.and_then(|r| r.strip_prefix("file://")) {
+        return registry::read_fixture(Path::new(fixture), verbose);
+    }
+
+    if registry_type == Some(RegistryType::GithubTags) {
+        let repo = match registry {
+            Some(repo) => repo.to_string(),
+            None => std::env::var("GITHUB_REPOSITORY")
+                .context("--registry-type github-tags needs --registry owner/repo")?,
+        };
+        if verbose {
+            eprintln!("[registry] type: GitHub tags ({})", repo);
+        }
+        #[cfg(not(feature = "network"))]
+        return Err(crate::error::not_built("network"));
+        #[cfg(feature = "network")]
+        return GitHubClient::new(&repo)?.get_package(verbose);
+    }
+
+    if target.package_name.is_empty() {
+        bail!(
+            "{} has no package name for the registry lookup; pass --package-name or set publish-name in oneup.toml",
+            path.display()
+        );
+    }
+
+    if target.is_vscode() && registry_type.is_none() {
+        #[cfg(not(feature = "network"))]
+        return Err(crate::error::not_built("network"));
+        // Marketplace by default; --registry open-vsx (or a URL) for Open VSX
+        #[cfg(feature = "network")]
+        return match registry {
+            None => {
+                if verbose {
+                    eprintln!("[registry] type: VS Code Marketplace");
+                }
+                MarketplaceClient::new().get_package(&target.package_name, verbose)
+            }
+            Some(url) => {
+                let url = if url == "open-vsx" { OPEN_VSX_URL } else { url };
+                if verbose {
+                    eprintln!("[registry] type: Open VSX");
+                }
+                OpenVsxClient::new(url).get_package(&target.package_name, verbose)
+            }
+        };
+    }
+
+    if registry_type.map_or(target.is_cargo(), |t| t == RegistryType::Crates) {
+        if verbose {
+            eprintln!("[registry] type: crates.io");
+        }
+
+        #[cfg(not(feature = "crates"))]
+        return Err(crate::error::not_built("crates"));
+        #[cfg(feature = "crates")]
+        return CratesIoClient::new(registry).get_package(&target.package_name, verbose);
+    }
+
+    query_npm(path, target, registry, config, verbose, timings)
+}
+
+#[cfg(not(feature = "npm"))]
+fn query_npm(
+    _: &Path,
+    _: &TargetFile,
+    _: Option<&str>,
+    _: &Config,
+    _: bool,
+    _: &mut Timings,
+) -> Result<PackageInfo> {
+    Err(crate::error::not_built("npm"))
+}
+
+/// The npm registry from .npmrc, then the configured mirrors if it fails;
+/// versions from every `publish-registries` entry are added in.
+#[cfg(feature = "npm")]
+fn query_npm(
+    path: &Path,
+    target: &TargetFile,
+    registry: Option<&str>,
+    config: &Config,
+    verbose: bool,
+    timings: &mut Timings,
+) -> Result<PackageInfo> {
+    let project_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let client = npm_client(project_dir, &target.package_name, registry, verbose)?
+        .with_max_response_mb(config.max_response_mb);
+    timings.lap("npmrc");
+    let mut result = client.get_package(&target.package_name, verbose);
+
+    // Read-only lookups are safe to retry against mirrors, in order
+    let mut tried = client.registry_url().to_string();
+    for mirror in &config.registry_mirrors {
+        let Err(err) = &result else {
+            break;
+        };
+        let url = mirror.trim_end_matches('/').to_string();
+        eprintln!(
+            "[registry] {}",
+            redact(&format!("{} failed: {:#}; trying {}", tried, err, url))
+        );
+        let auth_token = NpmrcConfig::load(project_dir)?
+            .auth_token(&url)
+            .or_else(|| keychain::token(&url, verbose));
+        result = RegistryClient::new(&url, auth_token)
+            .with_max_response_mb(config.max_response_mb)
+            .get_package(&target.package_name, verbose);
+        if result.is_ok() {
+            eprintln!("[registry] answered by mirror {}", redact(&url));
+        }
+        tried = url;
+    }
+
+    // The package is also published elsewhere: versions from every registry
+    // count, so the next version is free on all of them
+    let mut info = result?;
+    for url in &config.publish_registries {
+        let url = url.trim_end_matches('/').to_string();
+        let auth_token = NpmrcConfig::load(project_dir)?
+            .auth_token(&url)
+            .or_else(|| keychain::token(&url, verbose));
+        let other = RegistryClient::new(&url, auth_token)
+            .with_max_response_mb(config.max_response_mb)
+            .get_package(&target.package_name, verbose)
+            .with_context(|| format!("publish registry {} failed", url))?;
+        if let (
+            PackageInfo::Found { latest, .. },
+            PackageInfo::Found {
+                latest: other_latest,
+                ..
+            },
+        ) = (&info, &other)
+            && latest != other_latest
+        {
+            eprintln!(
+                "warning: registries are out of step: {} has latest {}, {} has {}",
+                redact(&tried),
+                latest,
+                redact(&url),
+                other_latest
+            );
+        }
+        if verbose && matches!(other, PackageInfo::NotFound) {
+            eprintln!("[registry] not yet published to {}", redact(&url));
+        }
+        info = info.merge(other);
+    }
+    Ok(info)
 }
 
 fn detect_targets() -> Result<Vec<PathBuf>> {
@@ -147,13 +909,6 @@ fn detect_targets() -> Result<Vec<PathBuf>> {
     }
 }
 
-fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
-    let parse = |s: &str| -> Vec<u64> {
-        s.split('.').filter_map(|p| p.parse().ok()).collect()
-    };
-    parse(a).cmp(&parse(b))
-}
-
 /// Bump logic:
 ///
 /// With MICRO:
@@ -167,85 +922,423 @@ fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
 ///   2. Check if it already exists in registry
 ///   3. If exists → no change (already current)
 ///   4. If not → use today's version
-fn determine_version(
-    info: PackageInfo,
+///
+/// Versions in the skip list are hopped over by advancing MICRO.
+pub fn determine_version(
+    info: &PackageInfo,
     _package_name: &str,
     fmt: &VersionFormat,
+    skip: &[Skip],
+    clock_skew_hours: u64,
     verbose: bool,
 ) -> Result<String> {
-    match info {
-        PackageInfo::NotFound => {
-            let version = fmt.build_version(0);
-            if verbose {
-                eprintln!("[bump] package not in registry, starting at {}", version);
+    let versions: &[String] = match info {
+        PackageInfo::NotFound => &[],
+        PackageInfo::Found {
+            versions, latest, ..
+        } => {
+            // Warn if registry latest is ahead of today, beyond what a
+            // publisher in an earlier-dated timezone explains
+            if let Some(latest_values) = fmt.extract_values(latest)
+                && fmt.ahead_of_today(&latest_values, clock_skew_hours)
+            {
+                eprintln!(
+                    "warning: registry latest {} is ahead of current date prefix by more than the {}h clock-skew window{}",
+                    latest,
+                    clock_skew_hours,
+                    info.published_at(latest)
+                        .map(|t| format!(" (published {})", t))
+                        .unwrap_or_default()
+                );
             }
-            Ok(version)
-        }
-        PackageInfo::Found { versions, latest } => {
-            // Warn if registry latest is ahead of today
-            if let Some(latest_values) = fmt.extract_values(&latest) {
-                if fmt.ahead_of_today(&latest_values) {
-                    eprintln!(
-                        "warning: registry latest {} is ahead of current date prefix",
-                        latest
-                    );
+            versions
+        }
+    };
+
+    let gaps = micro_gaps(fmt, versions, skip);
+    if !gaps.is_empty() {
+        let missing: Vec<String> = gaps.iter().map(|m| fmt.build_version(*m)).collect();
+        eprintln!(
+            "warning: today's releases are missing {} (an unpublished or failed release?)",
+            missing.join(", ")
+        );
+    }
+
+    let decision = resolve::decide(fmt, versions, skip)?;
+    if verbose {
+        if let Some(max) = decision.max_micro {
+            for micro in (0..=max).filter(|m| !gaps.contains(m)) {
+                let version = fmt.build_version(micro);
+                if let Some(time) = info.published_at(&version) {
+                    eprintln!("[bump] {} published {}", version, time);
                 }
             }
+        }
+        for version in &decision.skipped {
+            eprintln!("[bump] {} is in the skip list, skipping", version);
+        }
+        let version = &decision.version;
+        match (info, decision.max_micro) {
+            (PackageInfo::NotFound, _) => {
+                eprintln!("[bump] package not in registry, starting at {}", version)
+            }
+            _ if !fmt.has_micro() && decision.exists => {
+                eprintln!("[bump] {} already exists in registry, no change", version)
+            }
+            _ if !fmt.has_micro() => eprintln!("[bump] new period → {}", version),
+            (_, Some(m)) => eprintln!(
+                "[bump] highest MICRO for today's prefix: {} → next: {}",
+                m, version
+            ),
+            (_, None) => eprintln!("[bump] no versions match today's prefix → {}", version),
+        }
+    }
+    Ok(decision.version)
+}
 
-            if fmt.has_micro() {
-                // With MICRO: find highest micro for today's prefix, increment
-                let mut max_micro: Option<u64> = None;
-
-                for v in &versions {
-                    if let Some(values) = fmt.extract_values(v) {
-                        if fmt.matches_today(&values) {
-                            if let Some(micro) = fmt.micro_value(&values) {
-                                max_micro = Some(max_micro.map_or(micro, |m: u64| m.max(micro)));
-                            }
-                        }
-                    }
-                }
+/// Next version for the primary target: a PR preview, or the CalVer
+/// version (registry or external MICRO) plus branch channel and +SHA.
+fn compute_version(
+    args: &VersionArgs,
+    fmt: &VersionFormat,
+    config: &Config,
+    info: Option<&PackageInfo>,
+    package_name: &str,
+    project_dir: &Path,
+) -> Result<String> {
+    // Pull request previews: 0.0.0-pr<number>.<n>
+    if let Some(pr) = pull_request(args)? {
+        let info = info.context(
+            "--pr needs the registry to number previews \
+             and cannot be combined with --micro-source git-count",
+        )?;
+        let version = prerelease_version(info, "0.0.0", &format!("pr{pr}"));
+        if args.verbose {
+            eprintln!("[bump] pull request #{} → {}", pr, version);
+        }
+        return Ok(version);
+    }
 
-                let next_micro = match max_micro {
-                    Some(m) => m + 1,
-                    None => 0,
-                };
+    // Hotfixes: <released version>-<segment>.<n>
+    if args.hotfix {
+        let info = info.context(
+            "--hotfix needs the registry to find the released version \
+             and cannot be combined with --micro-source git-count",
+        )?;
+        let PackageInfo::Found { latest, .. } = info else {
+            bail!("--hotfix: package has no released version to patch");
+        };
+        let released = release_base(latest);
+        let version = prerelease_version(info, released, &config.hotfix_segment);
+        if args.verbose {
+            eprintln!("[bump] hotfix of {} → {}", released, version);
+        }
+        return Ok(version);
+    }
 
-                let version = fmt.build_version(next_micro);
+    let new_version = match (resolve_micro(args, fmt, project_dir)?, info) {
+        (Some(micro), info) => fixed_micro_version(info, fmt, micro, &config.skip, args.verbose)?,
+        (None, Some(info)) => determine_version(
+            info,
+            package_name,
+            fmt,
+            &config.skip,
+            config.clock_skew_hours,
+            args.verbose,
+        )?,
+        (None, None) => unreachable!("registry is only skipped when MICRO comes from git"),
+    };
 
-                if verbose {
-                    match max_micro {
-                        Some(m) => eprintln!(
-                            "[bump] highest MICRO for today's prefix: {} → next: {}",
-                            m, version
-                        ),
-                        None => eprintln!("[bump] no versions match today's prefix → {}", version),
-                    }
-                }
+    // Prerelease channel for the current branch ([channels] in oneup.toml)
+    let new_version = match branch_channel(config, project_dir, args.verbose)? {
+        Some(channel) => {
+            let info = info.context(
+                "prerelease channels need the registry to number builds \
+                 and cannot be combined with --micro-source git-count",
+            )?;
+            let version = prerelease_version(info, &new_version, channel);
+            if args.verbose {
+                eprintln!("[bump] channel {} → {}", channel, version);
+            }
+            version
+        }
+        None => new_version,
+    };
 
-                Ok(version)
-            } else {
-                // Without MICRO: today's date version, no-op if already exists
-                let version = fmt.build_version(0);
-
-                let exists = versions.iter().any(|v| {
-                    if let Some(values) = fmt.extract_values(v) {
-                        fmt.matches_today(&values)
-                    } else {
-                        false
-                    }
-                });
-
-                if exists {
-                    if verbose {
-                        eprintln!("[bump] {} already exists in registry, no change", version);
-                    }
-                } else if verbose {
-                    eprintln!("[bump] new period → {}", version);
-                }
+    // Append the commit hash as build metadata ("+SHA" in the format)
+    if fmt.sha {
+        Ok(format!("{}+{}", new_version, git::short_sha(project_dir)?))
+    } else {
+        Ok(new_version)
+    }
+}
 
-                Ok(version)
+/// Pull request number for preview versions: `--pr <n>`, or `--pr` alone
+/// to read it from the CI environment.
+fn pull_request(args: &VersionArgs) -> Result<Option<u64>> {
+    match args.pr.as_deref() {
+        None => Ok(None),
+        Some("auto") => {
+            let pr = CiInfo::detect()
+                .and_then(|ci| ci.pull_request)
+                .context("--pr: not running in a pull request pipeline")?;
+            Ok(Some(pr))
+        }
+        Some(n) => n
+            .parse()
+            .map(Some)
+            .with_context(|| format!("--pr: invalid pull request number '{}'", n)),
+    }
+}
+
+/// MICRO chosen outside the registry (--micro or --micro-source), if any.
+fn resolve_micro(args: &VersionArgs, fmt: &VersionFormat, dir: &Path) -> Result<Option<u64>> {
+    if let Some(micro) = args.micro {
+        return Ok(Some(micro));
+    }
+
+    match args.micro_source {
+        MicroSource::Registry => Ok(None),
+        MicroSource::Ci => {
+            let number = CiInfo::detect().and_then(|ci| ci.run_number).context(
+                "--micro-source ci: no CI run number found \
+                 (GITHUB_RUN_NUMBER, CI_PIPELINE_IID, CIRCLE_BUILD_NUM, BUILDKITE_BUILD_NUMBER)",
+            )?;
+            Ok(Some(number))
+        }
+        MicroSource::GitCount => {
+            let since = format!("{} 00:00:00", fmt.period_start());
+            let count = git::commit_count_since(dir, &since)?;
+            if args.verbose {
+                eprintln!("[git] {} commits since {}", count, since);
             }
+            Ok(Some(count))
+        }
+    }
+}
+
+/// Build today's version with an externally chosen MICRO, making sure it
+/// still sorts above everything already published for today's prefix
+/// (when the registry was queried).
+fn fixed_micro_version(
+    info: Option<&PackageInfo>,
+    fmt: &VersionFormat,
+    micro: u64,
+    skip: &[Skip],
+    verbose: bool,
+) -> Result<String> {
+    if !fmt.has_micro() {
+        bail!("--micro and --micro-source require a format with MICRO");
+    }
+
+    let version = fmt.build_version(micro);
+
+    if skip.iter().any(|s| s.matches(&version, Some(micro))) {
+        bail!("version {} is in the skip list", version);
+    }
+
+    if let Some(PackageInfo::Found { versions, .. }) = info
+        && let Some(max) = max_micro_today(fmt, versions)
+        && micro <= max
+    {
+        return Err(anyhow!(
+            "MICRO {} is not above the highest published MICRO {} for today's prefix",
+            micro,
+            max
+        ))
+        .kind(ErrorKind::Conflict);
+    }
+
+    if verbose {
+        eprintln!("[bump] external MICRO {} → {}", micro, version);
+    }
+
+    Ok(version)
+}
+
+/// Prerelease channel configured for the branch being built, if any.
+/// The branch comes from the CI environment, falling back to git.
+fn branch_channel<'a>(config: &'a Config, dir: &Path, verbose: bool) -> Result<Option<&'a str>> {
+    if config.channels.is_empty() {
+        return Ok(None);
+    }
+
+    let Some(branch) = current_branch(dir)? else {
+        if verbose {
+            eprintln!("[channel] detached HEAD, no branch → stable");
+        }
+        return Ok(None);
+    };
+
+    let channel = config.channel_for(&branch);
+    if verbose {
+        eprintln!(
+            "[channel] branch {} → {}",
+            branch,
+            channel.unwrap_or("stable")
+        );
+    }
+    Ok(channel)
+}
+
+/// The branch being built: from the CI environment, falling back to git
+/// (None on a detached HEAD).
+pub fn current_branch(dir: &Path) -> Result<Option<String>> {
+    match CiInfo::detect().and_then(|ci| ci.branch) {
+        Some(branch) => Ok(Some(branch)),
+        None => git::current_branch(dir),
+    }
+}
+
+/// `<base>-<channel>.<n>` where n is one above the highest published
+/// prerelease of that base and channel (0 if none).
+fn prerelease_version(info: &PackageInfo, base: &str, channel: &str) -> String {
+    let prefix = format!("{base}-{channel}.");
+    let next = match info {
+        PackageInfo::NotFound => 0,
+        PackageInfo::Found { versions, .. } => versions
+            .iter()
+            .filter_map(|v| v.strip_prefix(&prefix))
+            .filter_map(|n| n.split('+').next()?.parse::<u64>().ok())
+            .max()
+            .map_or(0, |n| n + 1),
+    };
+    format!("{prefix}{next}")
+}
+
+/// Render `version` (built with `fmt`) in `target_fmt`, keeping its MICRO and
+/// any prerelease or build suffix. Versions not built from today's date
+/// (--pr previews, --hotfix) are returned unchanged.
+fn reformat_version(
+    fmt: &VersionFormat,
+    target_fmt: &VersionFormat,
+    version: &str,
+) -> Result<String> {
+    if target_fmt.sha {
+        bail!("+SHA is not supported in per-target formats");
+    }
+
+    let (core, suffix) = version.split_at(version.find(['-', '+']).unwrap_or(version.len()));
+    match fmt.extract_values(core) {
+        Some(values) if fmt.matches_today(&values) => {
+            let micro = fmt.micro_value(&values).unwrap_or(0);
+            Ok(format!("{}{}", target_fmt.build_version(micro), suffix))
+        }
+        _ => Ok(version.to_string()),
+    }
+}
+
+/// Version without its prerelease and build metadata, so a hotfix of a
+/// hotfix numbers on from the same release.
+fn release_base(version: &str) -> &str {
+    version.split(['-', '+']).next().unwrap_or(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(versions: &[&str]) -> PackageInfo {
+        PackageInfo::Found {
+            versions: versions.iter().map(|v| v.to_string()).collect(),
+            latest: versions.last().unwrap().to_string(),
+            published: Default::default(),
         }
     }
+
+    #[test]
+    fn primary_is_first_target_in_its_own_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, version: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(
+                &path,
+                format!(r#"{{"name": "app", "version": "{version}"}}"#),
+            )
+            .unwrap();
+            path
+        };
+        let daily = write("daily.json", "2026.2.17");
+        let a = write("a.json", "26.2.1");
+        let b = write("b.json", "26.2.3");
+        let mut config = Config::default();
+        config.targets.push((
+            daily.clone(),
+            TargetConfig {
+                format: Some("YYYY.MM.DD".to_string()),
+                ..Default::default()
+            },
+        ));
+
+        // Neither the higher 2026.2.17 nor the higher 26.2.3 takes over
+        let targets =
+            load_targets(&[daily.clone(), a.clone(), b.clone()], None, &config, None).unwrap();
+        assert_eq!(targets[0].0, a);
+        assert_eq!(targets[2].0, daily);
+
+        let targets = load_targets(&[daily, a, b.clone()], Some(&b), &config, None).unwrap();
+        assert_eq!(targets[0].0, b);
+    }
+
+    #[test]
+    fn below_published_checks_full_history() {
+        let info = found(&["2026.9.1", "26.10.3"]);
+        let problem = below_published(&info, "26.10.4").unwrap();
+        assert!(
+            problem
+                .starts_with("26.10.4 does not sort above 1 published release(s), up to 2026.9.1")
+        );
+        assert_eq!(below_published(&info, "2026.10.0"), None);
+        assert_eq!(below_published(&info, "26.10.3"), None);
+        assert_eq!(below_published(&PackageInfo::NotFound, "26.10.4"), None);
+    }
+
+    #[test]
+    fn prerelease_starts_at_zero() {
+        assert_eq!(
+            prerelease_version(&PackageInfo::NotFound, "26.2.5", "beta"),
+            "26.2.5-beta.0"
+        );
+        assert_eq!(
+            prerelease_version(&found(&["26.2.4"]), "26.2.5", "beta"),
+            "26.2.5-beta.0"
+        );
+    }
+
+    #[test]
+    fn prerelease_increments_per_base_and_channel() {
+        let info = found(&[
+            "26.2.5-beta.0",
+            "26.2.5-beta.3",
+            "26.2.5-rc.7",
+            "26.2.4-beta.9",
+        ]);
+        assert_eq!(prerelease_version(&info, "26.2.5", "beta"), "26.2.5-beta.4");
+        assert_eq!(prerelease_version(&info, "26.2.5", "rc"), "26.2.5-rc.8");
+    }
+
+    #[test]
+    fn reformat_keeps_micro_and_suffix() {
+        let fmt = VersionFormat::parse("YY.MM.MICRO").unwrap();
+        let daily = VersionFormat::parse("YYYY.MM.DD.MICRO").unwrap();
+        let version = format!("{}-beta.2", fmt.build_version(7));
+        assert_eq!(
+            reformat_version(&fmt, &daily, &version).unwrap(),
+            format!("{}-beta.2", daily.build_version(7))
+        );
+        assert_eq!(
+            reformat_version(&fmt, &daily, "0.0.0-pr12.0").unwrap(),
+            "0.0.0-pr12.0"
+        );
+
+        let sha = VersionFormat::parse("YY.MM.MICRO+SHA").unwrap();
+        assert!(reformat_version(&fmt, &sha, &version).is_err());
+    }
+
+    #[test]
+    fn release_base_strips_prerelease_and_metadata() {
+        assert_eq!(release_base("26.2.5"), "26.2.5");
+        assert_eq!(release_base("26.2.5-hotfix.1"), "26.2.5");
+        assert_eq!(release_base("26.2.5+a1b2c3d"), "26.2.5");
+    }
 }
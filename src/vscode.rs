@@ -0,0 +1,243 @@
+use anyhow::{Result, bail};
+
+// The Marketplace and Open VSX clients; version rules and the Open VSX URL
+// are needed without the network too
+#[cfg(feature = "network")]
+use {
+    crate::error::{ErrorKind, ResultExt},
+    crate::http,
+    crate::redact::redact,
+    crate::registry::PackageInfo,
+    anyhow::{Context, anyhow},
+    serde_json::json,
+};
+
+#[cfg(feature = "network")]
+const MARKETPLACE_URL: &str =
+    "https://marketplace.visualstudio.com/_apis/public/gallery/extensionquery";
+pub const OPEN_VSX_URL: &str = "https://open-vsx.org";
+
+/// Marketplace largest allowed version component (32-bit signed integer)
+const MAX_COMPONENT: u64 = i32::MAX as u64;
+
+/// Visual Studio Marketplace gallery API
+#[cfg(feature = "network")]
+pub struct MarketplaceClient {
+    http: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "network")]
+impl MarketplaceClient {
+    pub fn new() -> Self {
+        Self {
+            http: http::client(),
+        }
+    }
+
+    /// Versions of `publisher.name`, newest first as returned by the gallery.
+    pub fn get_package(&self, extension_id: &str, verbose: bool) -> Result<PackageInfo> {
+        if verbose {
+            eprintln!("[registry] POST {} ({})", MARKETPLACE_URL, extension_id);
+        }
+
+        // filterType 7 = extension name, flags 1 = include versions
+        let query = json!({
+            "filters": [{
+                "criteria": [{ "filterType": 7, "value": extension_id }],
+                "pageNumber": 1,
+                "pageSize": 1,
+            }],
+            "flags": 1,
+        });
+
+        let resp = self
+            .http
+            .post(MARKETPLACE_URL)
+            .header("Accept", "application/json;api-version=3.0-preview.1")
+            .json(&query)
+            .send()
+            .with_context(|| {
+                format!(
+                    "failed to query the VS Code Marketplace for {}",
+                    extension_id
+                )
+            })
+            .kind(ErrorKind::Network)?;
+
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "failed to query the VS Code Marketplace: HTTP {}",
+                resp.status()
+            ))
+            .kind(ErrorKind::Network);
+        }
+
+        let body: serde_json::Value = resp
+            .json()
+            .context("failed to parse VS Code Marketplace response")
+            .kind(ErrorKind::Network)?;
+
+        let Some(extension) = body.pointer("/results/0/extensions/0") else {
+            if verbose {
+                eprintln!("[registry] extension not found");
+            }
+            return Ok(PackageInfo::NotFound);
+        };
+
+        let versions: Vec<String> = extension
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.get("version").and_then(|n| n.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let latest = versions
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "0.0.0".to_string());
+        let published = extension
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| {
+                        let version = v.get("version")?.as_str()?;
+                        let updated = v.get("lastUpdated")?.as_str()?;
+                        Some((version.to_string(), updated.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if verbose {
+            eprintln!("[registry] latest: {}", latest);
+            eprintln!("[registry] total versions: {}", versions.len());
+        }
+
+        Ok(PackageInfo::Found {
+            versions,
+            latest,
+            published,
+        })
+    }
+}
+
+/// Open VSX registry API (open-vsx.org or a self-hosted instance)
+#[cfg(feature = "network")]
+pub struct OpenVsxClient {
+    http: reqwest::blocking::Client,
+    registry_url: String,
+}
+
+#[cfg(feature = "network")]
+impl OpenVsxClient {
+    pub fn new(registry_url: &str) -> Self {
+        Self {
+            http: http::client(),
+            registry_url: registry_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn get_package(&self, extension_id: &str, verbose: bool) -> Result<PackageInfo> {
+        let (namespace, name) = extension_id.split_once('.').with_context(|| {
+            format!(
+                "invalid extension id {} (expected publisher.name)",
+                extension_id
+            )
+        })?;
+        let url = format!("{}/api/{}/{}", self.registry_url, namespace, name);
+
+        if verbose {
+            eprintln!("[registry] GET {}", redact(&url));
+        }
+
+        let resp = self
+            .http
+            .get(&url)
+            .send()
+            .with_context(|| format!("failed to query Open VSX for {}", extension_id))
+            .kind(ErrorKind::Network)?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            if verbose {
+                eprintln!("[registry] extension not found (404)");
+            }
+            return Ok(PackageInfo::NotFound);
+        }
+
+        if !resp.status().is_success() {
+            return Err(anyhow!("failed to query Open VSX: HTTP {}", resp.status()))
+                .kind(ErrorKind::Network);
+        }
+
+        let body: serde_json::Value = resp
+            .json()
+            .context("failed to parse Open VSX response")
+            .kind(ErrorKind::Network)?;
+
+        let latest = body
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("0.0.0")
+            .to_string();
+
+        // allVersions maps each version (plus aliases like "latest") to its URL
+        let versions: Vec<String> = body
+            .get("allVersions")
+            .and_then(|v| v.as_object())
+            .map(|obj| {
+                obj.keys()
+                    .filter(|k| k.starts_with(|c: char| c.is_ascii_digit()))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if verbose {
+            eprintln!("[registry] latest: {}", latest);
+            eprintln!("[registry] total versions: {}", versions.len());
+        }
+
+        Ok(PackageInfo::Found {
+            versions,
+            latest,
+            published: Default::default(),
+        })
+    }
+}
+
+/// The Marketplace only accepts `major.minor.patch` with each part a 32-bit
+/// integer; prerelease tags are not allowed (pre-releases are a publish flag).
+pub fn check_version(version: &str) -> Result<()> {
+    let parts: Vec<&str> = version.split('.').collect();
+    let valid = parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| p.parse::<u64>().is_ok_and(|n| n <= MAX_COMPONENT));
+    if !valid {
+        bail!(
+            "{} is not a valid VS Code extension version (exactly three integers, \
+             no prerelease or build suffix); publish pre-releases with `vsce publish --pre-release`",
+            version
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_version_rules() {
+        assert!(check_version("26.2.5").is_ok());
+        assert!(check_version("2026.2.17.5").is_err());
+        assert!(check_version("26.2").is_err());
+        assert!(check_version("26.2.5-beta.1").is_err());
+        assert!(check_version("26.2.5+abc").is_err());
+        assert!(check_version("26.2.3000000000").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use wasm_bindgen::prelude::*;
+
+use crate::format::VersionFormat;
+use crate::resolve::{self, Skip};
+
+/// Next version for `format` given the versions already published, as of
+/// `today` (YYYY-MM-DD) — the decision `oneup version` makes once it has the
+/// registry's answer. `skip` entries are versions, or MICRO values as
+/// plain numbers.
+#[wasm_bindgen(js_name = nextVersion)]
+pub fn next_version(
+    format: &str,
+    versions: Vec<String>,
+    today: &str,
+    skip: Vec<String>,
+) -> Result<String, JsError> {
+    let today = chrono::NaiveDate::parse_from_str(today, "%Y-%m-%d")
+        .map_err(|_| JsError::new(&format!("invalid date '{}': expected YYYY-MM-DD", today)))?;
+    let fmt = VersionFormat::parse(format)
+        .map_err(|err| JsError::new(&format!("{:#}", err)))?
+        .with_today(today);
+    let skip: Vec<Skip> = skip
+        .into_iter()
+        .map(|entry| match entry.parse() {
+            Ok(micro) => Skip::Micro(micro),
+            Err(_) => Skip::Version(entry),
+        })
+        .collect();
+    resolve::decide(&fmt, &versions, &skip)
+        .map(|decision| decision.version)
+        .map_err(|err| JsError::new(&format!("{:#}", err)))
+}
+
+/// -1, 0 or 1 as `a` sorts before, equal to or after `b`.
+#[wasm_bindgen(js_name = compareVersions)]
+pub fn compare_versions(a: &str, b: &str) -> i32 {
+    resolve::compare_versions(a, b) as i32
+}
@@ -0,0 +1,41 @@
+use anyhow::{Result, bail};
+
+/// Chrome Web Store largest allowed version component
+const MAX_COMPONENT: u64 = 65535;
+
+/// WebExtension versions are one to four period-separated integers, each at
+/// most 65535 and without leading zeros (Chrome Web Store rules, which
+/// Firefox Add-ons also accepts).
+pub fn check_version(version: &str) -> Result<()> {
+    let parts: Vec<&str> = version.split('.').collect();
+    let valid = parts.len() <= 4
+        && parts.iter().all(|p| {
+            (p.len() == 1 || !p.starts_with('0'))
+                && p.parse::<u64>().is_ok_and(|n| n <= MAX_COMPONENT)
+        });
+    if !valid {
+        bail!(
+            "{} is not a valid browser extension version (at most four period-separated \
+             integers, each 0-65535 without leading zeros, no prerelease or build suffix); \
+             use a format like YY.MM.MICRO or YYYY.MM.DD.MICRO without zero padding",
+            version
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_version_rules() {
+        assert!(check_version("26.2.5").is_ok());
+        assert!(check_version("2026.2.17.5").is_ok());
+        assert!(check_version("26.2.17.5.1").is_err());
+        assert!(check_version("20260217.5").is_err());
+        assert!(check_version("26.02.5").is_err());
+        assert!(check_version("26.2.5-beta.1").is_err());
+        assert!(check_version("26.2.5+abc").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::fmt;
+
+/// A weekly window in which releases are allowed,
+/// e.g. "Mon-Thu 09:00-17:00 Europe/Berlin"
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseWindow {
+    spec: String,
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+    tz: Tz,
+}
+
+impl ReleaseWindow {
+    /// Parse "<days> <HH:MM>-<HH:MM> [timezone]". Days are a comma-separated
+    /// list of days or day ranges ("Mon-Fri", "Sat,Sun", "Fri-Mon");
+    /// the timezone defaults to UTC.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let parts: Vec<&str> = spec.split_whitespace().collect();
+        let (days, times, tz) = match parts.as_slice() {
+            [days, times] => (*days, *times, "UTC"),
+            [days, times, tz] => (*days, *times, *tz),
+            _ => bail!(
+                "invalid release window '{}' (expected e.g. \"Mon-Thu 09:00-17:00 Europe/Berlin\")",
+                spec
+            ),
+        };
+
+        let mut weekdays = Vec::new();
+        for item in days.split(',') {
+            match item.split_once('-') {
+                Some((from, to)) => {
+                    let mut day = parse_day(from)?;
+                    let to = parse_day(to)?;
+                    weekdays.push(day);
+                    while day != to {
+                        day = day.succ();
+                        weekdays.push(day);
+                    }
+                }
+                None => weekdays.push(parse_day(item)?),
+            }
+        }
+
+        let (start, end) = times
+            .split_once('-')
+            .with_context(|| format!("invalid time range '{}' (expected HH:MM-HH:MM)", times))?;
+        let start = parse_time(start)?;
+        let end = parse_time(end)?;
+        if end <= start {
+            bail!(
+                "invalid time range '{}': end must be after start (windows cannot span midnight)",
+                times
+            );
+        }
+
+        let tz: Tz = tz
+            .parse()
+            .map_err(|_| anyhow::anyhow!("unknown timezone '{}'", tz))?;
+
+        Ok(Self {
+            spec: spec.to_string(),
+            days: weekdays,
+            start,
+            end,
+            tz,
+        })
+    }
+
+    /// Whether the instant `now` falls inside this window (end exclusive).
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let local = self.tz.from_utc_datetime(&now.naive_utc());
+        let time = local.time();
+        self.days.contains(&local.weekday()) && time >= self.start && time < self.end
+    }
+}
+
+impl fmt::Display for ReleaseWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.spec)
+    }
+}
+
+fn parse_day(s: &str) -> Result<Weekday> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("invalid weekday '{}' (expected Mon, Tue, ...)", s))
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .with_context(|| format!("invalid time '{}' (expected HH:MM)", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parse_day_ranges() {
+        let window = ReleaseWindow::parse("Mon-Thu 09:00-17:00").unwrap();
+        assert_eq!(
+            window.days,
+            vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu]
+        );
+        assert_eq!(window.tz, Tz::UTC);
+
+        let window = ReleaseWindow::parse("Fri-Mon,Wed 00:00-23:59 UTC").unwrap();
+        assert_eq!(
+            window.days,
+            vec![
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+                Weekday::Mon,
+                Weekday::Wed
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_specs() {
+        assert!(ReleaseWindow::parse("Mon-Thu").is_err());
+        assert!(ReleaseWindow::parse("Someday 09:00-17:00").is_err());
+        assert!(ReleaseWindow::parse("Mon 9-17").is_err());
+        assert!(ReleaseWindow::parse("Mon 17:00-09:00").is_err());
+        assert!(ReleaseWindow::parse("Mon 09:00-17:00 Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn contains_uses_window_timezone() {
+        let window = ReleaseWindow::parse("Mon-Thu 09:00-17:00 Europe/Berlin").unwrap();
+        // Thursday 2026-02-05, Berlin is UTC+1 in winter
+        assert!(window.contains(utc("2026-02-05T08:00:00Z")));
+        assert!(!window.contains(utc("2026-02-05T07:59:00Z")));
+        assert!(!window.contains(utc("2026-02-05T16:00:00Z")));
+        // Friday
+        assert!(!window.contains(utc("2026-02-06T10:00:00Z")));
+    }
+}
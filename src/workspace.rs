@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::glob_match;
+
+/// Manifests of the packages in the workspace at `dir`, relative to it: the
+/// root package.json and Cargo.toml when they define a package, then the
+/// members of npm `workspaces` and Cargo `[workspace] members`.
+pub fn packages(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut manifests = Vec::new();
+
+    let package_json = dir.join("package.json");
+    if package_json.exists() {
+        let content = std::fs::read_to_string(&package_json)
+            .with_context(|| format!("failed to read {}", package_json.display()))?;
+        let doc: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("failed to parse {}: invalid JSON", package_json.display()))?;
+        if doc.get("version").is_some() {
+            manifests.push(PathBuf::from("package.json"));
+        }
+        // "workspaces": [...] or, with Yarn, "workspaces": {"packages": [...]}
+        let workspaces = doc
+            .get("workspaces")
+            .map(|w| w.get("packages").unwrap_or(w));
+        let patterns: Vec<&str> = workspaces
+            .and_then(|w| w.as_array())
+            .map(|a| a.iter().filter_map(|p| p.as_str()).collect())
+            .unwrap_or_default();
+        let (exclude, include): (Vec<&str>, Vec<&str>) =
+            patterns.into_iter().partition(|p| p.starts_with('!'));
+        let exclude: Vec<&str> = exclude.iter().map(|p| &p[1..]).collect();
+        manifests.extend(members(dir, &include, &exclude, "package.json")?);
+    }
+
+    let cargo_toml = dir.join("Cargo.toml");
+    if cargo_toml.exists() {
+        let content = std::fs::read_to_string(&cargo_toml)
+            .with_context(|| format!("failed to read {}", cargo_toml.display()))?;
+        let doc: toml_edit::DocumentMut = content
+            .parse()
+            .with_context(|| format!("failed to parse {}: invalid TOML", cargo_toml.display()))?;
+        if doc.get("package").is_some() {
+            manifests.push(PathBuf::from("Cargo.toml"));
+        }
+        let list = |key: &str| -> Vec<&str> {
+            doc.get("workspace")
+                .and_then(|w| w.get(key))
+                .and_then(|m| m.as_array())
+                .map(|a| a.iter().filter_map(|p| p.as_str()).collect())
+                .unwrap_or_default()
+        };
+        manifests.extend(members(
+            dir,
+            &list("members"),
+            &list("exclude"),
+            "Cargo.toml",
+        )?);
+    }
+
+    manifests.dedup();
+    Ok(manifests)
+}
+
+/// `manifest` in every member directory matched by `include` and not by
+/// `exclude`. A `*` matches within one path segment (`packages/*`,
+/// `crates/oneup-*`); `**` is not supported.
+fn members(dir: &Path, include: &[&str], exclude: &[&str], manifest: &str) -> Result<Vec<PathBuf>> {
+    let excluded: Vec<PathBuf> = exclude
+        .iter()
+        .map(|p| expand(dir, p))
+        .collect::<Result<Vec<_>>>()?
+        .concat();
+
+    let mut manifests = Vec::new();
+    for pattern in include {
+        for member in expand(dir, pattern)? {
+            let path = member.join(manifest);
+            if !excluded.contains(&member) && dir.join(&path).is_file() {
+                manifests.push(path);
+            }
+        }
+    }
+    Ok(manifests)
+}
+
+/// Directories under `dir` matching `pattern`, relative to `dir`, sorted.
+fn expand(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut matches = vec![PathBuf::new()];
+    for segment in pattern.trim_end_matches('/').split('/') {
+        if segment.is_empty() || segment == "." {
+            continue;
+        }
+        if !segment.contains('*') {
+            matches = matches.into_iter().map(|m| m.join(segment)).collect();
+            continue;
+        }
+        let mut next = Vec::new();
+        for m in &matches {
+            let Ok(entries) = std::fs::read_dir(dir.join(m)) else {
+                continue;
+            };
+            for entry in entries {
+                let entry = entry.with_context(|| format!("failed to list {}", m.display()))?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if entry.path().is_dir() && glob_match(segment, &name) {
+                    next.push(m.join(name));
+                }
+            }
+        }
+        next.sort();
+        matches = next;
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn npm_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{"private": true, "workspaces": ["packages/*", "!packages/internal", "tools/cli"]}"#,
+        );
+        write(root, "packages/b/package.json", "{}");
+        write(root, "packages/a/package.json", "{}");
+        write(root, "packages/internal/package.json", "{}");
+        write(root, "packages/docs/README.md", "");
+        write(root, "tools/cli/package.json", "{}");
+
+        assert_eq!(
+            packages(root).unwrap(),
+            vec![
+                PathBuf::from("packages/a/package.json"),
+                PathBuf::from("packages/b/package.json"),
+                PathBuf::from("tools/cli/package.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn cargo_workspace_with_root_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"1.0.0\"\n\n[workspace]\nmembers = [\"crates/app-*\"]\nexclude = [\"crates/app-old\"]\n",
+        );
+        write(root, "crates/app-core/Cargo.toml", "");
+        write(root, "crates/app-old/Cargo.toml", "");
+        write(root, "crates/other/Cargo.toml", "");
+        write(
+            root,
+            "package.json",
+            r#"{"name": "app", "version": "1.0.0"}"#,
+        );
+
+        assert_eq!(
+            packages(root).unwrap(),
+            vec![
+                PathBuf::from("package.json"),
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("crates/app-core/Cargo.toml"),
+            ]
+        );
+    }
+}
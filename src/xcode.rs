@@ -0,0 +1,172 @@
+use anyhow::{Context, Result, bail};
+use std::ops::Range;
+
+use crate::scan;
+
+const SHORT_VERSION_KEY: &str = "CFBundleShortVersionString";
+const BUNDLE_VERSION_KEY: &str = "CFBundleVersion";
+const MARKETING_VERSION: &str = "MARKETING_VERSION";
+
+/// Current `CFBundleShortVersionString` in an Info.plist.
+pub fn plist_version(content: &str) -> Option<&str> {
+    plist_value(content, SHORT_VERSION_KEY).map(|r| &content[r])
+}
+
+/// Set `CFBundleShortVersionString` and `CFBundleVersion` to `version`.
+/// Values that reference a build setting (`$(MARKETING_VERSION)`) are left
+/// for the project.pbxproj target to fill in.
+pub fn update_plist(content: &str, version: &str) -> Result<String> {
+    check_apple_version(version)?;
+
+    let short = plist_value(content, SHORT_VERSION_KEY)
+        .with_context(|| format!("no {} string found", SHORT_VERSION_KEY))?;
+    let mut edits = vec![short];
+    edits.extend(plist_value(content, BUNDLE_VERSION_KEY));
+
+    let mut edits: Vec<_> = edits
+        .into_iter()
+        .filter(|r| !content[r.clone()].starts_with("$("))
+        .collect();
+    edits.sort_by_key(|r| std::cmp::Reverse(r.start));
+
+    let mut output = content.to_string();
+    for range in edits {
+        output.replace_range(range, version);
+    }
+    Ok(output)
+}
+
+/// First `MARKETING_VERSION` in a project.pbxproj.
+pub fn pbxproj_version(content: &str) -> Option<&str> {
+    marketing_versions(content)
+        .into_iter()
+        .next()
+        .map(|r| &content[r])
+}
+
+/// Set every `MARKETING_VERSION = ...;` build setting to `version`.
+pub fn update_pbxproj(content: &str, version: &str) -> Result<String> {
+    check_apple_version(version)?;
+
+    let ranges = marketing_versions(content);
+    if ranges.is_empty() {
+        bail!("no {} build setting found", MARKETING_VERSION);
+    }
+
+    let mut output = content.to_string();
+    for range in ranges.into_iter().rev() {
+        output.replace_range(range, version);
+    }
+    Ok(output)
+}
+
+/// App Store versions are at most three period-separated integers.
+fn check_apple_version(version: &str) -> Result<()> {
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() > 3 || parts.iter().any(|p| p.parse::<u64>().is_err()) {
+        bail!(
+            "{} is not a valid App Store version (at most three period-separated integers, \
+             no prerelease or build suffix); use a format like YY.MM.MICRO",
+            version
+        );
+    }
+    Ok(())
+}
+
+/// Byte range of the `<string>` value following `<key>{key}</key>`.
+fn plist_value(content: &str, key: &str) -> Option<Range<usize>> {
+    let key_tag = format!("<key>{}</key>", key);
+    let after_key = content.find(&key_tag)? + key_tag.len();
+    let rest = &content[after_key..];
+    let open = rest.trim_start();
+    let open = open.strip_prefix("<string>")?;
+    let start = content.len() - open.len();
+    let len = open.find("</string>")?;
+    Some(start..start + len)
+}
+
+/// Byte ranges of the values of every `MARKETING_VERSION = value;`.
+fn marketing_versions(content: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    // Not the tail of a longer setting like INFOPLIST_MARKETING_VERSION
+    for start in scan::word_starts(content, MARKETING_VERSION, &['_']) {
+        let from = start + MARKETING_VERSION.len();
+        let rest = &content[from..];
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let start = content.len() - value.len();
+        let Some(len) = value.find(';') else {
+            continue;
+        };
+        // Values may be quoted: MARKETING_VERSION = "1.0";
+        let (start, len) = if value.starts_with('"') && len >= 2 {
+            (start + 1, len - 2)
+        } else {
+            (start, len)
+        };
+        ranges.push(start..start + len);
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>CFBundleShortVersionString</key>
+	<string>1.0</string>
+	<key>CFBundleVersion</key>
+	<string>1</string>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn plist_updates_both_keys() {
+        assert_eq!(plist_version(PLIST), Some("1.0"));
+        let updated = update_plist(PLIST, "26.2.5").unwrap();
+        assert!(updated.contains("<string>26.2.5</string>\n\t<key>CFBundleVersion</key>"));
+        assert!(updated.ends_with("<string>26.2.5</string>\n</dict>\n</plist>\n"));
+    }
+
+    #[test]
+    fn plist_keeps_build_setting_references() {
+        let content = PLIST.replace(
+            "<string>1.0</string>",
+            "<string>$(MARKETING_VERSION)</string>",
+        );
+        let updated = update_plist(&content, "26.2.5").unwrap();
+        assert!(updated.contains("$(MARKETING_VERSION)"));
+        assert!(updated.contains("<key>CFBundleVersion</key>\n\t<string>26.2.5</string>"));
+    }
+
+    #[test]
+    fn rejects_non_app_store_versions() {
+        assert!(update_plist(PLIST, "2026.2.17.5").is_err());
+        assert!(update_plist(PLIST, "26.2.5-beta.1").is_err());
+    }
+
+    #[test]
+    fn pbxproj_updates_every_configuration() {
+        let content = "\t\t\t\tMARKETING_VERSION = 1.0;\n\t\t\t\tMARKETING_VERSION = \"1.0\";\n";
+        assert_eq!(pbxproj_version(content), Some("1.0"));
+        let updated = update_pbxproj(content, "26.2.5").unwrap();
+        assert_eq!(
+            updated,
+            "\t\t\t\tMARKETING_VERSION = 26.2.5;\n\t\t\t\tMARKETING_VERSION = \"26.2.5\";\n"
+        );
+        assert!(update_pbxproj("nothing here", "26.2.5").is_err());
+
+        let content =
+            "\t\t\t\tINFOPLIST_MARKETING_VERSION = 1.0;\n\t\t\t\tMARKETING_VERSION = 1.0;\n";
+        assert_eq!(
+            update_pbxproj(content, "26.2.5").unwrap(),
+            "\t\t\t\tINFOPLIST_MARKETING_VERSION = 1.0;\n\t\t\t\tMARKETING_VERSION = 26.2.5;\n"
+        );
+    }
+}
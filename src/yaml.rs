@@ -0,0 +1,60 @@
+use std::ops::Range;
+
+/// Byte range of the value of a top-level `key: value` line, inside quotes
+/// if quoted and without a trailing comment. Editing just that range keeps
+/// comments, quoting and key order intact.
+pub fn top_level_value(content: &str, key: &str) -> Option<Range<usize>> {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        let Some(rest) = line.strip_prefix(key).and_then(|r| r.strip_prefix(':')) else {
+            continue;
+        };
+        let value = rest.trim_start_matches([' ', '\t']);
+        let value_start = start + (line.len() - value.len());
+
+        if let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') {
+            let len = value[1..].find(quote)?;
+            return Some(value_start + 1..value_start + 1 + len);
+        }
+
+        let end = value
+            .find(" #")
+            .or_else(|| value.find(['\r', '\n']))
+            .unwrap_or(value.len());
+        let len = value[..end].trim_end().len();
+        return Some(value_start..value_start + len);
+    }
+    None
+}
+
+/// Offset of the end of the line containing `pos`, before its newline.
+pub fn line_end(content: &str, pos: usize) -> usize {
+    content[pos..].find('\n').map_or(content.len(), |i| pos + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_level_value_forms() {
+        let content = "a: 1 # note\nb: \"2\"\nc: '3'\n  d: 4\n";
+        assert_eq!(
+            top_level_value(content, "a").map(|r| &content[r]),
+            Some("1")
+        );
+        assert_eq!(
+            top_level_value(content, "b").map(|r| &content[r]),
+            Some("2")
+        );
+        assert_eq!(
+            top_level_value(content, "c").map(|r| &content[r]),
+            Some("3")
+        );
+        assert_eq!(top_level_value(content, "d"), None);
+        assert_eq!(line_end(content, 0), 11);
+    }
+}
@@ -0,0 +1,21 @@
+//! End-to-end run of `oneup selftest`: the built binary against a local
+//! registry stub and temporary git repositories.
+//!
+//! cargo test --features selftest
+#![cfg(feature = "selftest")]
+
+use std::process::Command;
+
+#[test]
+fn selftest_passes() {
+    let output = Command::new(env!("CARGO_BIN_EXE_oneup"))
+        .arg("selftest")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("selftest passed"));
+}